@@ -1,4 +1,8 @@
 fn main() {
+    // The wasm32 reader never calls Swiss Ephemeris
+    if std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() == Ok("wasm32") {
+        return;
+    }
     println!("cargo:rustc-link-search=native=/usr/local/lib");  // Add library search path
     println!("cargo:rustc-link-search=native=/usr/lib");        // Add system library path
     println!("cargo:rustc-link-lib=static=swe");                // Try static linking first
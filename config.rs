@@ -39,9 +39,7 @@ pub struct MemoryConfig {
 pub struct KernelConfig {
     pub position_bits: u8,
     pub metadata_offset: u8,
-    pub eclipse_offset: u8,
     pub metadata_in_kernel: bool,
-    pub include_eclipses: bool,
     pub version: u8,
     pub flags: Vec<String>,
     pub compression: bool,
@@ -0,0 +1,93 @@
+/*
+ * Exercises the C ABI in include/medusa.h against a real kernel:
+ *
+ *     make -C examples/c
+ *     make -C examples/c KERNEL=zenith.kernel
+ *
+ * Exits non-zero on the first failed check.
+ */
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "medusa.h"
+
+static int failures = 0;
+
+#define CHECK(cond) do { \
+    if (!(cond)) { \
+        fprintf(stderr, "FAIL %s:%d: %s\n", __FILE__, __LINE__, #cond); \
+        failures++; \
+    } \
+} while (0)
+
+#define CHECK_STATUS(call, expected) do { \
+    MedusaStatus status_ = (call); \
+    if (status_ != (expected)) { \
+        fprintf(stderr, "FAIL %s:%d: %s returned %d (%s), expected %d\n", __FILE__, __LINE__, \
+                #call, status_, medusa_status_message(status_), (expected)); \
+        failures++; \
+    } \
+} while (0)
+
+int main(int argc, char **argv) {
+    const char *path = argc > 1 ? argv[1] : "zenith.kernel";
+    MedusaKernel *kernel = NULL;
+    MedusaPosition pos;
+    MedusaHouses houses;
+    double start, end;
+
+    CHECK(medusa_abi_version() == MEDUSA_ABI_VERSION);
+    CHECK(strcmp(medusa_status_message(MEDUSA_STATUS_OK), "ok") == 0);
+    CHECK(strcmp(medusa_status_message(999), "unknown status") == 0);
+
+    CHECK_STATUS(medusa_open("/nonexistent/zenith.kernel", &kernel), MEDUSA_STATUS_ERR_IO);
+    CHECK(kernel == NULL);
+    CHECK_STATUS(medusa_open(NULL, &kernel), MEDUSA_STATUS_ERR_NULL);
+
+    CHECK_STATUS(medusa_open(path, &kernel), MEDUSA_STATUS_OK);
+    if (kernel == NULL) {
+        fprintf(stderr, "cannot open %s\n", path);
+        return 1;
+    }
+    CHECK_STATUS(medusa_range(kernel, &start, &end), MEDUSA_STATUS_OK);
+    CHECK(end >= start);
+
+    /* The Sun (body 0) is in every geocentric kernel */
+    CHECK_STATUS(medusa_position_at(kernel, 0, start + (end - start) / 2, &pos), MEDUSA_STATUS_OK);
+    CHECK(pos.longitude >= 0.0 && pos.longitude < 360.0);
+    CHECK(pos.distance > 0.9 && pos.distance < 1.1);
+    CHECK(pos.speed_longitude > 0.9 && pos.speed_longitude < 1.1);
+    printf("Sun at JD %.1f: %.6f deg, %.6f AU\n", start + (end - start) / 2, pos.longitude, pos.distance);
+
+    /* Errors leave the output alone */
+    memset(&pos, 0, sizeof pos);
+    CHECK_STATUS(medusa_position_at(kernel, 0, end + 1000.0, &pos), MEDUSA_STATUS_ERR_OUT_OF_RANGE);
+    CHECK(pos.longitude == 0.0);
+    CHECK_STATUS(medusa_position_at(kernel, 9999, start, &pos), MEDUSA_STATUS_ERR_UNKNOWN_BODY);
+    CHECK_STATUS(medusa_position_at(kernel, 0, start, NULL), MEDUSA_STATUS_ERR_NULL);
+    CHECK_STATUS(medusa_position_at(NULL, 0, start, &pos), MEDUSA_STATUS_ERR_NULL);
+
+    /* Greenwich; the first cusp is the Ascendant, the tenth the MC */
+    CHECK_STATUS(medusa_houses_at(kernel, start, 51.4769, 0.0, 0.0, 'P', &houses), MEDUSA_STATUS_OK);
+    CHECK(houses.system == 'P');
+    CHECK(fabs(houses.cusps[0] - houses.ascendant) < 1e-9);
+    CHECK(fabs(houses.cusps[9] - houses.mc) < 1e-9);
+    printf("Greenwich at JD %.1f: Asc %.6f, MC %.6f\n", start, houses.ascendant, houses.mc);
+
+    /* Placidus falls back to Porphyry inside the polar circles */
+    CHECK_STATUS(medusa_houses_at(kernel, start, 78.2, 15.6, 0.0, 'P', &houses), MEDUSA_STATUS_OK);
+    CHECK(houses.system == 'O');
+    CHECK_STATUS(medusa_houses_at(kernel, start, 51.4769, 0.0, 0.0, 'Z', &houses), MEDUSA_STATUS_ERR_INVALID_ARGUMENT);
+
+    medusa_close(kernel);
+    medusa_close(NULL);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("All checks passed\n");
+    return 0;
+}
@@ -0,0 +1,25 @@
+// Writes the small kernel the C harness runs against by default:
+//
+//     cargo run --example c_fixture -- examples/c/fixture.kernel
+//
+// Thirty daily Sun records from JD 2451545.0 at a constant 1°/day, so no
+// Swiss Ephemeris run is needed. tests/c_abi.rs writes the same kernel.
+use medusa::kernel::{KernelWriter, Position};
+
+fn main() {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "fixture.kernel".to_string());
+    let mut writer = KernelWriter::new(2451545.0, 1.0, &[0]);
+    for day in 0..30 {
+        writer.push_record(&[Position {
+            longitude: 280.0 + day as f64,
+            distance: 0.98,
+            speed_longitude: 1.0,
+            ..Position::default()
+        }]);
+    }
+    if let Err(e) = writer.write(&path) {
+        eprintln!("Error: cannot write {}: {}", path, e);
+        std::process::exit(1);
+    }
+    println!("Wrote {}", path);
+}
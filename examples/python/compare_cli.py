@@ -0,0 +1,62 @@
+"""Checks the Python module against the CLIs, which share its reader.
+
+Build both first, then run from the directory holding zenith.kernel:
+
+    maturin develop --release
+    cargo build --release --bin ephemeris_table --bin events
+    python examples/python/compare_cli.py [path/to/target/release]
+"""
+import json
+import os
+import subprocess
+import sys
+
+import numpy as np
+
+import medusa
+
+BIN = sys.argv[1] if len(sys.argv) > 1 else os.path.join(os.path.dirname(__file__), "..", "..", "target", "release")
+
+
+def cli(name, *args):
+    out = subprocess.run([os.path.join(BIN, name), *args, "--format", "ndjson"],
+                         check=True, capture_output=True, text=True).stdout
+    return [json.loads(line) for line in out.splitlines() if line]
+
+
+kernel = medusa.Kernel("zenith.kernel")
+print(kernel)
+start = kernel.start_jd + 0.5
+end = min(start + 60.0, kernel.end_jd)
+
+# Positions: every body every 12 hours, exactly as ephemeris_table prints them
+rows = cli("ephemeris_table", str(start), str(end), "--step", "12h")
+jds = np.array(sorted({row["jd"] for row in rows}))
+positions = kernel.positions(jds)
+assert positions.shape == (len(jds), len(kernel.bodies), 6)
+for row in rows:
+    i = int(np.searchsorted(jds, row["jd"]))
+    j = kernel.bodies.index(row["body"])
+    lon, lat, dist, speed = positions[i, j, :4]
+    assert (lon, lat, dist, speed) == (row["longitude"], row["latitude"], row["distance"], row["speed"]), row
+print(f"positions: {len(rows)} rows match")
+
+# Events: same search, same records
+expected = cli("events", str(start), str(end))
+events = kernel.events(start, end)
+assert events == expected, (events[:3], expected[:3])
+print(f"events: {len(events)} match")
+
+# Houses of the Sun over a day at Greenwich
+houses = kernel.houses_of(np.linspace(start, start + 1.0, 25), 51.4769, 0.0, ["sun"])
+assert houses.shape == (25, 1) and houses.min() >= 1 and houses.max() <= 12
+chart = kernel.chart(start, 51.4769, 0.0)
+assert chart["houses"]["cusps"][0] == chart["houses"]["ascendant"]
+print("houses: ok")
+
+try:
+    kernel.positions([kernel.end_jd + 1000.0])
+except medusa.MedusaError as e:
+    print(f"out of range raises MedusaError: {e}")
+else:
+    raise AssertionError("expected MedusaError")
@@ -0,0 +1,147 @@
+/* Medusa kernel reader C ABI. Generated from src/ffi.rs by cbindgen; do not edit. */
+
+#ifndef MEDUSA_H
+#define MEDUSA_H
+
+#include <stdint.h>
+
+/**
+ * Bumped whenever a signature or struct layout below changes.
+ */
+#define MEDUSA_ABI_VERSION 1
+
+enum MedusaStatus
+#ifdef __cplusplus
+  : int32_t
+#endif // __cplusplus
+ {
+  MEDUSA_STATUS_OK = 0,
+  /**
+   * A required pointer was null.
+   */
+  MEDUSA_STATUS_ERR_NULL = 1,
+  /**
+   * Path is not UTF-8, or an unknown house system.
+   */
+  MEDUSA_STATUS_ERR_INVALID_ARGUMENT = 2,
+  MEDUSA_STATUS_ERR_IO = 3,
+  MEDUSA_STATUS_ERR_BAD_MAGIC = 4,
+  MEDUSA_STATUS_ERR_UNSUPPORTED_VERSION = 5,
+  MEDUSA_STATUS_ERR_TRUNCATED = 6,
+  MEDUSA_STATUS_ERR_OUT_OF_RANGE = 7,
+  MEDUSA_STATUS_ERR_UNKNOWN_BODY = 8,
+  MEDUSA_STATUS_ERR_BAD_SECTION = 9,
+  MEDUSA_STATUS_ERR_FRAME = 10,
+  /**
+   * Any other kernel error.
+   */
+  MEDUSA_STATUS_ERR_KERNEL = 11,
+  MEDUSA_STATUS_ERR_PANIC = 12,
+};
+#ifndef __cplusplus
+typedef int32_t MedusaStatus;
+#endif // __cplusplus
+
+/**
+ * Opaque handle from `medusa_open`. Safe to share between threads for
+ * reading; free it with `medusa_close`.
+ */
+typedef struct MedusaKernel MedusaKernel;
+
+/**
+ * Ecliptic position in the kernel's frame: degrees, AU, and per-day speeds.
+ */
+typedef struct MedusaPosition {
+  double longitude;
+  double latitude;
+  double distance;
+  double speed_longitude;
+  double speed_latitude;
+  double speed_distance;
+} MedusaPosition;
+
+/**
+ * Tropical houses. `system` is the letter actually used: Placidus and Koch
+ * fall back to Porphyry ('O') inside the polar circles.
+ */
+typedef struct MedusaHouses {
+  char system;
+  /**
+   * cusps[0] is the first house.
+   */
+  double cusps[12];
+  double ascendant;
+  double mc;
+  double armc;
+} MedusaHouses;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+uint32_t medusa_abi_version(void);
+
+/**
+ * Static, NUL-terminated description of a status code. Takes a plain int
+ * so a stray value from C is not undefined behaviour.
+ */
+const char *medusa_status_message(int32_t status);
+
+/**
+ * Maps the kernel at `path` and stores a handle in `*out`.
+ *
+ * # Safety
+ * `path` must be a NUL-terminated string and `out` a valid pointer.
+ */
+MedusaStatus medusa_open(const char *path, MedusaKernel **out);
+
+/**
+ * First and last JD the kernel covers.
+ *
+ * # Safety
+ * `kernel` must come from `medusa_open`; `start` and `end` must be valid
+ * pointers.
+ */
+MedusaStatus medusa_range(const MedusaKernel *kernel, double *start, double *end);
+
+/**
+ * Position of `body` (a Swiss Ephemeris body number) at `jd` (UT).
+ *
+ * # Safety
+ * `kernel` must come from `medusa_open` and `out` must be a valid pointer.
+ */
+MedusaStatus medusa_position_at(const MedusaKernel *kernel,
+                                int32_t body,
+                                double jd,
+                                MedusaPosition *out);
+
+/**
+ * Tropical houses at `jd` (UT) for a place in degrees (east and north
+ * positive) and metres. `system` is a house system letter: P, K, O, R, E
+ * or W. Like the CLIs' charts, houses come from the time alone, so `jd`
+ * need not be inside the kernel.
+ *
+ * # Safety
+ * `kernel` must come from `medusa_open` and `out` must be a valid pointer.
+ */
+MedusaStatus medusa_houses_at(const MedusaKernel *kernel,
+                              double jd,
+                              double latitude,
+                              double longitude,
+                              double altitude,
+                              char system,
+                              MedusaHouses *out);
+
+/**
+ * Unmaps the kernel. Null is ignored.
+ *
+ * # Safety
+ * `kernel` must come from `medusa_open` and not be used afterwards.
+ */
+void medusa_close(MedusaKernel *kernel);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MEDUSA_H */
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use crate::coords::{arc, declination, mean_obliquity};
+use crate::kernel::{Kernel, KernelError, Position};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AspectKind {
+    pub name: String,
+    pub symbol: String,
+    pub angle: f64,
+    pub orb: f64,
+    pub major: bool,
+}
+
+impl AspectKind {
+    pub fn new(name: &str, symbol: &str, angle: f64, orb: f64, major: bool) -> Self {
+        Self { name: name.to_string(), symbol: symbol.to_string(), angle, orb, major }
+    }
+}
+
+pub fn major_aspects() -> Vec<AspectKind> {
+    vec![
+        AspectKind::new("Conjunction", "☌", 0.0, 8.0, true),
+        AspectKind::new("Opposition", "☍", 180.0, 8.0, true),
+        AspectKind::new("Trine", "△", 120.0, 7.0, true),
+        AspectKind::new("Square", "□", 90.0, 7.0, true),
+        AspectKind::new("Sextile", "⚹", 60.0, 5.0, true),
+    ]
+}
+
+pub fn minor_aspects() -> Vec<AspectKind> {
+    vec![
+        AspectKind::new("Semisextile", "⚺", 30.0, 2.0, false),
+        AspectKind::new("Semisquare", "∠", 45.0, 2.0, false),
+        AspectKind::new("Sesquiquadrate", "⚼", 135.0, 2.0, false),
+        AspectKind::new("Quincunx", "⚻", 150.0, 3.0, false),
+        AspectKind::new("Quintile", "Q", 72.0, 2.0, false),
+        AspectKind::new("Biquintile", "bQ", 144.0, 2.0, false),
+    ]
+}
+
+/// Aspects of the nth harmonic: every multiple of 360°/n up to 180°.
+pub fn harmonic_aspects(n: u32, orb: f64) -> Vec<AspectKind> {
+    (1..=n / 2)
+        .map(|k| AspectKind::new(
+            &format!("H{} {}/{}", n, k, n),
+            &format!("H{}", n),
+            360.0 * k as f64 / n as f64,
+            orb,
+            false,
+        ))
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct AspectConfig {
+    pub aspects: Vec<AspectKind>,
+    /// Per-body orb factors; a pair uses the mean of its two factors
+    /// (1.0 when unset) to scale each aspect's orb.
+    pub body_orb_factors: HashMap<i32, f64>,
+    /// Orb for declination parallels; None disables them.
+    pub parallel_orb: Option<f64>,
+}
+
+impl Default for AspectConfig {
+    fn default() -> Self {
+        Self {
+            aspects: major_aspects(),
+            body_orb_factors: HashMap::new(),
+            parallel_orb: Some(1.0),
+        }
+    }
+}
+
+impl AspectConfig {
+    pub fn with_minor(mut self) -> Self {
+        self.aspects.extend(minor_aspects());
+        self
+    }
+
+    pub fn orb_for(&self, a: i32, b: i32, kind: &AspectKind) -> f64 {
+        let factor = |body| self.body_orb_factors.get(&body).copied().unwrap_or(1.0);
+        kind.orb * (factor(a) + factor(b)) / 2.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Aspect {
+    pub body_a: i32,
+    pub body_b: i32,
+    pub kind: AspectKind,
+    /// Distance from exact, degrees (always >= 0)
+    pub orb: f64,
+    pub applying: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParallelKind {
+    Parallel,
+    ContraParallel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Parallel {
+    pub body_a: i32,
+    pub body_b: i32,
+    pub kind: ParallelKind,
+    pub orb: f64,
+}
+
+/// Best-fitting aspect between two positions, if any is within orb.
+pub fn aspect_between(a: (i32, &Position), b: (i32, &Position), config: &AspectConfig) -> Option<Aspect> {
+    let delta = arc(a.1.longitude, b.1.longitude);
+    let separation = delta.abs();
+    // Rate of change of the separation, from the stored speeds. From 0°
+    // it can only grow, whichever body is faster.
+    let relative_speed = b.1.speed_longitude - a.1.speed_longitude;
+    let separation_rate = if delta == 0.0 { relative_speed.abs() } else { delta.signum() * relative_speed };
+
+    config.aspects.iter()
+        .filter_map(|kind| {
+            let deviation = separation - kind.angle;
+            (deviation.abs() <= config.orb_for(a.0, b.0, kind)).then(|| Aspect {
+                body_a: a.0,
+                body_b: b.0,
+                kind: kind.clone(),
+                orb: deviation.abs(),
+                // An exact aspect is perfecting, so it counts as applying
+                // while the bodies still move apart from each other
+                applying: deviation * separation_rate < 0.0 || (deviation == 0.0 && separation_rate != 0.0),
+            })
+        })
+        .min_by(|x, y| x.orb.total_cmp(&y.orb))
+}
+
+/// Aspects within one chart.
+pub fn find_aspects(positions: &[(i32, Position)], config: &AspectConfig) -> Vec<Aspect> {
+    let mut found = Vec::new();
+    for (i, (body_a, pos_a)) in positions.iter().enumerate() {
+        for (body_b, pos_b) in &positions[i + 1..] {
+            if let Some(aspect) = aspect_between((*body_a, pos_a), (*body_b, pos_b), config) {
+                found.push(aspect);
+            }
+        }
+    }
+    found
+}
+
+/// Aspects from every body of `a` to every body of `b` (transits to natal,
+/// synastry).
+pub fn find_aspects_between(a: &[(i32, Position)], b: &[(i32, Position)], config: &AspectConfig) -> Vec<Aspect> {
+    a.iter()
+        .flat_map(|(body_a, pos_a)| b.iter()
+            .filter_map(move |(body_b, pos_b)| aspect_between((*body_a, pos_a), (*body_b, pos_b), config)))
+        .collect()
+}
+
+pub fn find_parallels(positions: &[(i32, Position)], jd: f64, orb: f64) -> Vec<Parallel> {
+    let eps = mean_obliquity(jd);
+    let decl: Vec<(i32, f64)> = positions.iter()
+        .map(|(body, pos)| (*body, declination(pos.longitude, pos.latitude, eps)))
+        .collect();
+
+    let mut found = Vec::new();
+    for (i, (body_a, dec_a)) in decl.iter().enumerate() {
+        for (body_b, dec_b) in &decl[i + 1..] {
+            let parallel = (dec_a - dec_b).abs();
+            let contra = (dec_a + dec_b).abs();
+            if parallel <= orb {
+                found.push(Parallel { body_a: *body_a, body_b: *body_b, kind: ParallelKind::Parallel, orb: parallel });
+            } else if contra <= orb {
+                found.push(Parallel { body_a: *body_a, body_b: *body_b, kind: ParallelKind::ContraParallel, orb: contra });
+            }
+        }
+    }
+    found
+}
+
+impl Kernel {
+    pub fn aspects_at(&self, jd: f64, config: &AspectConfig) -> Result<Vec<Aspect>, KernelError> {
+        Ok(find_aspects(&self.positions_at(jd)?, config))
+    }
+
+    pub fn parallels_at(&self, jd: f64, config: &AspectConfig) -> Result<Vec<Parallel>, KernelError> {
+        match config.parallel_orb {
+            Some(orb) => Ok(find_parallels(&self.positions_at(jd)?, jd, orb)),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(longitude: f64, speed: f64) -> Position {
+        Position { longitude, distance: 1.0, speed_longitude: speed, ..Position::default() }
+    }
+
+    fn conjunction(a: &Position, b: &Position, config: &AspectConfig) -> Option<Aspect> {
+        aspect_between((0, a), (1, b), config).filter(|aspect| aspect.kind.angle == 0.0)
+    }
+
+    #[test]
+    fn tells_applying_from_separating() {
+        let config = AspectConfig::default();
+        // The faster body behind the slower one closes the gap
+        assert!(conjunction(&at(10.0, 1.0), &at(15.0, 0.5), &config).unwrap().applying);
+        assert!(!conjunction(&at(10.0, 0.5), &at(15.0, 1.0), &config).unwrap().applying);
+        // Same, across 0°
+        assert!(conjunction(&at(358.0, 1.0), &at(3.0, 0.5), &config).unwrap().applying);
+        assert!(!conjunction(&at(3.0, 1.0), &at(358.0, 0.5), &config).unwrap().applying);
+        // A retrograde body moving back onto a direct one
+        let square = aspect_between((0, &at(0.0, 1.0)), (1, &at(95.0, -0.5)), &config).unwrap();
+        assert_eq!((square.kind.angle, square.orb), (90.0, 5.0));
+        assert!(square.applying);
+    }
+
+    #[test]
+    fn exact_aspects_are_applying_while_the_bodies_move() {
+        let config = AspectConfig::default();
+        let exact = conjunction(&at(42.0, 1.0), &at(42.0, 0.5), &config).unwrap();
+        assert_eq!(exact.orb, 0.0);
+        assert!(exact.applying);
+        assert!(conjunction(&at(42.0, 0.5), &at(42.0, 1.0), &config).unwrap().applying);
+        assert!(!conjunction(&at(42.0, 0.5), &at(42.0, 0.5), &config).unwrap().applying);
+
+        let trine = aspect_between((0, &at(10.0, 1.0)), (1, &at(130.0, 0.1)), &config).unwrap();
+        assert_eq!((trine.kind.angle, trine.orb, trine.applying), (120.0, 0.0, true));
+    }
+
+    #[test]
+    fn scales_orbs_by_body_factors() {
+        let (a, b) = (at(0.0, 1.0), at(10.0, 1.0));
+        let mut config = AspectConfig::default();
+        assert!(conjunction(&a, &b, &config).is_none());
+
+        // The pair uses the mean factor: (2.0 + 1.0) / 2 × 8° = 12°
+        config.body_orb_factors.insert(1, 2.0);
+        let conj = &major_aspects()[0];
+        assert_eq!(config.orb_for(0, 1, conj), 12.0);
+        assert_eq!(conjunction(&a, &b, &config).unwrap().orb, 10.0);
+
+        config.body_orb_factors.insert(0, 0.5);
+        assert_eq!(config.orb_for(0, 1, conj), 10.0);
+        assert!(conjunction(&a, &b, &config).is_some());
+        config.body_orb_factors.insert(0, 0.25);
+        assert!(conjunction(&a, &b, &config).is_none());
+    }
+
+    #[test]
+    fn picks_the_closest_aspect() {
+        // 63°: within the 5° sextile orb and nothing else
+        let config = AspectConfig::default().with_minor();
+        let aspect = aspect_between((0, &at(0.0, 1.0)), (1, &at(63.0, 1.0)), &config).unwrap();
+        assert_eq!(aspect.kind.name, "Sextile");
+        // 73°: the quintile at 1° beats the sextile at 13°
+        let aspect = aspect_between((0, &at(0.0, 1.0)), (1, &at(73.0, 1.0)), &config).unwrap();
+        assert_eq!(aspect.kind.name, "Quintile");
+    }
+
+    #[test]
+    fn finds_parallels_and_contraparallels() {
+        // Declination is ±obliquity at 90° and 270°, and 0 at 0° and 180°
+        let positions = [(0, at(90.0, 1.0)), (1, at(270.0, 1.0)), (2, at(90.5, 1.0)), (3, at(0.0, 1.0))];
+        let found = find_parallels(&positions, 2451545.0, 1.0);
+        let kinds: Vec<(i32, i32, ParallelKind)> = found.iter().map(|p| (p.body_a, p.body_b, p.kind)).collect();
+        assert_eq!(kinds, [
+            (0, 1, ParallelKind::ContraParallel),
+            (0, 2, ParallelKind::Parallel),
+            (1, 2, ParallelKind::ContraParallel),
+        ]);
+        assert!(found.iter().all(|p| p.orb <= 1.0));
+        assert!(find_parallels(&positions, 2451545.0, 0.0).iter().all(|p| p.kind == ParallelKind::ContraParallel && p.orb < 1e-9));
+    }
+}
@@ -0,0 +1,60 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use crate::coords::GeoLocation;
+use crate::time::parse_jd_or_datetime;
+
+/// One line of batch input: a JD or UTC date/time, optionally followed by
+/// whitespace and `lat,lon[,alt]`. Blank lines and `#` comments are skipped.
+///
+/// ```text
+/// 2451545.0
+/// 2024-04-08 18:00:00 39.7294,-104.8319
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchQuery {
+    /// 1-based line number, for matching results and errors to the input.
+    pub line: usize,
+    pub jd: f64,
+    pub location: Option<GeoLocation>,
+}
+
+impl BatchQuery {
+    pub fn parse(line: usize, text: &str) -> Result<Option<Self>, String> {
+        let text = text.split('#').next().unwrap_or("").trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        // Date/times may contain a space but never a comma, so a trailing
+        // field with a comma is the location.
+        let (time, location) = match text.rsplit_once(char::is_whitespace) {
+            Some((time, location)) if location.contains(',') => (time.trim_end(), Some(location)),
+            _ => (text, None),
+        };
+        let jd = parse_jd_or_datetime(time).map_err(|e| format!("line {}: {}", line, e))?;
+        let location = location
+            .map(GeoLocation::parse)
+            .transpose()
+            .map_err(|e| format!("line {}: {}", line, e))?;
+        Ok(Some(Self { line, jd, location }))
+    }
+}
+
+/// `-` for stdin, otherwise a file path.
+pub fn open_input(path: &str) -> io::Result<Box<dyn BufRead>> {
+    if path == "-" {
+        Ok(Box::new(BufReader::new(io::stdin())))
+    } else {
+        Ok(Box::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+/// Queries from `input` one line at a time, so arbitrarily long inputs run
+/// in constant memory.
+pub fn read_queries<R: BufRead>(input: R) -> impl Iterator<Item = Result<BatchQuery, String>> {
+    input.lines()
+        .enumerate()
+        .filter_map(|(i, line)| match line {
+            Ok(text) => BatchQuery::parse(i + 1, &text).transpose(),
+            Err(e) => Some(Err(format!("line {}: {}", i + 1, e))),
+        })
+}
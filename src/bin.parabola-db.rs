@@ -1,7 +1,10 @@
-use std::fs::File;
-use std::io::Read;
 use swisseph_sys::*;
 use medusa::SE_AST_OFFSET;
+use medusa::cli::take_value;
+use medusa::coords::arc;
+use medusa::json::Json;
+use medusa::kernel::{Kernel, Position};
+use medusa::output::{position_record, print_records, Format};
 use chrono::{DateTime, TimeZone, Utc};
 
 const BODIES: [&str; 18] = [
@@ -49,7 +52,12 @@ fn jd_to_datetime(jd: f64) -> DateTime<Utc> {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args: Vec<String> = std::env::args().collect();
+    // --format json|csv|ndjson prints the Swiss Ephemeris positions as records
+    let format = match take_value(&mut args, "--format")? {
+        Some(format) => Format::parse(&format)?,
+        None => Format::Table,
+    };
     
     let search_jd = if args.len() == 2 {
         // Direct JD search
@@ -72,6 +80,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("Usage:");
         println!("  {} <julian_date>            - Search by Julian Date", args[0]);
         println!("  {} YYYY MM DD HH MM SS      - Search by calendar date/time", args[0]);
+        println!("  --format table|json|csv|ndjson");
         println!("\nExamples:");
         println!("  {} 2451545.0                - Search JD directly", args[0]);
         println!("  {} 2024 2 4 15 30 45        - Feb 4, 2024 at 15:30:45 UTC", args[0]);
@@ -79,13 +88,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let date_time = jd_to_datetime(search_jd);
-    println!("
+    if format == Format::Table {
+        println!("
 ╭──────────────────────────────────────────────╮
 │            PARABOLA EPHEMERIS                │
 ╰──────────────────────────────────────────────╯");
 
-    println!("\n🔍 Time: {} UTC", date_time.format("%Y-%m-%d %H:%M:%S"));
-    println!("   JD:   {:.6}", search_jd);
+        println!("\n🔍 Time: {} UTC", date_time.format("%Y-%m-%d %H:%M:%S"));
+        println!("   JD:   {:.6}", search_jd);
+    }
 
     // Configure Swiss Ephemeris for validation
     unsafe {
@@ -93,29 +104,46 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         swe_set_jpl_file(std::ffi::CString::new("de441.eph")?.as_ptr());
     }
 
-    // Read kernel
-    let mut file = File::open("zenith.kernel")?;
-    let mut timestamp_bytes = [0u8; 8];
-    file.read_exact(&mut timestamp_bytes)?;
-    let timestamp = f64::from_le_bytes(timestamp_bytes);
-
-    let mut base_positions = Vec::with_capacity(18);
-    for _ in 0..18 {
-        let mut pos_bytes = [0u8; 8];
-        file.read_exact(&mut pos_bytes)?;
-        base_positions.push(f64::from_le_bytes(pos_bytes));
-    }
+    let kernel = Kernel::open("zenith.kernel")?;
 
-    // Calculate current positions
+    // Calculate current positions. Same order as BODIES: SE_ASC and SE_ARMC
+    // are house points, not planets, and swe_calc_ut reads them as the Sun
+    // and Moon, so they are left out.
     let mut xx = [0.0; 6];
     let mut serr = [0i8; 256];
     let bodies = [
         SE_SUN, SE_MOON, SE_MERCURY, SE_VENUS, SE_MARS, SE_JUPITER, 
         SE_SATURN, SE_URANUS, SE_NEPTUNE, SE_PLUTO, SE_CHIRON, 
         SE_TRUE_NODE, SE_MEAN_APOG, SE_VESTA, SE_JUNO, SE_CERES,
-        SE_PALLAS, SE_ASC, SE_ARMC, (SE_AST_OFFSET + 5550)
+        SE_PALLAS, (SE_AST_OFFSET + 5550)
     ];
 
+    // Kernel longitude to compare against; None where the kernel does not
+    // cover the time or carry the body
+    let kernel_longitude = |body: u32| kernel.position_at(body as i32, search_jd).ok().map(|p| p.longitude);
+
+    if format != Format::Table {
+        let mut records = Vec::new();
+        for &body in bodies.iter() {
+            let ret = unsafe {
+                swe_calc_ut(search_jd, body as i32, (SEFLG_SPEED | SEFLG_SWIEPH) as i32,
+                            xx.as_mut_ptr(), serr.as_mut_ptr())
+            };
+            if ret >= 0 {
+                let pos = Position::from_xx(&xx);
+                let mut record = position_record(search_jd, body as i32, &pos, None);
+                let delta = match kernel_longitude(body) {
+                    Some(longitude) => arc(longitude, pos.longitude).abs().into(),
+                    None => Json::Null,
+                };
+                record.push("kernel_delta", delta);
+                records.push(record);
+            }
+        }
+        print_records(format, &records)?;
+        return Ok(());
+    }
+
     println!("\n╭────────┬─────────────────┬────────────┬───────────┬─────────╮");
     println!("│ Body   │    Position     │   Speed    │  Status   │  Δ SwE  │");
     println!("├────────┼─────────────────┼────────────┼───────────┼─────────┤");
@@ -126,7 +154,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         (7..10, "Outer Planets"),
         (10..13, "Nodes & Points"),
         (13..17, "Asteroids"),
-        (17..18, "Angles & Special Points")
+        (17..18, "Minor Bodies")
     ];
 
     for &(ref range, title) in groups.iter() {
@@ -141,10 +169,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 );
 
                 if ret >= 0 {
-                    let kernel_pos = base_positions[i];
                     let swe_pos = xx[0].rem_euclid(360.0);
                     let speed = xx[3];
-                    let diff = (kernel_pos - swe_pos).abs();
+                    let diff = kernel_longitude(bodies[i]).map(|kernel_pos| arc(kernel_pos, swe_pos).abs());
                     
                     print!("│ {:<4} {} │ {} │ {} │", 
                         SYMBOLS[i],
@@ -163,10 +190,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     }
 
                     // Difference indicator
-                    if diff > 0.0001 {
-                        println!(" {:6.3}° │", diff);
-                    } else {
-                        println!("   OK   │");
+                    match diff {
+                        Some(diff) if diff > 0.0001 => println!(" {:6.3}° │", diff),
+                        Some(_) => println!("   OK   │"),
+                        None => println!("   --   │"),
                     }
                 }
             }
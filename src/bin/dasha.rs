@@ -0,0 +1,103 @@
+use medusa::cli::flag_value;
+use medusa::dasha::{self, DashaOptions, DashaPeriod, DashaSystem, YearLength, MAX_LEVELS};
+use medusa::json::{datetime_json, Json};
+use medusa::kernel::Kernel;
+use medusa::output::{print_records, Format};
+use medusa::sidereal::Ayanamsa;
+use medusa::time::{jd_to_datetime, parse_jd_or_datetime};
+use medusa::SE_SIDM_LAHIRI;
+
+fn print_period(period: &DashaPeriod, system: DashaSystem) {
+    let indent = "   ".repeat(period.level as usize - 1);
+    let label = match (system, dasha::yogini_name(period.lord)) {
+        (DashaSystem::Yogini, Some(yogini)) => format!("{} ({})", yogini, period.lord.name()),
+        _ => period.lord.name().to_string(),
+    };
+    println!("{}{:<20} {} → {}",
+        indent,
+        label,
+        jd_to_datetime(period.start_jd).format("%Y-%m-%d"),
+        jd_to_datetime(period.end_jd).format("%Y-%m-%d")
+    );
+    for sub in &period.sub_periods {
+        print_period(sub, system);
+    }
+}
+
+// Depth-first, so each period is followed by its sub-periods as in the table.
+fn period_records(period: &DashaPeriod, system: DashaSystem, records: &mut Vec<Json>) {
+    records.push(Json::object([
+        ("level", (period.level as u32).into()),
+        ("lord", period.lord.name().into()),
+        ("yogini", dasha::yogini_name(period.lord).filter(|_| system == DashaSystem::Yogini).into()),
+        ("start_jd", period.start_jd.into()),
+        ("start", datetime_json(Some(period.start_jd))),
+        ("end_jd", period.end_jd.into()),
+        ("end", datetime_json(Some(period.end_jd))),
+    ]));
+    for sub in &period.sub_periods {
+        period_records(sub, system, records);
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    let Some(birth) = args.get(1) else {
+        println!("Usage:");
+        println!("  {} <birth jd | YYYY-MM-DDTHH:MM:SS> [options]", args[0]);
+        println!("\nOptions:");
+        println!("  -s <ayanamsa>        Sidereal mode (default Lahiri)");
+        println!("  --system <name>      vimshottari (default), yogini, ashtottari");
+        println!("  --year <days>        Year length, 365.25 (default) or 360");
+        println!("  --levels <1-3>       Maha / antar / pratyantar depth (default 2)");
+        println!("  --format <format>    table (default), json, csv, ndjson");
+        return Ok(());
+    };
+    let birth_jd = parse_jd_or_datetime(birth)?;
+
+    let mode = match flag_value(&args, "-s")? {
+        Some(s) => Ayanamsa::parse(s)?,
+        None => Ayanamsa::Predefined(SE_SIDM_LAHIRI as i32),
+    };
+    let system = match flag_value(&args, "--system")?.unwrap_or("vimshottari") {
+        "vimshottari" => DashaSystem::Vimshottari,
+        "yogini" => DashaSystem::Yogini,
+        "ashtottari" => DashaSystem::Ashtottari,
+        other => return Err(format!("unknown dasha system {}", other).into()),
+    };
+    let year_length = match flag_value(&args, "--year")? {
+        None | Some("365.25") => YearLength::Julian,
+        Some("360") => YearLength::Savana,
+        Some(days) => YearLength::Days(days.parse()?),
+    };
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+
+    let mut options = DashaOptions { system, year_length, ..DashaOptions::default() };
+    if let Some(levels) = flag_value(&args, "--levels")? {
+        options.levels = levels.parse().ok()
+            .filter(|levels| (1..=MAX_LEVELS).contains(levels))
+            .ok_or(format!("--levels must be 1 to {}, got {}", MAX_LEVELS, levels))?;
+    }
+    let kernel = Kernel::open("zenith.kernel")?;
+    let periods = kernel.dasha_timeline(birth_jd, mode, &options)?;
+    if format != Format::Table {
+        let mut records = Vec::new();
+        for period in &periods {
+            period_records(period, system, &mut records);
+        }
+        print_records(format, &records)?;
+        return Ok(());
+    }
+
+    println!("\n🪐 {} dasha from {} UTC ({})",
+        system.name(),
+        jd_to_datetime(birth_jd).format("%Y-%m-%d %H:%M:%S"),
+        mode.name()
+    );
+    println!("   Year: {} days\n", year_length.days());
+    for period in &periods {
+        print_period(period, system);
+    }
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use medusa::cli::flag_value;
+use medusa::coords::GeoLocation;
+use medusa::eclipse::{self, EclipseKind};
+use medusa::ephemeris::{self, EphemerisSource};
+use medusa::json::{datetime_json, time_json, Json};
+use medusa::kernel::Kernel;
+use medusa::output::{print_records, Format};
+use medusa::time::{jd_to_datetime, parse_jd_or_datetime};
+
+fn format_time(jd: Option<f64>) -> String {
+    match jd {
+        Some(jd) => jd_to_datetime(jd).format("%H:%M:%S").to_string(),
+        None => "   —    ".to_string(),
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 3 {
+        println!("Usage:");
+        println!("  {} <from> <to> [--solar|--lunar] [--local lat,lon[,alt]] [--format table|json|csv|ndjson]", args[0]);
+        println!("\nLists the eclipses stored in zenith.kernel (generate it with --eclipses).");
+        println!("--local computes local circumstances of solar eclipses with the live ephemeris.");
+        return Ok(());
+    }
+
+    let from = parse_jd_or_datetime(&args[1])?;
+    let to = parse_jd_or_datetime(&args[2])?;
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+    let kind = if args.iter().any(|a| a == "--solar") {
+        Some(EclipseKind::Solar)
+    } else if args.iter().any(|a| a == "--lunar") {
+        Some(EclipseKind::Lunar)
+    } else {
+        None
+    };
+
+    if let Some(location) = flag_value(&args, "--local")? {
+        let location = GeoLocation::parse(location)?;
+        ephemeris::init(EphemerisSource::Swiss);
+        if format == Format::Table {
+            println!("\n🌒 Solar eclipses visible from {:.4}, {:.4}\n", location.latitude, location.longitude);
+        }
+        let mut jd = from;
+        let mut records = Vec::new();
+        loop {
+            let local = eclipse::next_local_solar_eclipse(jd, location, EphemerisSource::Swiss)?;
+            if local.maximum_jd > to {
+                break;
+            }
+            jd = local.maximum_jd + 1.0;
+            if format != Format::Table {
+                let mut fields = time_json(local.maximum_jd);
+                fields.extend([
+                    ("type", local.eclipse_type.name().into()),
+                    ("magnitude", local.magnitude.into()),
+                    ("obscuration", local.obscuration.into()),
+                    ("sun_altitude", local.sun_altitude.into()),
+                    ("first_contact", datetime_json(local.first_contact)),
+                    ("second_contact", datetime_json(local.second_contact)),
+                    ("third_contact", datetime_json(local.third_contact)),
+                    ("fourth_contact", datetime_json(local.fourth_contact)),
+                    ("saros_series", local.saros_series.into()),
+                    ("saros_member", local.saros_member.into()),
+                ]);
+                records.push(Json::object(fields));
+                continue;
+            }
+            println!("  {} UTC  {:<9} mag {:.3}  obsc {:.3}  alt {:>5.1}°  C1 {}  C2 {}  C3 {}  C4 {}  saros {}/{}",
+                jd_to_datetime(local.maximum_jd).format("%Y-%m-%d %H:%M:%S"),
+                local.eclipse_type.name(),
+                local.magnitude,
+                local.obscuration,
+                local.sun_altitude,
+                format_time(local.first_contact),
+                format_time(local.second_contact),
+                format_time(local.third_contact),
+                format_time(local.fourth_contact),
+                local.saros_series,
+                local.saros_member
+            );
+        }
+        if format != Format::Table {
+            print_records(format, &records)?;
+        }
+        return Ok(());
+    }
+
+    let kernel = Kernel::open("zenith.kernel")?;
+    let eclipses = kernel.eclipses(from, to, kind)?;
+    if format != Format::Table {
+        let records: Vec<Json> = eclipses.iter()
+            .map(|e| {
+                let mut fields = time_json(e.maximum_jd);
+                fields.extend([
+                    ("kind", match e.kind { EclipseKind::Solar => "solar", EclipseKind::Lunar => "lunar" }.into()),
+                    ("type", e.eclipse_type.name().into()),
+                    ("magnitude", e.magnitude.into()),
+                    ("contacts", Json::object([
+                        ("begin", datetime_json(e.contacts.begin)),
+                        ("end", datetime_json(e.contacts.end)),
+                        ("partial_begin", datetime_json(e.contacts.partial_begin)),
+                        ("partial_end", datetime_json(e.contacts.partial_end)),
+                        ("total_begin", datetime_json(e.contacts.total_begin)),
+                        ("total_end", datetime_json(e.contacts.total_end)),
+                    ])),
+                    ("saros_series", e.saros_series.into()),
+                    ("saros_member", e.saros_member.into()),
+                ]);
+                Json::object(fields)
+            })
+            .collect();
+        print_records(format, &records)?;
+        return Ok(());
+    }
+    println!("\n🌒 {} eclipses\n", eclipses.len());
+    for e in &eclipses {
+        println!("  {} UTC  {} {:<9} mag {:.4}  {} → {}  total {} → {}  saros {}/{}",
+            jd_to_datetime(e.maximum_jd).format("%Y-%m-%d %H:%M:%S"),
+            match e.kind { EclipseKind::Solar => "☉", EclipseKind::Lunar => "☽" },
+            e.eclipse_type.name(),
+            e.magnitude,
+            format_time(e.contacts.begin),
+            format_time(e.contacts.end),
+            format_time(e.contacts.total_begin),
+            format_time(e.contacts.total_end),
+            e.saros_series,
+            e.saros_member
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use std::io::{self, BufWriter, Write};
+use chrono::Datelike;
+use medusa::bodies::{body_from_name, body_symbol, SIGNS};
+use medusa::cli::flag_value;
+use medusa::coords::sidereal_time;
+use medusa::ephemeris_table::{sample_times, EphemerisRow};
+use medusa::kernel::Kernel;
+use medusa::output::{position_record, Format, RecordWriter};
+use medusa::sidereal::{Ayanamsa, Zodiac};
+use medusa::time::{jd_to_datetime, parse_jd_or_datetime, parse_step};
+
+const MONTHS: [&str; 12] = [
+    "JANUARY", "FEBRUARY", "MARCH", "APRIL", "MAY", "JUNE",
+    "JULY", "AUGUST", "SEPTEMBER", "OCTOBER", "NOVEMBER", "DECEMBER",
+];
+const CELL_WIDTH: usize = 9;
+
+fn pad(s: &str, width: usize) -> String {
+    format!("{}{}", s, " ".repeat(width.saturating_sub(s.chars().count())))
+}
+
+// "12♑34'" with the sign glyph only where `show_sign` asks for it, as in
+// printed ephemerides, and ℞ while retrograde.
+fn cell(longitude: f64, speed: f64, show_sign: bool) -> String {
+    let minutes = (longitude * 60.0).round() as i64 % 21600;
+    let sign = SIGNS[(minutes / 1800) as usize];
+    format!("{:>2}{}{:02}'{}",
+        minutes % 1800 / 60,
+        if show_sign { sign } else { " " },
+        minutes % 60,
+        if speed < 0.0 { "℞" } else { "" }
+    )
+}
+
+fn sign_of(longitude: f64) -> i64 {
+    (longitude * 60.0).round() as i64 % 21600 / 1800
+}
+
+struct MonthlyPages<W: Write> {
+    out: W,
+    bodies: Vec<i32>,
+    zodiac: Zodiac,
+    daily: bool,
+    month: Option<(i32, u32)>,
+    previous_signs: Vec<i64>,
+}
+
+impl<W: Write> MonthlyPages<W> {
+    fn header(&mut self, row: &EphemerisRow) -> io::Result<()> {
+        let dt = jd_to_datetime(row.jd);
+        if self.month.is_some() {
+            // Form feed, so each month prints on its own page
+            write!(self.out, "\x0c")?;
+        }
+        let time = if self.daily { format!("{} UT", dt.format("%H:%M")) } else { String::new() };
+        writeln!(self.out, "\n{:<24}{} {}{:>24}  {}\n", "", MONTHS[dt.month0() as usize], dt.year(), time, self.zodiac.name())?;
+        let columns: String = self.bodies.iter().map(|&b| pad(body_symbol(b), CELL_WIDTH)).collect();
+        writeln!(self.out, "{:<8} {:<9} {}", "Day", "Sid.t", columns.trim_end())?;
+        Ok(())
+    }
+
+    fn row(&mut self, row: &EphemerisRow) -> io::Result<()> {
+        let dt = jd_to_datetime(row.jd);
+        let month = (dt.year(), dt.month());
+        let new_page = self.month != Some(month);
+        if new_page {
+            self.header(row)?;
+            self.month = Some(month);
+        }
+
+        let label = if self.daily {
+            format!("{} {:>2}", &dt.format("%a").to_string()[..2], dt.day())
+        } else {
+            format!("{:>2} {}", dt.day(), dt.format("%H:%M"))
+        };
+        let st = sidereal_time(row.jd) / 15.0;
+        let seconds = (st * 3600.0).round() as i64 % 86400;
+        let st = format!("{:02}:{:02}:{:02}", seconds / 3600, seconds % 3600 / 60, seconds % 60);
+
+        let mut cells = String::new();
+        for (i, (_, pos)) in row.positions.iter().enumerate() {
+            let sign = sign_of(pos.longitude);
+            let show_sign = new_page || self.previous_signs.get(i) != Some(&sign);
+            cells.push_str(&pad(&cell(pos.longitude, pos.speed_longitude, show_sign), CELL_WIDTH));
+            if i < self.previous_signs.len() {
+                self.previous_signs[i] = sign;
+            } else {
+                self.previous_signs.push(sign);
+            }
+        }
+        writeln!(self.out, "{:<8} {:<9} {}", label, st, cells.trim_end())
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 3 {
+        println!("Usage:");
+        println!("  {} <from> <to> [options]", args[0]);
+        println!("\nOptions:");
+        println!("  --step <step>          1d (default), 12h, 1h, 30m, or days");
+        println!("  --body <name>,...      Bodies (default: all in the kernel)");
+        println!("  -s <ayanamsa>          Sidereal zodiac");
+        println!("  --format <format>      table (monthly pages, default), json, csv, ndjson");
+        println!("\nExample:");
+        println!("  {} 2024-01-01T12:00 2024-12-31T12:00 --format csv", args[0]);
+        return Ok(());
+    }
+
+    let from = parse_jd_or_datetime(&args[1])?;
+    let to = parse_jd_or_datetime(&args[2])?;
+    if to < from {
+        return Err("<to> must not be before <from>".into());
+    }
+    let step = parse_step(flag_value(&args, "--step")?.unwrap_or("1d"))?;
+    let zodiac = match flag_value(&args, "-s")? {
+        Some(mode) => Zodiac::Sidereal(Ayanamsa::parse(mode)?),
+        None => Zodiac::Tropical,
+    };
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+
+    let kernel = Kernel::open("zenith.kernel")?;
+    let bodies = match flag_value(&args, "--body")? {
+        Some(list) => list.split(',')
+            .map(|name| body_from_name(name).ok_or(format!("unknown body {}", name)))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => kernel.bodies().to_vec(),
+    };
+
+    // Fail before printing anything rather than partway down the table
+    kernel.check_range(from)?;
+    if let Some(last) = sample_times(from, to, step).last() {
+        kernel.check_range(last)?;
+    }
+
+    let stdout = io::stdout();
+    let out = BufWriter::new(stdout.lock());
+    let rows = kernel.ephemeris_rows(&bodies, from, to, step, zodiac);
+
+    if format == Format::Table {
+        // Whole-day steps label rows by weekday and date; finer ones by time
+        let daily = (step - step.round()).abs() < 1e-9;
+        let mut pages = MonthlyPages { out, bodies: bodies.clone(), zodiac, daily, month: None, previous_signs: Vec::new() };
+        for row in rows {
+            pages.row(&row?)?;
+        }
+        pages.out.flush()?;
+        return Ok(());
+    }
+
+    let mut writer = RecordWriter::new(out, format);
+    for row in rows {
+        let row = row?;
+        let records: Vec<_> = row.positions.iter()
+            .map(|(body, pos)| position_record(row.jd, *body, pos, None))
+            .collect();
+        writer.write(&records)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use medusa::bodies::{body_from_name, body_name, body_symbol, format_position};
+use medusa::cli::flag_value;
+use medusa::events::{Boundary, EventKind, EventOptions};
+use medusa::json::event_json;
+use medusa::kernel::Kernel;
+use medusa::output::{print_records, Format};
+use medusa::sidereal::{Ayanamsa, Zodiac};
+use medusa::time::{jd_to_datetime, parse_jd_or_datetime, parse_step};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 3 {
+        println!("Usage:");
+        println!("  {} <from> <to> [options]", args[0]);
+        println!("\nOptions:");
+        println!("  --body <name>,...          Bodies (default: all in the kernel)");
+        println!("  --ingress <kind>,...       sign (default), nakshatra, or a degree step like 1");
+        println!("  --no-ingress               Stations only");
+        println!("  --no-stations              Ingresses only");
+        println!("  -s <ayanamsa>              Sidereal zodiac");
+        println!("  --step <step>              Scan step: days, or 6h, 30m (default 0.25)");
+        println!("  --format <format>          table (default), json, csv, ndjson");
+        println!("\nExample:");
+        println!("  {} 2024-01-01 2025-01-01 --body mercury,venus,mars", args[0]);
+        return Ok(());
+    }
+
+    let from = parse_jd_or_datetime(&args[1])?;
+    let to = parse_jd_or_datetime(&args[2])?;
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+    let kernel = Kernel::open("zenith.kernel")?;
+
+    let bodies = match flag_value(&args, "--body")? {
+        Some(list) => list.split(',')
+            .map(|name| body_from_name(name).ok_or(format!("unknown body {}", name)))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => kernel.bodies().to_vec(),
+    };
+
+    let mut options = EventOptions::default();
+    if let Some(list) = flag_value(&args, "--ingress")? {
+        options.boundaries = list.split(',').map(Boundary::parse).collect::<Result<_, _>>()?;
+    }
+    if args.iter().any(|a| a == "--no-ingress") {
+        options.boundaries.clear();
+    }
+    options.stations = !args.iter().any(|a| a == "--no-stations");
+    if let Some(mode) = flag_value(&args, "-s")? {
+        options.zodiac = Zodiac::Sidereal(Ayanamsa::parse(mode)?);
+    }
+    if let Some(step) = flag_value(&args, "--step")? {
+        options.search.scan_step = parse_step(step)?;
+    }
+
+    let events = kernel.find_events(&bodies, from, to, &options)?;
+    if format != Format::Table {
+        print_records(format, &events.iter().map(event_json).collect::<Vec<_>>())?;
+        return Ok(());
+    }
+
+    println!("\n📅 {} events, {} zodiac\n", events.len(), options.zodiac.name());
+    for event in &events {
+        let description = match event.kind {
+            EventKind::Ingress { boundary, index, retrograde } => format!("enters {}{}",
+                boundary.label(index),
+                if retrograde { " ℞" } else { "" }
+            ),
+            EventKind::Station { retrograde: true } => "stations retrograde".to_string(),
+            EventKind::Station { retrograde: false } => "stations direct".to_string(),
+        };
+        println!("  {} UTC  {} {:<10} {:<28} {}",
+            jd_to_datetime(event.jd).format("%Y-%m-%d %H:%M:%S"),
+            body_symbol(event.body),
+            body_name(event.body),
+            description,
+            format_position(event.longitude)
+        );
+    }
+    Ok(())
+}
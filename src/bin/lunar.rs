@@ -0,0 +1,156 @@
+use medusa::bodies::{body_name, body_symbol, format_position, NEPTUNE, PLUTO, SIGN_NAMES, URANUS};
+use medusa::cli::flag_value;
+use medusa::json::{datetime_json, sign_json, time_json, Json};
+use medusa::kernel::Kernel;
+use medusa::lunar::VoidOptions;
+use medusa::output::{print_records, Format};
+use medusa::search::{SearchOptions, Target};
+use medusa::sidereal::{Ayanamsa, Zodiac};
+use medusa::time::{jd_to_datetime, parse_jd_or_datetime};
+
+fn format_jd(jd: Option<f64>) -> String {
+    match jd {
+        Some(jd) => jd_to_datetime(jd).format("%Y-%m-%d %H:%M").to_string(),
+        None => "—".to_string(),
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 4 {
+        println!("Usage:");
+        println!("  {} <phases|void|lunations> <from> <to> [options]", args[0]);
+        println!("\nOptions:");
+        println!("  --aspect <angle>,...   Aspects that end a void (default 0,60,90,120,180)");
+        println!("  --outer                Let Uranus, Neptune and Pluto end a void too");
+        println!("  -s <ayanamsa>          Sidereal signs for void of course");
+        println!("  --format <format>      table (default), json, csv, ndjson");
+        println!("\nExample:");
+        println!("  {} void 2024-01-01 2024-02-01", args[0]);
+        return Ok(());
+    }
+
+    let from = parse_jd_or_datetime(&args[2])?;
+    let to = parse_jd_or_datetime(&args[3])?;
+    let kernel = Kernel::open("zenith.kernel")?;
+    let search = SearchOptions::default();
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+
+    match args[1].as_str() {
+        "phases" => {
+            let phases = kernel.find_lunar_phases(from, to, &search)?;
+            if format != Format::Table {
+                let records: Vec<Json> = phases.iter()
+                    .map(|event| {
+                        let mut fields = time_json(event.jd);
+                        fields.extend([("phase", event.phase.name().into()), ("longitude", event.moon_longitude.into())]);
+                        fields.extend(sign_json(event.moon_longitude));
+                        Json::object(fields)
+                    })
+                    .collect();
+                print_records(format, &records)?;
+                return Ok(());
+            }
+            println!("\n🌙 {} lunar phases\n", phases.len());
+            for event in &phases {
+                println!("  {} UTC  {} {:<14} {}",
+                    jd_to_datetime(event.jd).format("%Y-%m-%d %H:%M:%S"),
+                    event.phase.symbol(),
+                    event.phase.name(),
+                    format_position(event.moon_longitude)
+                );
+            }
+        }
+        "lunations" => {
+            let lunations = kernel.lunations(from, to, &search)?;
+            if format != Format::Table {
+                let records: Vec<Json> = lunations.iter()
+                    .map(|lunation| Json::object([
+                        ("number", (lunation.number as f64).into()),
+                        ("new_moon", datetime_json(Some(lunation.new_moon))),
+                        ("first_quarter", datetime_json(lunation.first_quarter)),
+                        ("full_moon", datetime_json(lunation.full_moon)),
+                        ("last_quarter", datetime_json(lunation.last_quarter)),
+                        ("new_moon_jd", lunation.new_moon.into()),
+                        ("length", lunation.length().into()),
+                    ]))
+                    .collect();
+                print_records(format, &records)?;
+                return Ok(());
+            }
+            println!("\n🌙 {} lunations\n", lunations.len());
+            println!("┌───────┬──────────────────┬──────────────────┬──────────────────┬──────────────────┬──────────┐");
+            println!("│ Brown │ 🌑 New           │ 🌓 First quarter │ 🌕 Full          │ 🌗 Last quarter  │  Days    │");
+            println!("├───────┼──────────────────┼──────────────────┼──────────────────┼──────────────────┼──────────┤");
+            for lunation in &lunations {
+                println!("│ {:>5} │ {:<16} │ {:<16} │ {:<16} │ {:<16} │ {:>8} │",
+                    lunation.number,
+                    format_jd(Some(lunation.new_moon)),
+                    format_jd(lunation.first_quarter),
+                    format_jd(lunation.full_moon),
+                    format_jd(lunation.last_quarter),
+                    lunation.length().map_or("—".to_string(), |days| format!("{:.4}", days))
+                );
+            }
+            println!("└───────┴──────────────────┴──────────────────┴──────────────────┴──────────────────┴──────────┘");
+        }
+        "void" => {
+            let mut options = VoidOptions { search, ..VoidOptions::default() };
+            if let Some(list) = flag_value(&args, "--aspect")? {
+                options.aspects = list.split(',').map(|a| a.parse()).collect::<Result<_, _>>()?;
+            }
+            if args.iter().any(|a| a == "--outer") {
+                options.bodies.extend([URANUS, NEPTUNE, PLUTO]);
+            }
+            if let Some(mode) = flag_value(&args, "-s")? {
+                options.zodiac = Zodiac::Sidereal(Ayanamsa::parse(mode)?);
+            }
+
+            let voids = kernel.void_of_course(from, to, &options)?;
+            if format != Format::Table {
+                let records: Vec<Json> = voids.iter()
+                    .map(|void| {
+                        let mut record = Json::object([
+                            ("start_jd", void.start_jd.into()),
+                            ("start", datetime_json(Some(void.start_jd))),
+                            ("end_jd", void.end_jd.into()),
+                            ("end", datetime_json(Some(void.end_jd))),
+                            ("hours", ((void.end_jd - void.start_jd) * 24.0).into()),
+                            ("sign", SIGN_NAMES[void.sign].into()),
+                            ("next_sign", SIGN_NAMES[void.next_sign].into()),
+                            ("last_aspect", void.last_aspect.map(|hit| hit.angle).into()),
+                        ]);
+                        record.push("last_aspect_body", match void.last_aspect.map(|hit| hit.target) {
+                            Some(Target::Body(body)) => body_name(body).into(),
+                            _ => Json::Null,
+                        });
+                        record
+                    })
+                    .collect();
+                print_records(format, &records)?;
+                return Ok(());
+            }
+            println!("\n🌙 {} void-of-course periods ({})\n", voids.len(), options.zodiac.name());
+            for void in &voids {
+                let last = match void.last_aspect {
+                    Some(hit) => match hit.target {
+                        Target::Body(body) => format!("{:>4}° {} {}", hit.angle, body_symbol(body), body_name(body)),
+                        Target::Natal(lon) => format!("{:>4}° {}", hit.angle, format_position(lon)),
+                    },
+                    None => "no aspect in sign".to_string(),
+                };
+                println!("  {} → {} UTC  {:>6.2}h  {} → {}  ({})",
+                    jd_to_datetime(void.start_jd).format("%Y-%m-%d %H:%M:%S"),
+                    jd_to_datetime(void.end_jd).format("%Y-%m-%d %H:%M:%S"),
+                    (void.end_jd - void.start_jd) * 24.0,
+                    SIGN_NAMES[void.sign],
+                    SIGN_NAMES[void.next_sign],
+                    last
+                );
+            }
+        }
+        other => return Err(format!("unknown command {}", other).into()),
+    }
+    Ok(())
+}
@@ -0,0 +1,303 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use std::thread;
+use tiny_http::{Header, Method, Response, Server};
+use medusa::bodies::body_from_name;
+use medusa::cli::flag_value;
+use medusa::coords::GeoLocation;
+use medusa::events::{Boundary, EventOptions};
+use medusa::houses::HouseSystem;
+use medusa::aspects::AspectConfig;
+use medusa::json::{chart_json, event_json, position_json, relationship_json, time_json, Json};
+use medusa::kernel::{Kernel, KernelError};
+use medusa::render::{bi_wheel, chart_wheel, WheelOptions};
+use medusa::sidereal::{Ayanamsa, Zodiac};
+use medusa::time::parse_jd_or_datetime;
+
+// Ingress and station searches scale with the range; keep one request
+// from tying up a worker for long.
+const MAX_EVENT_DAYS: f64 = 3660.0;
+
+enum Reply {
+    Json(Json),
+    Svg(String),
+}
+
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self { status: 400, message: message.into() }
+    }
+
+    fn json(&self) -> Json {
+        Json::object([("error", Json::object([
+            ("status", (self.status as u32).into()),
+            ("message", self.message.clone().into()),
+        ]))])
+    }
+}
+
+impl From<KernelError> for ApiError {
+    fn from(e: KernelError) -> Self {
+        let status = match e {
+            KernelError::OutOfRange { .. } | KernelError::UnknownBody(_) | KernelError::UnknownStar(_)
+            | KernelError::AyanamsaNotStored(_) | KernelError::FrameMismatch { .. } => 422,
+            _ => 500,
+        };
+        Self { status, message: e.to_string() }
+    }
+}
+
+struct Query(Vec<(String, String)>);
+
+impl Query {
+    fn parse(query: &str) -> Self {
+        Query(query.split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(key), percent_decode(value))
+            })
+            .collect())
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    fn required(&self, key: &str) -> Result<&str, ApiError> {
+        self.get(key).ok_or_else(|| ApiError::bad_request(format!("missing parameter {}", key)))
+    }
+
+    /// `jd=` or `datetime=` (UTC or RFC 3339 with offset).
+    fn time(&self) -> Result<f64, ApiError> {
+        let value = self.get("jd").or(self.get("datetime"))
+            .ok_or_else(|| ApiError::bad_request("missing parameter jd or datetime"))?;
+        parse_jd_or_datetime(value).map_err(ApiError::bad_request)
+    }
+
+    /// A birth given as `<person>=<jd|datetime>` and `<person>_place=lat,lon`.
+    fn birth(&self, person: &str) -> Result<(f64, GeoLocation), ApiError> {
+        let jd = parse_jd_or_datetime(self.required(person)?).map_err(ApiError::bad_request)?;
+        let place = GeoLocation::parse(self.required(&format!("{}_place", person))?).map_err(ApiError::bad_request)?;
+        Ok((jd, place))
+    }
+
+    fn zodiac(&self) -> Result<Zodiac, ApiError> {
+        match self.get("zodiac") {
+            None | Some("tropical") => Ok(Zodiac::Tropical),
+            Some(mode) => Ok(Zodiac::Sidereal(Ayanamsa::parse(mode).map_err(ApiError::bad_request)?)),
+        }
+    }
+
+    fn bodies(&self, kernel: &Kernel) -> Result<Vec<i32>, ApiError> {
+        match self.get("body") {
+            Some(list) => list.split(',')
+                .map(|name| body_from_name(name).ok_or_else(|| ApiError::bad_request(format!("unknown body {}", name))))
+                .collect(),
+            None => Ok(kernel.bodies().to_vec()),
+        }
+    }
+}
+
+// Decodes %XX escapes and '+' as a space.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()
+                    .and_then(|h| u8::from_str_radix(h, 16).ok());
+                match hex {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn health(kernel: &Kernel) -> Json {
+    let header = kernel.header();
+    Json::object([
+        ("status", "ok".into()),
+        ("start_jd", header.start_jd.into()),
+        ("end_jd", header.end_jd().into()),
+        ("frame", kernel.frame().to_string().into()),
+        ("bodies", kernel.bodies().to_vec().into()),
+    ])
+}
+
+fn positions(kernel: &Kernel, query: &Query) -> Result<Json, ApiError> {
+    let jd = query.time()?;
+    let zodiac = query.zodiac()?;
+    let positions = query.bodies(kernel)?.into_iter()
+        .map(|body| Ok(position_json(body, &kernel.position_in(body, jd, zodiac)?)))
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    let mut fields = time_json(jd);
+    fields.extend([("zodiac", zodiac.name().into()), ("positions", Json::Array(positions))]);
+    Ok(Json::object(fields))
+}
+
+fn chart(kernel: &Kernel, query: &Query) -> Result<Json, ApiError> {
+    let jd = query.time()?;
+    let location = GeoLocation::parse(query.required("place")?).map_err(ApiError::bad_request)?;
+    let system = HouseSystem::parse(query.get("houses").unwrap_or("P")).map_err(ApiError::bad_request)?;
+    let chart = kernel.chart(jd, location, system, query.zodiac()?)?;
+    Ok(chart_json(&chart))
+}
+
+fn synastry(kernel: &Kernel, query: &Query) -> Result<Json, ApiError> {
+    let system = HouseSystem::parse(query.get("houses").unwrap_or("P")).map_err(ApiError::bad_request)?;
+    let mut config = AspectConfig::default();
+    if query.get("minor") == Some("true") {
+        config = config.with_minor();
+    }
+    let relationship = kernel.relationship(query.birth("a")?, query.birth("b")?, system, query.zodiac()?, &config)?;
+    Ok(relationship_json(&relationship))
+}
+
+// The chart as an SVG wheel; with transit= the positions at that time go in
+// an outer ring.
+fn wheel(kernel: &Kernel, query: &Query) -> Result<Reply, ApiError> {
+    let jd = query.time()?;
+    let location = GeoLocation::parse(query.required("place")?).map_err(ApiError::bad_request)?;
+    let system = HouseSystem::parse(query.get("houses").unwrap_or("P")).map_err(ApiError::bad_request)?;
+    let zodiac = query.zodiac()?;
+    let chart = kernel.chart(jd, location, system, zodiac)?;
+
+    let mut options = WheelOptions::default();
+    if let Some(size) = query.get("size") {
+        options.size = size.parse::<f64>().ok()
+            .filter(|s| (100.0..=4000.0).contains(s))
+            .ok_or_else(|| ApiError::bad_request("size must be between 100 and 4000"))?;
+    }
+    let svg = match query.get("transit") {
+        Some(transit) => {
+            let transit_jd = parse_jd_or_datetime(transit).map_err(ApiError::bad_request)?;
+            bi_wheel(&chart, &kernel.positions_in(transit_jd, zodiac)?, &options)
+        }
+        None => chart_wheel(&chart, &options),
+    };
+    Ok(Reply::Svg(svg))
+}
+
+fn events(kernel: &Kernel, query: &Query) -> Result<Json, ApiError> {
+    let from = parse_jd_or_datetime(query.required("from")?).map_err(ApiError::bad_request)?;
+    let to = parse_jd_or_datetime(query.required("to")?).map_err(ApiError::bad_request)?;
+    if to <= from {
+        return Err(ApiError::bad_request("to must be after from"));
+    }
+    if to - from > MAX_EVENT_DAYS {
+        return Err(ApiError::bad_request(format!("range is limited to {} days", MAX_EVENT_DAYS)));
+    }
+
+    let mut options = EventOptions { zodiac: query.zodiac()?, ..EventOptions::default() };
+    if let Some(list) = query.get("boundary") {
+        options.boundaries = list.split(',').map(Boundary::parse).collect::<Result<_, _>>().map_err(ApiError::bad_request)?;
+    }
+    match query.get("type").unwrap_or("all") {
+        "all" => {}
+        "ingress" => options.stations = false,
+        "station" => options.boundaries.clear(),
+        other => return Err(ApiError::bad_request(format!("unknown event type {} (ingress, station, all)", other))),
+    }
+
+    let events = kernel.find_events(&query.bodies(kernel)?, from, to, &options)?;
+    Ok(Json::object([
+        ("from", from.into()),
+        ("to", to.into()),
+        ("events", Json::Array(events.iter().map(event_json).collect())),
+    ]))
+}
+
+fn route(kernel: &Kernel, method: &Method, url: &str) -> Result<Reply, ApiError> {
+    if *method != Method::Get {
+        return Err(ApiError { status: 405, message: "only GET is supported".to_string() });
+    }
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let query = Query::parse(query);
+    match path {
+        "/health" => Ok(Reply::Json(health(kernel))),
+        "/positions" => positions(kernel, &query).map(Reply::Json),
+        "/chart" => chart(kernel, &query).map(Reply::Json),
+        "/wheel" => wheel(kernel, &query),
+        "/synastry" => synastry(kernel, &query).map(Reply::Json),
+        "/events" => events(kernel, &query).map(Reply::Json),
+        _ => Err(ApiError { status: 404, message: format!("no endpoint {}", path) }),
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.iter().any(|a| a == "--help") {
+        println!("Usage:");
+        println!("  {} [--addr 127.0.0.1:8080] [--kernel zenith.kernel] [--workers n]", args[0]);
+        println!("\nEndpoints:");
+        println!("  GET /positions?jd=|datetime=[&body=sun,moon][&zodiac=lahiri]");
+        println!("  GET /chart?datetime=&place=lat,lon[,alt][&houses=P][&zodiac=]");
+        println!("  GET /wheel?datetime=&place=lat,lon[,alt][&houses=P][&transit=][&size=600]  (SVG)");
+        println!("  GET /synastry?a=&a_place=&b=&b_place=[&houses=P][&zodiac=][&minor=true]");
+        println!("  GET /events?from=&to=[&type=ingress|station|all][&body=][&boundary=sign]");
+        println!("  GET /health");
+        return Ok(());
+    }
+
+    let addr = flag_value(&args, "--addr")?.unwrap_or("127.0.0.1:8080");
+    let workers: usize = match flag_value(&args, "--workers")? {
+        Some(n) => n.parse()?,
+        None => thread::available_parallelism().map_or(4, |n| n.get()),
+    };
+
+    // One mapping shared by every worker
+    let kernel = Arc::new(Kernel::open(flag_value(&args, "--kernel")?.unwrap_or("zenith.kernel"))?);
+    let server = Arc::new(Server::http(addr).map_err(|e| e.to_string())?);
+    println!("🌐 Serving on http://{} with {} workers", addr, workers);
+
+    let json_header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    let svg_header = Header::from_bytes(&b"Content-Type"[..], &b"image/svg+xml"[..]).unwrap();
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let (kernel, server) = (Arc::clone(&kernel), Arc::clone(&server));
+            let (json_header, svg_header) = (json_header.clone(), svg_header.clone());
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    // A panic answers 500 instead of taking this worker down with it
+                    let reply = catch_unwind(AssertUnwindSafe(|| route(&kernel, request.method(), request.url())))
+                        .unwrap_or_else(|_| Err(ApiError { status: 500, message: "internal error".to_string() }));
+                    let (status, body, header) = match reply {
+                        Ok(Reply::Json(json)) => (200, json.to_string(), &json_header),
+                        Ok(Reply::Svg(svg)) => (200, svg, &svg_header),
+                        Err(e) => (e.status, e.json().to_string(), &json_header),
+                    };
+                    let response = Response::from_string(body)
+                        .with_status_code(status)
+                        .with_header(header.clone());
+                    if let Err(e) = request.respond(response) {
+                        eprintln!("failed to respond: {}", e);
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let _ = handle.join();
+    }
+    Ok(())
+}
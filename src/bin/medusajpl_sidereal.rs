@@ -1,75 +1,115 @@
-use std::fs::File;
-use std::io::{Read, Write};
-use swisseph_sys::*;
 use std::time::Instant;
+use medusa::bodies::{body_name, DEFAULT_BODIES, EARTH, SUN};
+use medusa::cli::flag_value;
+use medusa::eclipse::{self, ECLIPSE_TAG};
+use medusa::ephemeris::{self, EphemerisSource};
+use medusa::frame::Frame;
+use medusa::kernel::{read_u32, KernelWriter};
+use medusa::sidereal::{self, Ayanamsa, AYANAMSA_TAG, DEFAULT_MODES, MODE_NAMES};
+use medusa::stars::{self, DEFAULT_STAR_STEP, STAR_TAG};
 
 const EPOCH: f64 = 2453307.0;
+const VALUE_FLAGS: [&str; 6] = ["--ayanamsa", "--ayanamsa-step", "--output", "--stars", "--star-step", "--frame"];
+
+fn parse_modes(arg: &str) -> Result<Vec<Ayanamsa>, String> {
+    if arg == "all" {
+        return Ok((0..MODE_NAMES.len() as i32).map(Ayanamsa::Predefined).collect());
+    }
+    arg.split(',').map(Ayanamsa::parse).collect()
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let start_time = Instant::now();
-    
+
     let args: Vec<String> = std::env::args().collect();
-    let start_jd: f64 = args.get(1)
+    let positional: Vec<&String> = args.iter().skip(1)
+        .enumerate()
+        .filter(|(i, a)| !a.starts_with("--") && !VALUE_FLAGS.contains(&args[*i].as_str()))
+        .map(|(_, a)| a)
+        .collect();
+
+    let start_jd: f64 = positional.first()
         .and_then(|s| s.parse().ok())
         .unwrap_or(EPOCH);
+    let end_jd: f64 = positional.get(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(start_jd + 365.25);
+    let step: f64 = positional.get(2)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1.0);
 
-    let mut xx = [0.0; 6];
-    let mut serr = [0i8; 256];
-    let mut base_positions = [0.0; 20];
-    let mut ayanamsa_values = [0.0; 3];
+    let modes = match flag_value(&args, "--ayanamsa")? {
+        Some(arg) => parse_modes(arg)?,
+        None => DEFAULT_MODES.to_vec(),
+    };
+    let ayanamsa_step: f64 = flag_value(&args, "--ayanamsa-step")?
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1.0);
+    let output = flag_value(&args, "--output")?.unwrap_or("zenith.kernel");
+    let include_eclipses = args.iter().any(|a| a == "--eclipses");
+    // Star names as in sefstars.txt, e.g. --stars Regulus,Spica,Algol
+    let star_names = flag_value(&args, "--stars")?.unwrap_or_default();
+    let star_step: f64 = flag_value(&args, "--star-step")?
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_STAR_STEP);
+
+    // --frame geo (default), helio, bary or topo:lat,lon[,alt]
+    let frame = match flag_value(&args, "--frame")? {
+        Some(arg) => Frame::parse(arg)?,
+        None => Frame::Geocentric,
+    };
+    // The heliocentric Sun is always zero, so store the Earth instead
+    let bodies: Vec<i32> = DEFAULT_BODIES.iter()
+        .map(|&body| if body == SUN && frame == Frame::Heliocentric { EARTH } else { body })
+        .collect();
 
     println!("🚀 Zenith Engine Starting");
-    println!("Processing JD {}", start_jd);
+    println!("Processing JD {} to {} every {} days ({})", start_jd, end_jd, step, frame);
 
     // Set up DE441
-    unsafe {
-        swe_set_ephe_path("./ephe\0".as_ptr() as *const i8);
-        swe_set_jpl_file("de441.eph\0".as_ptr() as *const i8);
-
-        // Calculate main ayanamsas at start time
-        swe_set_sid_mode(SE_SIDM_FAGAN_BRADLEY.try_into().unwrap(), 0.0, 0.0);
-        ayanamsa_values[0] = swe_get_ayanamsa(start_jd);
+    ephemeris::init(EphemerisSource::Jpl);
 
-        swe_set_sid_mode(SE_SIDM_LAHIRI.try_into().unwrap(), 0.0, 0.0);
-        ayanamsa_values[1] = swe_get_ayanamsa(start_jd);
+    let mut writer = KernelWriter::new(start_jd, step, &bodies);
+    writer.set_frame(frame);
+    let records = ((end_jd - start_jd) / step).ceil() as u64 + 1;
+    let pb = indicatif::ProgressBar::new(records);
 
-        swe_set_sid_mode(SE_SIDM_TRUE_CITRA.try_into().unwrap(), 0.0, 0.0);
-        ayanamsa_values[2] = swe_get_ayanamsa(start_jd);
+    for i in 0..records {
+        let jd = start_jd + i as f64 * step;
+        let positions = bodies.iter()
+            .map(|&body| ephemeris::position_in_frame(jd, body, EphemerisSource::Jpl, frame)
+                .map_err(|e| format!("{} at JD {}: {}", body_name(body), jd, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        writer.push_record(&positions);
+        pb.inc(1);
     }
+    pb.finish();
 
-    let bodies = [SE_SUN, SE_MOON, SE_MERCURY, SE_VENUS, SE_MARS,
-                 SE_JUPITER, SE_SATURN, SE_URANUS, SE_NEPTUNE, SE_PLUTO,
-                 SE_CHIRON, SE_TRUE_NODE, SE_MEAN_APOG, SE_VESTA, 
-                 SE_JUNO, SE_CERES, SE_PALLAS, SE_ASC, SE_ARMC, 
-                 (SE_AST_OFFSET + 5550)];
-
-    for (i, &body) in bodies.iter().enumerate() {
-        unsafe {
-            let ret = swe_calc_ut(start_jd, body as i32,
-                (SEFLG_SPEED | SEFLG_JPLEPH) as i32,
-                xx.as_mut_ptr(), serr.as_mut_ptr());
-
-            if ret >= 0 {
-                let pos = xx[0].rem_euclid(360.0);
-                println!("Planet {}: {:.6}°", i, pos);
-                base_positions[i] = pos;
-            } else {
-                println!("Failed to calculate position for body {}", i);
-            }
-        }
+    // Ayanamsa drifts ~50"/year, so store it as a series rather than a
+    // single start value.
+    let series_end = start_jd + (records - 1) as f64 * step;
+    println!("\nStoring {} ayanamsa series every {} days:", modes.len(), ayanamsa_step);
+    for mode in &modes {
+        println!("  {}", mode.name());
     }
+    let section = sidereal::build_section(start_jd, series_end, ayanamsa_step, &modes, EphemerisSource::Jpl)?;
+    writer.add_section(AYANAMSA_TAG, section);
 
-    let mut file = File::create("zenith.kernel")?;
-    file.write_all(&(start_jd as f64).to_le_bytes())?;
-    for pos in &base_positions {
-        file.write_all(&(*pos as f64).to_le_bytes())?;
+    if include_eclipses {
+        let section = eclipse::build_section(start_jd, series_end, EphemerisSource::Jpl)?;
+        println!("\nStoring {} eclipses", read_u32(&section, 0));
+        writer.add_section(ECLIPSE_TAG, section);
     }
-    // Add ayanamsa values to end of kernel
-    for ayan in &ayanamsa_values {
-        file.write_all(&(*ayan as f64).to_le_bytes())?;
+
+    if !star_names.is_empty() {
+        let names: Vec<&str> = star_names.split(',').collect();
+        println!("\nStoring {} fixed stars every {} days", names.len(), star_step);
+        let section = stars::build_section(start_jd, series_end, star_step, &names, EphemerisSource::Jpl)?;
+        writer.add_section(STAR_TAG, section);
     }
 
+    let size = writer.write(output)?;
     println!("\n✨ Completed in {:?}", start_time.elapsed());
-    println!("Size: {} bytes", std::fs::metadata("zenith.kernel")?.len());
+    println!("Size: {} bytes", size);
     Ok(())
-}
\ No newline at end of file
+}
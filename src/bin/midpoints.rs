@@ -0,0 +1,126 @@
+use medusa::bodies::{body_from_name, body_name, body_symbol, format_position};
+use medusa::cli::flag_value;
+use medusa::json::{sign_json, Json};
+use medusa::kernel::Kernel;
+use medusa::midpoints::{midpoint_tree, planetary_pictures, sort_on_dial, Dial, DialPoint};
+use medusa::output::{print_records, Format};
+use medusa::sidereal::{Ayanamsa, Zodiac};
+use medusa::time::{jd_to_datetime, parse_jd_or_datetime};
+
+fn short(body: i32) -> String {
+    format!("{}{}", body_symbol(body), body_name(body).chars().take(2).collect::<String>())
+}
+
+fn record(fields: Vec<(&str, Json)>, longitude: f64) -> Json {
+    let mut record = Json::object(fields);
+    record.push("longitude", longitude.into());
+    for (key, value) in sign_json(longitude) {
+        record.push(key, value);
+    }
+    record
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 2 {
+        println!("Usage:");
+        println!("  {} <jd|datetime> [options]", args[0]);
+        println!("\nOptions:");
+        println!("  --dial <360|90|45>     Dial for sorting and pictures (default 90)");
+        println!("  --orb <degrees>        Orb for trees and pictures (default 1.5)");
+        println!("  --tree <body>          Midpoint tree of one body instead of the sort list");
+        println!("  --pictures             List planetary pictures A+B-C=D");
+        println!("  --harmonic <n>         Work in the nth harmonic chart");
+        println!("  -s <ayanamsa>          Sidereal zodiac");
+        println!("  --format <format>      table (default), json, csv, ndjson");
+        return Ok(());
+    }
+
+    let jd = parse_jd_or_datetime(&args[1])?;
+    let dial = Dial::parse(flag_value(&args, "--dial")?.unwrap_or("90"))?;
+    let orb: f64 = flag_value(&args, "--orb")?.map_or(Ok(1.5), str::parse)?;
+    let zodiac = match flag_value(&args, "-s")? {
+        Some(mode) => Zodiac::Sidereal(Ayanamsa::parse(mode)?),
+        None => Zodiac::Tropical,
+    };
+
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+    let kernel = Kernel::open("zenith.kernel")?;
+    let positions = match flag_value(&args, "--harmonic")? {
+        Some(n) => kernel.harmonic_chart(jd, n.parse()?, zodiac)?,
+        None => kernel.positions_in(jd, zodiac)?,
+    };
+
+    if format != Format::Table {
+        let records: Vec<Json> = if let Some(name) = flag_value(&args, "--tree")? {
+            let body = body_from_name(name).ok_or(format!("unknown body {}", name))?;
+            midpoint_tree(body, &positions, dial, orb).into_iter()
+                .map(|(m, distance)| record(vec![
+                    ("a", body_name(m.a).into()),
+                    ("b", body_name(m.b).into()),
+                    ("orb", distance.into()),
+                ], m.longitude))
+                .collect()
+        } else if args.iter().any(|a| a == "--pictures") {
+            planetary_pictures(&positions, dial, orb).into_iter()
+                .map(|p| record(vec![
+                    ("a", body_name(p.a).into()),
+                    ("b", body_name(p.b).into()),
+                    ("c", body_name(p.c).into()),
+                    ("d", body_name(p.d).into()),
+                    ("orb", p.orb.into()),
+                ], p.longitude))
+                .collect()
+        } else {
+            sort_on_dial(&positions, dial).into_iter()
+                .map(|entry| {
+                    let (a, b) = match entry.point {
+                        DialPoint::Body(body) => (body, None),
+                        DialPoint::Midpoint(a, b) => (a, Some(b)),
+                    };
+                    record(vec![
+                        ("position", entry.position.into()),
+                        ("a", body_name(a).into()),
+                        ("b", b.map(body_name).into()),
+                    ], entry.longitude)
+                })
+                .collect()
+        };
+        print_records(format, &records)?;
+        return Ok(());
+    }
+
+    println!("\n🎯 Midpoints for {} UTC  ({}° dial, {})",
+        jd_to_datetime(jd).format("%Y-%m-%d %H:%M:%S"),
+        dial.modulus(),
+        zodiac.name()
+    );
+    if let Some(n) = flag_value(&args, "--harmonic")? {
+        println!("   Harmonic {}", n);
+    }
+
+    if let Some(name) = flag_value(&args, "--tree")? {
+        let body = body_from_name(name).ok_or(format!("unknown body {}", name))?;
+        println!("\n{} {} =\n", body_symbol(body), body_name(body));
+        for (m, distance) in midpoint_tree(body, &positions, dial, orb) {
+            println!("   {}/{}  {}  orb {:.2}°", short(m.a), short(m.b), format_position(m.longitude), distance);
+        }
+    } else if args.iter().any(|a| a == "--pictures") {
+        println!();
+        for p in planetary_pictures(&positions, dial, orb) {
+            println!("   {} + {} - {} = {}   {}  orb {:.2}°",
+                short(p.a), short(p.b), short(p.c), short(p.d), format_position(p.longitude), p.orb);
+        }
+    } else {
+        println!();
+        for entry in sort_on_dial(&positions, dial) {
+            let label = match entry.point {
+                DialPoint::Body(body) => short(body),
+                DialPoint::Midpoint(a, b) => format!("{}/{}", short(a), short(b)),
+            };
+            println!("   {:>6.2}°  {:<14} {}", entry.position, label, format_position(entry.longitude));
+        }
+    }
+    Ok(())
+}
@@ -1,54 +1,47 @@
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom};
 use chrono::{DateTime, TimeZone, Utc};
-
-const BODIES: [&str; 20] = [
-    "Sun", "Moon", "Mercury", "Venus", "Mars",
-    "Jupiter", "Saturn", "Uranus", "Neptune", "Pluto",
-    "Chiron", "True Node", "Mean Apogee",
-    "Vesta", "Juno", "Ceres", "Pallas", "ASC", "ARMC",
-    "15550"
-];
-
-const SYMBOLS: [&str; 20] = [
-    "☉", "☽", "☿", "♀", "♂", "♃", "♄", "♅", "♆", "⯓",
-    "⚷", "☊", "⚸", "⚴", "⊕", "⚶", "⯓", "Asc", "MC",
-    "☄︎"
-];
+use medusa::batch::{open_input, read_queries, BatchQuery};
+use medusa::aspects::{harmonic_aspects, AspectConfig, ParallelKind};
+use medusa::bodies::{body_name, body_symbol, decimal_to_dms, MEAN_APOGEE, MEAN_NODE, SOUTH_NODE, TRUE_NODE};
+use medusa::cli::{take_switch, take_value};
+use medusa::coords::GeoLocation;
+use medusa::houses::HouseSystem;
+use medusa::json::Json;
+use medusa::kernel::{Kernel, Position};
+use medusa::lots::{is_day_chart, traditional_lots, Lot};
+use medusa::output::{self, house_records, point_record, position_record, Format, RecordWriter};
+use medusa::render::{bi_wheel, chart_wheel, WheelOptions};
+use medusa::sidereal::{Ayanamsa, Zodiac};
+use medusa::time::parse_jd_or_datetime;
+use medusa::vedic::Varga;
 
 const SIGNS: [&str; 12] = ["♈", "♉", "♊", "♋", "♌", "♍", "♎", "♏", "♐", "♑", "♒", "♓"];
 
+struct DisplayOptions {
+    zodiac: Zodiac,
+    vargas: Vec<Varga>,
+    aspects: AspectConfig,
+    location: Option<GeoLocation>,
+    house_system: HouseSystem,
+    lots: Vec<Lot>,
+    topocentric: bool,
+    format: Format,
+    svg: Option<String>,
+    transit: Option<f64>,
+}
+
 struct ParabolaReader {
-    file: File,
+    kernel: Kernel,
     house_file: File,
 }
 
 impl ParabolaReader {
     fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let file = File::open("zenith.kernel")?;
+        let kernel = Kernel::open("zenith.kernel")?;
         let house_file = File::open("houses.kernel")?;
         
-        Ok(Self { file, house_file })
-    }
-
-    fn read_timestamp(&mut self, offset: u64) -> Result<f64, Box<dyn std::error::Error>> {
-        self.file.seek(SeekFrom::Start(offset))?;
-        let mut timestamp_bytes = [0u8; 8];
-        self.file.read_exact(&mut timestamp_bytes)?;
-        Ok(f64::from_le_bytes(timestamp_bytes))
-    }
-
-    fn read_positions(&mut self, offset: u64) -> Result<Vec<f64>, Box<dyn std::error::Error>> {
-        self.file.seek(SeekFrom::Start(offset))?;
-        let mut positions = Vec::with_capacity(20);
-        
-        for _ in 0..20 {
-            let mut pos_bytes = [0u8; 8];
-            self.file.read_exact(&mut pos_bytes)?;
-            positions.push(f64::from_le_bytes(pos_bytes));
-        }
-        
-        Ok(positions)
+        Ok(Self { kernel, house_file })
     }
 
     fn read_houses(&mut self) -> Result<Vec<Vec<f64>>, Box<dyn std::error::Error>> {
@@ -75,54 +68,57 @@ impl ParabolaReader {
         format!("{}{}°{:02}'", SIGNS[sign_num], sign_deg, minutes)
     }
 
-    fn find_closest_time(&mut self, target_jd: f64) -> Result<u64, Box<dyn std::error::Error>> {
-        let mut offset = 0;
-        let mut closest_offset = 0;
-        let mut closest_diff = f64::MAX;
-
-        loop {
-            match self.read_timestamp(offset) {
-                Ok(timestamp) => {
-                    let diff = (timestamp - target_jd).abs();
-                    if diff < closest_diff {
-                        closest_diff = diff;
-                        closest_offset = offset;
-                    }
-                    offset += 8 + (20 * 8);  // timestamp + positions
-                },
-                Err(_) => break
+    fn positions(&self, jd: f64, location: Option<GeoLocation>, options: &DisplayOptions) -> Result<Vec<(i32, Position)>, Box<dyn std::error::Error>> {
+        let mut positions = self.kernel.positions_in(jd, options.zodiac)?;
+        if options.topocentric {
+            let location = location.ok_or("--topocentric needs a location")?;
+            // The lunar points have no real distance, so no parallax
+            for (body, pos) in positions.iter_mut().filter(|(b, _)| ![TRUE_NODE, MEAN_NODE, MEAN_APOGEE].contains(b)) {
+                *pos = self.kernel.topocentric_position_in(*body, jd, location, options.zodiac)?;
             }
         }
-
-        Ok(closest_offset)
+        Ok(positions)
     }
 
-    fn print_positions(&mut self, jd: f64) -> Result<(), Box<dyn std::error::Error>> {
-        let offset = self.find_closest_time(jd)?;
-        let timestamp = self.read_timestamp(offset)?;
-        let positions = self.read_positions(offset + 8)?;
+    fn print_positions(&mut self, jd: f64, options: &DisplayOptions) -> Result<(), Box<dyn std::error::Error>> {
+        let zodiac = options.zodiac;
+        let positions = self.positions(jd, options.location, options)?;
+        if options.format != Format::Table {
+            output::print_records(options.format, &self.records(jd, &positions, options.location, options)?)?;
+            return Ok(());
+        }
         let house_positions = self.read_houses()?;
 
-        let date_time = jd_to_datetime(timestamp);
+        let date_time = jd_to_datetime(jd);
         println!("\n🔍 Time: {} UTC", date_time.format("%Y-%m-%d %H:%M:%S"));
-        println!("   JD:   {:.6}\n", timestamp);
+        println!("   JD:   {:.6}", jd);
+        println!("   Mode: {}", zodiac.name());
+        println!("   Frame: {}{}\n", self.kernel.frame(), if options.topocentric { ", parallax applied" } else { "" });
 
         println!("╭────────┬─────────────────╮");
         println!("│ Body   │    Position     │");
         println!("├────────┼─────────────────┤");
 
-        for i in 0..20 {
+        for (i, (body, pos)) in positions.iter().enumerate() {
             print!("│ {:<4} {} │ {} │\n",
-                SYMBOLS[i],
-                BODIES[i].chars().take(2).collect::<String>(),
-                self.format_position(positions[i]).pad_to_width(15)
+                body_symbol(*body),
+                body_name(*body).chars().take(2).collect::<String>(),
+                self.format_position(pos.longitude).pad_to_width(15)
             );
-            if i < 19 {
+            if i < positions.len() - 1 {
                 println!("├────────┼─────────────────┤");
             }
         }
         println!("╰────────┴─────────────────╯\n");
 
+        if let Zodiac::Sidereal(mode) = zodiac {
+            self.print_vedic(jd, mode, &options.vargas)?;
+        }
+        self.print_aspects(jd, &options.aspects)?;
+        if let Some(location) = options.location {
+            self.print_lots(jd, location, options)?;
+        }
+
         // Print houses
         let names = ["Placidus", "Koch", "Equal", "Whole Sign", "Regiomontanus"];
         for (i, name) in names.iter().enumerate() {
@@ -145,6 +141,175 @@ impl ParabolaReader {
 
         Ok(())
     }
+
+    // Bodies, then with a location the angles, cusps and lots, all in the
+    // shared record schema.
+    fn records(&self, jd: f64, positions: &[(i32, Position)], location: Option<GeoLocation>,
+               options: &DisplayOptions) -> Result<Vec<Json>, Box<dyn std::error::Error>> {
+        let chart = location
+            .map(|location| self.kernel.chart(jd, location, options.house_system, options.zodiac))
+            .transpose()?;
+        let house = |longitude: f64| chart.as_ref().map(|c| c.houses.house_of(longitude));
+
+        let mut records: Vec<_> = positions.iter()
+            .map(|(body, pos)| position_record(jd, *body, pos, house(pos.longitude)))
+            .collect();
+        if let Some(chart) = &chart {
+            records.extend(house_records(jd, &chart.houses));
+            for lot in chart.lots(&options.lots)? {
+                records.push(point_record(jd, &lot.name, lot.longitude, Some(lot.house)));
+            }
+        }
+        Ok(records)
+    }
+
+    // The chart wheel for --svg, with the --transit positions outside it.
+    fn write_svg(&self, jd: f64, path: &str, options: &DisplayOptions) -> Result<(), Box<dyn std::error::Error>> {
+        let location = options.location.ok_or("--svg needs --location")?;
+        let chart = self.kernel.chart(jd, location, options.house_system, options.zodiac)?;
+        let wheel_options = WheelOptions { aspects: options.aspects.clone(), ..WheelOptions::default() };
+        let svg = match options.transit {
+            Some(transit) => bi_wheel(&chart, &self.kernel.positions_in(transit, options.zodiac)?, &wheel_options),
+            None => chart_wheel(&chart, &wheel_options),
+        };
+        std::fs::write(path, svg)?;
+        Ok(())
+    }
+
+    fn batch_records(&self, query: &BatchQuery, options: &DisplayOptions) -> Result<Vec<Json>, Box<dyn std::error::Error>> {
+        let location = query.location.or(options.location);
+        let positions = self.positions(query.jd, location, options)?;
+        let mut records = self.records(query.jd, &positions, location, options)?;
+        for record in &mut records {
+            record.push("line", query.line.into());
+        }
+        Ok(records)
+    }
+
+    // Answers every query in `input` from the one open kernel, streaming
+    // the records as each line is done. Bad lines are reported on stderr
+    // and skipped.
+    fn run_batch(&self, input: &str, options: &DisplayOptions) -> Result<(), Box<dyn std::error::Error>> {
+        let stdout = io::stdout();
+        let mut writer = RecordWriter::new(BufWriter::new(stdout.lock()), options.format);
+        let mut failed = 0;
+        for query in read_queries(open_input(input)?) {
+            let result = match query {
+                Ok(query) => self.batch_records(&query, options).map_err(|e| format!("line {}: {}", query.line, e)),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(records) => {
+                    writer.write(&records)?;
+                    writer.flush()?;
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    failed += 1;
+                }
+            }
+        }
+        writer.finish()?;
+        if failed > 0 {
+            return Err(format!("{} queries failed", failed).into());
+        }
+        Ok(())
+    }
+
+    fn print_vedic(&self, jd: f64, mode: Ayanamsa, vargas: &[Varga]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut bodies = self.kernel.bodies().to_vec();
+        bodies.push(SOUTH_NODE);
+        let charts = vargas.iter()
+            .map(|&v| self.kernel.varga_chart(jd, mode, v))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let varga_cols: String = vargas.iter().map(|v| format!(" {:<4}│", format!("D{}", v.division()))).collect();
+        let varga_rule: String = vargas.iter().map(|_| "──────┼").collect();
+        println!("╭────────┬──────────────────────┬──────┬─────────┬{}", varga_rule.replace('┼', "┬"));
+        println!("│ Body   │ Nakshatra            │ Pada │ Lord    │{}", varga_cols);
+        println!("├────────┼──────────────────────┼──────┼─────────┼{}", varga_rule);
+
+        for body in bodies {
+            let nakshatra = match self.kernel.nakshatra_at(body, jd, mode) {
+                Ok(n) => n,
+                Err(_) => continue,
+            };
+            let signs: String = charts.iter()
+                .map(|chart| {
+                    let sign = chart.iter().find(|(b, _)| *b == body).map(|(_, s)| *s).unwrap_or(0);
+                    format!("  {}  │", SIGNS[sign])
+                })
+                .collect();
+            println!("│ {:<4} {} │ {:<20} │  {}   │ {:<7} │{}",
+                body_symbol(body),
+                body_name(body).chars().take(2).collect::<String>(),
+                nakshatra.name(),
+                nakshatra.pada,
+                nakshatra.lord.name(),
+                signs
+            );
+        }
+        println!("╰────────┴──────────────────────┴──────┴─────────┴{}\n", varga_rule.replace('┼', "┴"));
+        Ok(())
+    }
+
+    fn print_lots(&self, jd: f64, location: GeoLocation, options: &DisplayOptions) -> Result<(), Box<dyn std::error::Error>> {
+        let chart = self.kernel.chart(jd, location, options.house_system, options.zodiac)?;
+        let lots = chart.lots(&options.lots)?;
+
+        println!("Lots ({} chart, {} houses):", if is_day_chart(&chart) { "day" } else { "night" }, chart.houses.system.name());
+        println!("╭─────────────┬───────────────┬───────╮");
+        println!("│ Lot         │   Position    │ House │");
+        println!("├─────────────┼───────────────┼───────┤");
+        for lot in &lots {
+            println!("│ {:<11} │ {} │  {:2}   │",
+                lot.name.chars().take(11).collect::<String>(),
+                self.format_position(lot.longitude).pad_to_width(13),
+                lot.house
+            );
+        }
+        println!("╰─────────────┴───────────────┴───────╯\n");
+        Ok(())
+    }
+
+    fn print_aspects(&self, jd: f64, config: &AspectConfig) -> Result<(), Box<dyn std::error::Error>> {
+        let aspects = self.kernel.aspects_at(jd, config)?;
+        let parallels = self.kernel.parallels_at(jd, config)?;
+
+        println!("Aspects:");
+        println!("╭───────────────┬────────────────┬─────────┬────────────╮");
+        println!("│ Bodies        │ Aspect         │   Orb   │            │");
+        println!("├───────────────┼────────────────┼─────────┼────────────┤");
+        for aspect in &aspects {
+            let (deg, min, _) = decimal_to_dms(aspect.orb);
+            println!("│ {} {:<2} {} {:<2}     │ {:<2} {:<11} │ {:>2}°{:02}'   │ {:<10} │",
+                body_symbol(aspect.body_a),
+                body_name(aspect.body_a).chars().take(2).collect::<String>(),
+                body_symbol(aspect.body_b),
+                body_name(aspect.body_b).chars().take(2).collect::<String>(),
+                aspect.kind.symbol,
+                aspect.kind.name.chars().take(11).collect::<String>(),
+                deg, min,
+                if aspect.applying { "applying" } else { "separating" }
+            );
+        }
+        for parallel in &parallels {
+            let (deg, min, _) = decimal_to_dms(parallel.orb);
+            println!("│ {} {:<2} {} {:<2}     │ {:<14} │ {:>2}°{:02}'   │            │",
+                body_symbol(parallel.body_a),
+                body_name(parallel.body_a).chars().take(2).collect::<String>(),
+                body_symbol(parallel.body_b),
+                body_name(parallel.body_b).chars().take(2).collect::<String>(),
+                match parallel.kind {
+                    ParallelKind::Parallel => "Parallel",
+                    ParallelKind::ContraParallel => "Contraparallel",
+                },
+                deg, min
+            );
+        }
+        println!("╰───────────────┴────────────────┴─────────┴────────────╯\n");
+        Ok(())
+    }
 }
 
 trait PadString {
@@ -168,15 +333,86 @@ fn jd_to_datetime(jd: f64) -> DateTime<Utc> {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args: Vec<String> = std::env::args().collect();
+
+    // -s <mode> selects a sidereal zodiac: a SE_SIDM_* number, a name such
+    // as "lahiri", or user:<t0>:<ayan_t0>
+    let zodiac = match take_value(&mut args, "-s")? {
+        Some(mode) => Zodiac::Sidereal(Ayanamsa::parse(&mode)?),
+        None => Zodiac::Tropical,
+    };
+    // -v D9,D10,D60 picks the divisional charts shown with a sidereal zodiac
+    let vargas = match take_value(&mut args, "-v")? {
+        Some(list) => list.split(',')
+            .map(|v| Varga::parse(v).ok_or(format!("unknown varga {}", v)))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => vec![Varga::D9],
+    };
+    // --minor adds the minor aspects, --harmonic <n> the nth harmonic ones
+    let mut aspects = AspectConfig::default();
+    if take_switch(&mut args, "--minor") {
+        aspects = aspects.with_minor();
+    }
+    if let Some(n) = take_value(&mut args, "--harmonic")? {
+        aspects.aspects.extend(harmonic_aspects(n.parse()?, 1.0));
+    }
+
+    // --location lat,lon[,alt] casts a chart for the lots; --lot Name=asc+a-b
+    // (repeatable) adds to the traditional catalog
+    let location = take_value(&mut args, "--location")?
+        .map(|s| GeoLocation::parse(&s))
+        .transpose()?;
+    let house_system = match take_value(&mut args, "--houses")? {
+        Some(system) => HouseSystem::parse(&system)?,
+        None => HouseSystem::Placidus,
+    };
+    // --batch <file|-> reads one "<jd|datetime> [lat,lon[,alt]]" query per
+    // line and streams records, NDJSON unless --format says otherwise
+    let batch = take_value(&mut args, "--batch")?;
+    // --topocentric corrects positions for parallax at --location (or at
+    // each batch line's location)
+    let topocentric = take_switch(&mut args, "--topocentric");
+    if topocentric && location.is_none() && batch.is_none() {
+        return Err("--topocentric needs --location".into());
+    }
+    // --format json|csv|ndjson prints records instead of the tables
+    let format = match take_value(&mut args, "--format")? {
+        Some(format) => Format::parse(&format)?,
+        None if batch.is_some() => Format::Ndjson,
+        None => Format::Table,
+    };
+    // --svg <file> draws the chart wheel (needs --location); --transit
+    // <jd|datetime> adds an outer ring of transiting positions
+    let svg = take_value(&mut args, "--svg")?;
+    let transit = take_value(&mut args, "--transit")?
+        .map(|t| parse_jd_or_datetime(&t))
+        .transpose()?;
+    if svg.is_some() && (location.is_none() || batch.is_some()) {
+        return Err("--svg needs --location and a single time".into());
+    }
+    let mut lots = traditional_lots();
+    while let Some(formula) = take_value(&mut args, "--lot")? {
+        lots.push(Lot::parse(&formula)?);
+    }
+
     let target_jd = if args.len() > 1 {
         args[1].parse()?
     } else {
         2451545.0  // J2000 if no argument
     };
 
+    let options = DisplayOptions { zodiac, vargas, aspects, location, house_system, lots, topocentric, format, svg, transit };
     let mut reader = ParabolaReader::new()?;
-    reader.print_positions(target_jd)?;
+    if let Some(input) = batch {
+        return reader.run_batch(&input, &options);
+    }
+    if let Some(path) = &options.svg {
+        reader.write_svg(target_jd, path, &options)?;
+        if options.format == Format::Table {
+            println!("🖼  Wrote {}", path);
+        }
+    }
+    reader.print_positions(target_jd, &options)?;
 
     Ok(())
-}
\ No newline at end of file
+}
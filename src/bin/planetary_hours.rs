@@ -0,0 +1,80 @@
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use medusa::bodies::{body_name, body_symbol};
+use medusa::cli::flag_value;
+use medusa::coords::GeoLocation;
+use medusa::ephemeris::{self, EphemerisSource};
+use medusa::json::Json;
+use medusa::kernel::Kernel;
+use medusa::output::{print_records, Format};
+use medusa::planetary_hours::planetary_day;
+use medusa::time::jd_to_local;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 2 || flag_value(&args, "--location")?.is_none() {
+        println!("Usage:");
+        println!("  {} <YYYY-MM-DD> --location lat,lon[,alt] [--tz Area/City] [--kernel riseset.kernel]", args[0]);
+        println!("      [--format table|json|csv|ndjson]");
+        println!("\nExample:");
+        println!("  {} 2025-03-20 --location 39.7294,-104.8319 --tz America/Denver", args[0]);
+        return Ok(());
+    }
+
+    let date = NaiveDate::parse_from_str(&args[1], "%Y-%m-%d")?;
+    let location = GeoLocation::parse(flag_value(&args, "--location")?.unwrap())?;
+    let tz: Tz = flag_value(&args, "--tz")?.unwrap_or("UTC").parse()?;
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+
+    // Stored rise/set tables skip the live swe calls
+    let day = match flag_value(&args, "--kernel")? {
+        Some(path) => Kernel::open(path)?.planetary_day(date, location)?,
+        None => {
+            ephemeris::init(EphemerisSource::Swiss);
+            planetary_day(date, location, &tz, EphemerisSource::Swiss)?
+        }
+    };
+
+    if format != Format::Table {
+        let local = |jd: f64| jd_to_local(jd, &tz).format("%Y-%m-%dT%H:%M:%S%:z").to_string();
+        let records: Vec<Json> = day.hours.iter()
+            .map(|hour| Json::object([
+                ("hour", (hour.number as u32).into()),
+                ("period", if hour.is_day() { "day" } else { "night" }.into()),
+                ("start_jd", hour.start_jd.into()),
+                ("start", local(hour.start_jd).into()),
+                ("end_jd", hour.end_jd.into()),
+                ("end", local(hour.end_jd).into()),
+                ("body", hour.ruler.into()),
+                ("name", body_name(hour.ruler).into()),
+            ]))
+            .collect();
+        print_records(format, &records)?;
+        return Ok(());
+    }
+
+    println!("\n⏳ Planetary hours for {} ({})", date.format("%A %Y-%m-%d"), tz);
+    println!("   Day ruler: {} {}", body_symbol(day.day_ruler), body_name(day.day_ruler));
+    println!("   Sunrise {}  Sunset {}  Next sunrise {}\n",
+        jd_to_local(day.sunrise, &tz).format("%H:%M:%S"),
+        jd_to_local(day.sunset, &tz).format("%H:%M:%S"),
+        jd_to_local(day.next_sunrise, &tz).format("%H:%M:%S")
+    );
+
+    println!("┌──────┬───────┬──────────┬──────────┬──────────────┐");
+    println!("│ Hour │       │ Start    │ End      │ Ruler        │");
+    println!("├──────┼───────┼──────────┼──────────┼──────────────┤");
+    for hour in &day.hours {
+        println!("│ {:>4} │ {} │ {} │ {} │ {} {:<10} │",
+            hour.number,
+            if hour.is_day() { "Day  " } else { "Night" },
+            jd_to_local(hour.start_jd, &tz).format("%H:%M:%S"),
+            jd_to_local(hour.end_jd, &tz).format("%H:%M:%S"),
+            body_symbol(hour.ruler),
+            body_name(hour.ruler)
+        );
+    }
+    println!("└──────┴───────┴──────────┴──────────┴──────────────┘");
+    Ok(())
+}
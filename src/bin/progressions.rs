@@ -0,0 +1,137 @@
+use medusa::bodies::{body_name, body_symbol, format_position};
+use medusa::chart::Chart;
+use medusa::cli::flag_value;
+use medusa::coords::GeoLocation;
+use medusa::houses::HouseSystem;
+use medusa::json::{datetime_json, Json};
+use medusa::kernel::Kernel;
+use medusa::output::{chart_records, print_records, Format};
+use medusa::progressions::{Key, ProgressionOptions, Significator};
+use medusa::sidereal::{Ayanamsa, Zodiac};
+use medusa::time::{datetime_to_jd, jd_to_datetime, parse_jd_or_datetime};
+
+fn print_chart(title: &str, chart: &Chart) {
+    println!("\n{}", title);
+    println!("   Asc {}   MC {}", format_position(chart.houses.ascendant), format_position(chart.houses.mc));
+    for (body, pos) in &chart.positions {
+        println!("   {} {:<12} {}  house {}",
+            body_symbol(*body),
+            body_name(*body),
+            format_position(pos.longitude),
+            chart.houses.house_of(pos.longitude)
+        );
+    }
+}
+
+fn significator_name(significator: Significator) -> String {
+    match significator {
+        Significator::Ascendant => "Asc".to_string(),
+        Significator::Mc => "MC".to_string(),
+        Significator::Descendant => "Desc".to_string(),
+        Significator::Ic => "IC".to_string(),
+        Significator::Body(body) => format!("{} {}", body_symbol(body), body_name(body)),
+    }
+}
+
+// Both charts in the shared schema with a "chart" column, then any
+// primary directions.
+fn print_progression_records(kernel: &Kernel, natal_jd: f64, location: GeoLocation, target_jd: f64,
+                             options: &ProgressionOptions, args: &[String], format: Format) -> Result<(), Box<dyn std::error::Error>> {
+    let tagged = |chart: &Chart, name: &str| chart_records(chart).into_iter().map(move |mut record| {
+        record.push("chart", name.into());
+        record
+    }).collect::<Vec<_>>();
+
+    let mut records = tagged(&kernel.progressed_chart(natal_jd, location, target_jd, options)?, "progressed");
+    records.extend(tagged(&kernel.solar_arc_chart(natal_jd, location, target_jd, options)?, "solar_arc"));
+    if let Some(i) = args.iter().position(|a| a == "--primary") {
+        let max_years = args.get(i + 1).and_then(|s| s.parse().ok()).unwrap_or(90.0);
+        for direction in kernel.primary_directions(natal_jd, location, max_years, options)? {
+            records.push(Json::object([
+                ("jd", direction.jd.into()),
+                ("datetime", datetime_json(Some(direction.jd))),
+                ("body", direction.promissor.into()),
+                ("name", body_name(direction.promissor).into()),
+                ("chart", "primary".into()),
+                ("significator", match direction.significator {
+                    Significator::Body(body) => body_name(body).to_string(),
+                    other => significator_name(other),
+                }.into()),
+                ("arc", direction.arc.into()),
+                ("years", direction.years.into()),
+            ]));
+        }
+    }
+    print_records(format, &records)?;
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 2 || flag_value(&args, "--location")?.is_none() {
+        println!("Usage:");
+        println!("  {} <natal jd|datetime> --location lat,lon[,alt] [options]", args[0]);
+        println!("\nOptions:");
+        println!("  --date <jd|datetime>   Progress to this date (default now)");
+        println!("  --key <name>           naibod (default), solar-arc, ptolemy");
+        println!("  -s <ayanamsa>          Sidereal zodiac");
+        println!("  --houses <system>      P (default), K, O, R, E, W");
+        println!("  --primary [years]      List Placidus primary directions (default 90 years)");
+        println!("  --format <format>      table (default), json, csv, ndjson");
+        return Ok(());
+    }
+
+    let natal_jd = parse_jd_or_datetime(&args[1])?;
+    let location = GeoLocation::parse(flag_value(&args, "--location")?.unwrap())?;
+    let target_jd = match flag_value(&args, "--date")? {
+        Some(s) => parse_jd_or_datetime(s)?,
+        None => datetime_to_jd(&chrono::Utc::now()),
+    };
+
+    let mut options = ProgressionOptions::default();
+    if let Some(key) = flag_value(&args, "--key")? {
+        options.key = Key::parse(key)?;
+    }
+    if let Some(mode) = flag_value(&args, "-s")? {
+        options.zodiac = Zodiac::Sidereal(Ayanamsa::parse(mode)?);
+    }
+    if let Some(system) = flag_value(&args, "--houses")? {
+        options.house_system = HouseSystem::parse(system)?;
+    }
+
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+    let kernel = Kernel::open("zenith.kernel")?;
+    if format != Format::Table {
+        return print_progression_records(&kernel, natal_jd, location, target_jd, &options, &args, format);
+    }
+    println!("\n📈 Progressions for {} UTC  ({} key, {})",
+        jd_to_datetime(target_jd).format("%Y-%m-%d"),
+        options.key.name(),
+        options.zodiac.name()
+    );
+
+    let progressed = kernel.progressed_chart(natal_jd, location, target_jd, &options)?;
+    print_chart(&format!("🌱 Secondary progressed (day of {} UTC)",
+        jd_to_datetime(progressed.jd).format("%Y-%m-%d %H:%M")), &progressed);
+
+    let directed = kernel.solar_arc_chart(natal_jd, location, target_jd, &options)?;
+    print_chart("☀️  Solar arc directed", &directed);
+
+    if let Some(i) = args.iter().position(|a| a == "--primary") {
+        let max_years = args.get(i + 1).and_then(|s| s.parse().ok()).unwrap_or(90.0);
+        let directions = kernel.primary_directions(natal_jd, location, max_years, &options)?;
+        println!("\n🧭 Primary directions (Placidus semi-arc, direct)\n");
+        for direction in &directions {
+            println!("   {:>7.3}°  {:>5.1}y  {}  {} {} → {}",
+                direction.arc,
+                direction.years,
+                jd_to_datetime(direction.jd).format("%Y-%m-%d"),
+                body_symbol(direction.promissor),
+                body_name(direction.promissor),
+                significator_name(direction.significator)
+            );
+        }
+    }
+    Ok(())
+}
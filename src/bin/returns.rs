@@ -0,0 +1,85 @@
+use medusa::bodies::{body_name, body_symbol, format_position, MOON, SUN};
+use medusa::cli::flag_value;
+use medusa::coords::GeoLocation;
+use medusa::houses::HouseSystem;
+use medusa::kernel::Kernel;
+use medusa::output::{chart_records, print_records, Format};
+use medusa::returns::{Direction, ReturnOptions};
+use medusa::sidereal::{Ayanamsa, Zodiac};
+use medusa::time::{datetime_to_jd, jd_to_datetime, parse_jd_or_datetime};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    let has = |flag: &str| args.iter().any(|a| a == flag);
+
+    if args.len() < 2 || flag_value(&args, "--location")?.is_none() {
+        println!("Usage:");
+        println!("  {} <natal jd|datetime> --location lat,lon[,alt] [options]", args[0]);
+        println!("\nOptions:");
+        println!("  --moon                     Lunar return (default solar)");
+        println!("  --from <jd|datetime>       Search from here (default now)");
+        println!("  --previous                 Previous return instead of next");
+        println!("  --natal-longitude <deg>    Natal longitude when birth is outside the kernel");
+        println!("  --precession               Precession-corrected tropical return");
+        println!("  -s <ayanamsa>              Sidereal return");
+        println!("  --houses <system>          P (default), K, O, R, E, W");
+        println!("  --format <format>          table (default), json, csv, ndjson");
+        return Ok(());
+    }
+
+    let natal_jd = parse_jd_or_datetime(&args[1])?;
+    let location = GeoLocation::parse(flag_value(&args, "--location")?.unwrap())?;
+    let body = if has("--moon") { MOON } else { SUN };
+    let from = match flag_value(&args, "--from")? {
+        Some(s) => parse_jd_or_datetime(s)?,
+        None => datetime_to_jd(&chrono::Utc::now()),
+    };
+    let direction = if has("--previous") { Direction::Previous } else { Direction::Next };
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+
+    let mut options = ReturnOptions::default();
+    if let Some(mode) = flag_value(&args, "-s")? {
+        options.zodiac = Zodiac::Sidereal(Ayanamsa::parse(mode)?);
+    }
+    if has("--precession") {
+        options.precession_from = Some(natal_jd);
+    }
+    if let Some(system) = flag_value(&args, "--houses")? {
+        options.house_system = HouseSystem::parse(system)?;
+    }
+
+    let kernel = Kernel::open("zenith.kernel")?;
+    let natal_longitude = match flag_value(&args, "--natal-longitude")? {
+        Some(lon) => lon.parse()?,
+        None => kernel.position_in(body, natal_jd, options.zodiac)?.longitude,
+    };
+    let chart = kernel.return_chart(body, natal_longitude, from, direction, location, &options)?;
+    if format != Format::Table {
+        print_records(format, &chart_records(&chart))?;
+        return Ok(());
+    }
+
+    println!("\n🔁 {} return to {} ({})",
+        body_name(body),
+        format_position(natal_longitude),
+        options.zodiac.name()
+    );
+    println!("   {} UTC  (JD {:.6})", jd_to_datetime(chart.jd).format("%Y-%m-%d %H:%M:%S"), chart.jd);
+    println!("   Location {:.4}, {:.4}  {} houses\n", location.latitude, location.longitude, chart.houses.system.name());
+
+    println!("   Asc {}   MC {}\n", format_position(chart.houses.ascendant), format_position(chart.houses.mc));
+    for (body, pos) in &chart.positions {
+        println!("   {} {:<12} {}  {}  house {}",
+            body_symbol(*body),
+            body_name(*body),
+            format_position(pos.longitude),
+            if pos.is_retrograde() { "℞" } else { " " },
+            chart.houses.house_of(pos.longitude)
+        );
+    }
+    println!();
+    for (i, cusp) in chart.houses.cusps.iter().enumerate() {
+        println!("   House {:>2}: {}", i + 1, format_position(*cusp));
+    }
+    Ok(())
+}
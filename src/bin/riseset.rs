@@ -0,0 +1,125 @@
+use std::time::Instant;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use medusa::bodies::{body_from_name, body_name, body_symbol, MOON, SUN};
+use medusa::cli::flag_value;
+use medusa::coords::GeoLocation;
+use medusa::ephemeris::{self, EphemerisSource};
+use medusa::json::Json;
+use medusa::kernel::{Kernel, KernelWriter};
+use medusa::output::{print_records, Format};
+use medusa::riseset::{self, Disc, RiseSetOptions, RISE_SET_TAG};
+use medusa::time::jd_to_local;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 3 || flag_value(&args, "--location")?.is_none() {
+        println!("Usage:");
+        println!("  {} build <from YYYY-MM-DD> <to YYYY-MM-DD> --location lat,lon[,alt] [options]", args[0]);
+        println!("  {} show <YYYY-MM-DD> --location lat,lon[,alt] [--tz Area/City] [--format table|json|csv|ndjson]", args[0]);
+        println!("\nBuild options:");
+        println!("  --body <name>,...      Bodies (default: sun,moon)");
+        println!("  --disc <point>         top (default), center, bottom");
+        println!("  --no-refraction        Geometric horizon");
+        println!("  --pressure <hPa>       Atmospheric pressure (default 1013.25)");
+        println!("  --temperature <°C>     Air temperature (default 10)");
+        println!("  --jpl                  Use DE441 instead of the Swiss files");
+        println!("  --output <file>        Default riseset.kernel");
+        println!("\nExample:");
+        println!("  {} build 2025-01-01 2026-01-01 --location 39.7294,-104.8319", args[0]);
+        return Ok(());
+    }
+
+    let location = GeoLocation::parse(flag_value(&args, "--location")?.unwrap())?;
+    let output = flag_value(&args, "--output")?.unwrap_or("riseset.kernel");
+
+    if args[1] == "show" {
+        let date = NaiveDate::parse_from_str(&args[2], "%Y-%m-%d")?;
+        let tz: Tz = flag_value(&args, "--tz")?.unwrap_or("UTC").parse()?;
+        let kernel = Kernel::open(output)?;
+        let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+        if format != Format::Table {
+            let local = |jd: Option<f64>| jd.map(|jd| jd_to_local(jd, &tz).format("%Y-%m-%dT%H:%M:%S%:z").to_string());
+            let mut records = Vec::new();
+            for &body in kernel.bodies() {
+                let events = kernel.rise_set(body, date, location)?;
+                records.push(Json::object([
+                    ("date", date.to_string().into()),
+                    ("body", body.into()),
+                    ("name", body_name(body).into()),
+                    ("rise", local(events.rise).into()),
+                    ("transit", local(events.transit).into()),
+                    ("set", local(events.set).into()),
+                    ("lower_transit", local(events.lower_transit).into()),
+                    ("rise_jd", events.rise.into()),
+                    ("transit_jd", events.transit.into()),
+                    ("set_jd", events.set.into()),
+                    ("lower_transit_jd", events.lower_transit.into()),
+                ]));
+            }
+            print_records(format, &records)?;
+            return Ok(());
+        }
+        let time = |jd: Option<f64>| jd.map_or("   --   ".to_string(),
+                                             |jd| jd_to_local(jd, &tz).format("%H:%M:%S").to_string());
+
+        println!("\n🌅 {} ({})\n", date.format("%A %Y-%m-%d"), tz);
+        println!("   {:<12} {:>8}  {:>8}  {:>8}  {:>8}", "", "Rise", "Transit", "Set", "Lower");
+        for &body in kernel.bodies() {
+            let events = kernel.rise_set(body, date, location)?;
+            println!("   {} {:<10} {}  {}  {}  {}",
+                body_symbol(body),
+                body_name(body),
+                time(events.rise),
+                time(events.transit),
+                time(events.set),
+                time(events.lower_transit)
+            );
+        }
+        return Ok(());
+    }
+
+    if args[1] != "build" || args.len() < 4 {
+        return Err(format!("unknown command {}", args[1]).into());
+    }
+    let start_time = Instant::now();
+    let from = NaiveDate::parse_from_str(&args[2], "%Y-%m-%d")?;
+    let to = NaiveDate::parse_from_str(&args[3], "%Y-%m-%d")?;
+    if to < from {
+        return Err("<to> must not be before <from>".into());
+    }
+    let days = (to - from).num_days().max(1) as u32;
+
+    let bodies = match flag_value(&args, "--body")? {
+        Some(list) => list.split(',')
+            .map(|name| body_from_name(name).ok_or(format!("unknown body {}", name)))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => vec![SUN, MOON],
+    };
+    let mut options = RiseSetOptions::default();
+    if let Some(disc) = flag_value(&args, "--disc")? {
+        options.disc = Disc::parse(disc)?;
+    }
+    options.refraction = !args.iter().any(|a| a == "--no-refraction");
+    if let Some(pressure) = flag_value(&args, "--pressure")? {
+        options.pressure = pressure.parse()?;
+    }
+    if let Some(temperature) = flag_value(&args, "--temperature")? {
+        options.temperature = temperature.parse()?;
+    }
+    let source = if args.iter().any(|a| a == "--jpl") { EphemerisSource::Jpl } else { EphemerisSource::Swiss };
+
+    println!("🌅 Rise/set tables for {} days from {} at {}, {}",
+        days, from, location.latitude, location.longitude);
+    ephemeris::init(source);
+
+    let section = riseset::build_section(from, days, &bodies, location, &options, source)?;
+    let mut writer = KernelWriter::new(riseset::local_day_start(from, location.longitude), 1.0, &bodies);
+    writer.add_section(RISE_SET_TAG, section);
+    let size = writer.write(output)?;
+
+    println!("\n✨ Completed in {:?}", start_time.elapsed());
+    println!("Size: {} bytes", size);
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use medusa::bodies::{body_from_name, body_name, body_symbol, format_position};
+use medusa::cli::flag_value;
+use medusa::coords::GeoLocation;
+use medusa::json::{sign_json, time_json, Json};
+use medusa::kernel::Kernel;
+use medusa::output::{print_records, Format};
+use medusa::search::SearchOptions;
+use medusa::sidereal::{Ayanamsa, Zodiac};
+use medusa::time::{jd_to_datetime, parse_jd_or_datetime};
+
+// Stars, conjunctions, parans and passes as records told apart by "type".
+fn print_star_records(kernel: &Kernel, jd: f64, zodiac: Zodiac, orb: f64, args: &[String],
+                      format: Format) -> Result<(), Box<dyn std::error::Error>> {
+    let stars = kernel.fixed_stars();
+    let mut records = Vec::new();
+
+    for star in &stars {
+        let pos = kernel.star_position_in(&star.name, jd, zodiac)?;
+        let mut fields = vec![
+            ("type", "star".into()),
+            ("star", star.name.clone().into()),
+            ("magnitude", star.magnitude.into()),
+            ("longitude", pos.longitude.into()),
+            ("latitude", pos.latitude.into()),
+        ];
+        fields.extend(sign_json(pos.longitude));
+        records.push(Json::object(fields));
+    }
+    for c in kernel.star_conjunctions_at(jd, orb)? {
+        records.push(Json::object([
+            ("type", "conjunction".into()),
+            ("star", c.star.into()),
+            ("body", c.body.into()),
+            ("name", body_name(c.body).into()),
+            ("orb", c.orb.into()),
+        ]));
+    }
+    if let Some(location) = flag_value(args, "--location")? {
+        let location = GeoLocation::parse(location)?;
+        let paran_orb: f64 = flag_value(args, "--paran-orb")?.map_or(Ok(1.0), str::parse)?;
+        for p in kernel.parans(jd, location, paran_orb)? {
+            records.push(Json::object([
+                ("type", "paran".into()),
+                ("star", p.star.into()),
+                ("star_event", p.star_event.name().into()),
+                ("body", p.body.into()),
+                ("name", body_name(p.body).into()),
+                ("body_event", p.body_event.name().into()),
+                ("orb", p.orb.into()),
+            ]));
+        }
+    }
+    if let Some(i) = args.iter().position(|a| a == "--find") {
+        let name = args.get(i + 1).ok_or("--find needs a body")?;
+        let body = body_from_name(name).ok_or(format!("unknown body {}", name))?;
+        let to = parse_jd_or_datetime(args.get(i + 2).ok_or("--find needs an end date")?)?;
+        for star in &stars {
+            for hit in kernel.find_star_conjunctions(body, &star.name, jd, to, &SearchOptions::default())? {
+                let mut fields = vec![("type", Json::from("pass")), ("star", star.name.clone().into())];
+                fields.extend(time_json(hit));
+                fields.extend([("body", body.into()), ("name", body_name(body).into())]);
+                records.push(Json::object(fields));
+            }
+        }
+    }
+    print_records(format, &records)?;
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 2 {
+        println!("Usage:");
+        println!("  {} <jd|datetime> [options]", args[0]);
+        println!("\nOptions:");
+        println!("  --orb <degrees>              Conjunction orb (default 1)");
+        println!("  --location lat,lon[,alt]     Also list parans for this place");
+        println!("  --paran-orb <degrees>        Paran orb in sidereal time (default 1, i.e. 4 minutes)");
+        println!("  --find <body> <to>           Times <body> passes each star up to <to>");
+        println!("  -s <ayanamsa>                Sidereal zodiac for listed positions");
+        println!("  --format <format>            table (default), json, csv, ndjson");
+        println!("\nStars come from the generator's --stars option.");
+        return Ok(());
+    }
+
+    let jd = parse_jd_or_datetime(&args[1])?;
+    let orb: f64 = flag_value(&args, "--orb")?.map_or(Ok(1.0), str::parse)?;
+    let zodiac = match flag_value(&args, "-s")? {
+        Some(mode) => Zodiac::Sidereal(Ayanamsa::parse(mode)?),
+        None => Zodiac::Tropical,
+    };
+
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+    let kernel = Kernel::open("zenith.kernel")?;
+    let stars = kernel.fixed_stars();
+    if stars.is_empty() {
+        return Err("this kernel stores no fixed stars; regenerate it with --stars".into());
+    }
+
+    if format != Format::Table {
+        return print_star_records(&kernel, jd, zodiac, orb, &args, format);
+    }
+
+    println!("\n⭐ Fixed stars at {} UTC ({})\n", jd_to_datetime(jd).format("%Y-%m-%d %H:%M:%S"), zodiac.name());
+    for star in &stars {
+        let pos = kernel.star_position_in(&star.name, jd, zodiac)?;
+        println!("   {:<24} {}  lat {:>7.3}°  mag {:>5.2}",
+            star.name, format_position(pos.longitude), pos.latitude, star.magnitude);
+    }
+
+    println!("\n☌ Conjunctions within {}°\n", orb);
+    for c in kernel.star_conjunctions_at(jd, orb)? {
+        println!("   {} {:<10} {:<24} {:+.2}°", body_symbol(c.body), body_name(c.body), c.star, c.orb);
+    }
+
+    if let Some(location) = flag_value(&args, "--location")? {
+        let location = GeoLocation::parse(location)?;
+        let paran_orb: f64 = flag_value(&args, "--paran-orb")?.map_or(Ok(1.0), str::parse)?;
+        println!("\n🌐 Parans at {}, {}\n", location.latitude, location.longitude);
+        for p in kernel.parans(jd, location, paran_orb)? {
+            println!("   {:<24} {:<16} with {} {:<10} {:<16} {:>4.1} min",
+                p.star, p.star_event.name(), body_symbol(p.body), body_name(p.body), p.body_event.name(), p.orb * 4.0);
+        }
+    }
+
+    if let Some(i) = args.iter().position(|a| a == "--find") {
+        let name = args.get(i + 1).ok_or("--find needs a body")?;
+        let body = body_from_name(name).ok_or(format!("unknown body {}", name))?;
+        let to = parse_jd_or_datetime(args.get(i + 2).ok_or("--find needs an end date")?)?;
+        println!("\n📅 {} {} passing stars\n", body_symbol(body), body_name(body));
+        for star in &stars {
+            for hit in kernel.find_star_conjunctions(body, &star.name, jd, to, &SearchOptions::default())? {
+                println!("   {}  {}", jd_to_datetime(hit).format("%Y-%m-%d %H:%M"), star.short_name());
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use medusa::aspects::AspectConfig;
+use medusa::bodies::{body_name, body_symbol, format_position};
+use medusa::chart::Chart;
+use medusa::cli::flag_value;
+use medusa::coords::GeoLocation;
+use medusa::houses::HouseSystem;
+use medusa::json::{aspect_json, overlay_json, Json};
+use medusa::kernel::Kernel;
+use medusa::output::{chart_records, print_records, Format};
+use medusa::sidereal::{Ayanamsa, Zodiac};
+use medusa::synastry::{HouseOverlay, Relationship};
+use medusa::time::{jd_to_datetime, parse_jd_or_datetime};
+
+// Every row tagged with what it is, so one CSV holds the whole comparison
+fn records(relationship: &Relationship) -> Vec<Json> {
+    let tagged = |kind: &str, mut record: Json| {
+        record.push("type", kind.into());
+        record
+    };
+    let synastry = &relationship.synastry;
+    let mut records: Vec<Json> = synastry.aspects.iter()
+        .map(|aspect| tagged("aspect", aspect_json(aspect)))
+        .collect();
+    records.extend(synastry.a_in_b.iter().map(|o| tagged("a_in_b", overlay_json(o))));
+    records.extend(synastry.b_in_a.iter().map(|o| tagged("b_in_a", overlay_json(o))));
+    records.extend(chart_records(&relationship.composite).into_iter().map(|r| tagged("composite", r)));
+    records.extend(chart_records(&relationship.davison).into_iter().map(|r| tagged("davison", r)));
+    records
+}
+
+fn print_overlays(title: &str, overlays: &[HouseOverlay]) {
+    println!("\n🏠 {}", title);
+    for overlay in overlays {
+        println!("   {} {:<12} {}  house {}",
+            body_symbol(overlay.body),
+            body_name(overlay.body),
+            format_position(overlay.longitude),
+            overlay.house
+        );
+    }
+}
+
+fn print_chart(title: &str, chart: &Chart) {
+    println!("\n{}", title);
+    println!("   {} UTC  (JD {:.6})", jd_to_datetime(chart.jd).format("%Y-%m-%d %H:%M:%S"), chart.jd);
+    println!("   Location {:.4}, {:.4}  {} houses", chart.location.latitude, chart.location.longitude, chart.houses.system.name());
+    println!("   Asc {}   MC {}\n", format_position(chart.houses.ascendant), format_position(chart.houses.mc));
+    for (body, pos) in &chart.positions {
+        println!("   {} {:<12} {}  house {}",
+            body_symbol(*body),
+            body_name(*body),
+            format_position(pos.longitude),
+            chart.houses.house_of(pos.longitude)
+        );
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    let has = |flag: &str| args.iter().any(|a| a == flag);
+
+    if args.len() < 5 {
+        println!("Usage:");
+        println!("  {} <jd|datetime A> <lat,lon A> <jd|datetime B> <lat,lon B> [options]", args[0]);
+        println!("\nOptions:");
+        println!("  --houses <system>      P (default), K, O, R, E, W");
+        println!("  -s <ayanamsa>          Sidereal zodiac");
+        println!("  --minor                Include minor aspects");
+        println!("  --format <format>      table (default), json, csv, ndjson");
+        println!("\nExample:");
+        println!("  {} 1990-05-01T08:30:00 51.5,-0.13 1988-11-12T22:10:00 48.86,2.35", args[0]);
+        return Ok(());
+    }
+
+    let a = (parse_jd_or_datetime(&args[1])?, GeoLocation::parse(&args[2])?);
+    let b = (parse_jd_or_datetime(&args[3])?, GeoLocation::parse(&args[4])?);
+    let system = match flag_value(&args, "--houses")? {
+        Some(system) => HouseSystem::parse(system)?,
+        None => HouseSystem::Placidus,
+    };
+    let zodiac = match flag_value(&args, "-s")? {
+        Some(mode) => Zodiac::Sidereal(Ayanamsa::parse(mode)?),
+        None => Zodiac::Tropical,
+    };
+    let mut config = AspectConfig::default();
+    if has("--minor") {
+        config = config.with_minor();
+    }
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+
+    let kernel = Kernel::open("zenith.kernel")?;
+    let relationship = kernel.relationship(a, b, system, zodiac, &config)?;
+    if format != Format::Table {
+        print_records(format, &records(&relationship))?;
+        return Ok(());
+    }
+
+    println!("\n💞 Synastry ({})", zodiac.name());
+    for aspect in &relationship.synastry.aspects {
+        println!("   {} {:<10} {} {:<14} {} {:<10} orb {:.2}° {}",
+            body_symbol(aspect.body_a),
+            body_name(aspect.body_a),
+            aspect.kind.symbol,
+            aspect.kind.name,
+            body_symbol(aspect.body_b),
+            body_name(aspect.body_b),
+            aspect.orb,
+            if aspect.applying { "applying" } else { "separating" }
+        );
+    }
+    print_overlays("A's bodies in B's houses", &relationship.synastry.a_in_b);
+    print_overlays("B's bodies in A's houses", &relationship.synastry.b_in_a);
+    print_chart("⚭ Composite (midpoints)", &relationship.composite);
+    print_chart("🕰  Davison (midpoint in time and space)", &relationship.davison);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use medusa::aspects::{major_aspects, minor_aspects};
+use medusa::bodies::{body_from_name, body_name, body_symbol, format_position};
+use medusa::cli::flag_value;
+use medusa::json::{time_json, Json};
+use medusa::kernel::Kernel;
+use medusa::output::{print_records, Format};
+use medusa::search::{AspectHit, Refinement, SearchOptions, Target};
+use medusa::time::{jd_to_datetime, parse_jd_or_datetime, parse_step};
+
+fn parse_aspect(name: &str) -> Result<f64, String> {
+    if let Ok(angle) = name.parse::<f64>() {
+        return Ok(angle);
+    }
+    major_aspects().into_iter()
+        .chain(minor_aspects())
+        .find(|kind| kind.name.eq_ignore_ascii_case(name))
+        .map(|kind| kind.angle)
+        .ok_or_else(|| format!("unknown aspect {}", name))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 3 || flag_value(&args, "--body")?.is_none() {
+        println!("Usage:");
+        println!("  {} <from> <to> --body <name> [--aspect <name|angle>,...] [target]", args[0]);
+        println!("\nTargets:");
+        println!("  --natal <jd> --natal-body <name>   Transit to natal position");
+        println!("  --natal-longitude <deg>            Transit to a fixed longitude");
+        println!("  --to-body <name>                   Transit to transit");
+        println!("\nOptions:");
+        println!("  --step <step>       Scan step: days, or 6h, 30m (default 0.25)");
+        println!("  --bisection         Refine by bisection instead of Newton");
+        println!("  --format <format>   table (default), json, csv, ndjson");
+        println!("\nExample:");
+        println!("  {} 2024-01-01 2026-01-01 --body saturn --aspect square --natal 2451545.0 --natal-body sun", args[0]);
+        return Ok(());
+    }
+
+    let from = parse_jd_or_datetime(&args[1])?;
+    let to = parse_jd_or_datetime(&args[2])?;
+    let lookup = |name: &str| body_from_name(name).ok_or(format!("unknown body {}", name));
+    let body = lookup(flag_value(&args, "--body")?.unwrap())?;
+    let angles = flag_value(&args, "--aspect")?.unwrap_or("conjunction")
+        .split(',')
+        .map(parse_aspect)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let format = Format::parse(flag_value(&args, "--format")?.unwrap_or("table"))?;
+    let kernel = Kernel::open("zenith.kernel")?;
+    let target = if let Some(other) = flag_value(&args, "--to-body")? {
+        Target::Body(lookup(other)?)
+    } else if let Some(lon) = flag_value(&args, "--natal-longitude")? {
+        Target::Natal(lon.parse()?)
+    } else if let Some(natal) = flag_value(&args, "--natal")? {
+        let natal_body = lookup(flag_value(&args, "--natal-body")?.unwrap_or("sun"))?;
+        let natal_jd = parse_jd_or_datetime(natal)?;
+        Target::Natal(kernel.position_at(natal_body, natal_jd)?.longitude)
+    } else {
+        return Err("need --natal, --natal-longitude or --to-body".into());
+    };
+
+    let mut options = SearchOptions::default();
+    if let Some(step) = flag_value(&args, "--step")? {
+        options.scan_step = parse_step(step)?;
+    }
+    if args.iter().any(|a| a == "--bisection") {
+        options.refinement = Refinement::Bisection;
+    }
+
+    let mut hits: Vec<AspectHit> = Vec::new();
+    for angle in angles {
+        hits.extend(kernel.find_aspect_hits(body, target, angle, from, to, &options)?);
+    }
+    hits.sort_by(|a, b| a.jd.total_cmp(&b.jd));
+
+    if format != Format::Table {
+        let records: Vec<Json> = hits.iter()
+            .map(|hit| {
+                let mut fields = time_json(hit.jd);
+                fields.extend([
+                    ("body", hit.body.into()),
+                    ("name", body_name(hit.body).into()),
+                ]);
+                match hit.target {
+                    Target::Natal(lon) => fields.extend([("target", "natal".into()), ("target_longitude", lon.into())]),
+                    Target::Body(other) => fields.extend([("target", body_name(other).into()), ("target_body", other.into())]),
+                }
+                fields.extend([("angle", hit.angle.into()), ("retrograde", hit.body_retrograde.into())]);
+                Json::object(fields)
+            })
+            .collect();
+        print_records(format, &records)?;
+        return Ok(());
+    }
+
+    let target_label = match target {
+        Target::Natal(lon) => format!("natal {}", format_position(lon)),
+        Target::Body(other) => format!("{} {}", body_symbol(other), body_name(other)),
+    };
+    println!("\n🔭 {} {} → {}: {} hits\n", body_symbol(body), body_name(body), target_label, hits.len());
+    for hit in &hits {
+        println!("  {} UTC  {:>7.2}°  {}",
+            jd_to_datetime(hit.jd).format("%Y-%m-%d %H:%M:%S"),
+            hit.angle,
+            if hit.body_retrograde { "℞" } else { "" }
+        );
+    }
+    Ok(())
+}
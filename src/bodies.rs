@@ -0,0 +1,126 @@
+use crate::{SE_AST_OFFSET, SE_CERES, SE_CHIRON, SE_EARTH, SE_JUNO, SE_JUPITER, SE_MARS,
+            SE_MEAN_APOG, SE_MEAN_NODE, SE_MERCURY, SE_MOON, SE_NEPTUNE, SE_PALLAS, SE_PLUTO,
+            SE_SATURN, SE_SUN, SE_TRUE_NODE, SE_URANUS, SE_VENUS, SE_VESTA};
+
+pub const SUN: i32 = SE_SUN as i32;
+pub const MOON: i32 = SE_MOON as i32;
+pub const MERCURY: i32 = SE_MERCURY as i32;
+pub const VENUS: i32 = SE_VENUS as i32;
+pub const MARS: i32 = SE_MARS as i32;
+pub const JUPITER: i32 = SE_JUPITER as i32;
+pub const SATURN: i32 = SE_SATURN as i32;
+pub const URANUS: i32 = SE_URANUS as i32;
+pub const NEPTUNE: i32 = SE_NEPTUNE as i32;
+pub const PLUTO: i32 = SE_PLUTO as i32;
+pub const MEAN_NODE: i32 = SE_MEAN_NODE as i32;
+pub const TRUE_NODE: i32 = SE_TRUE_NODE as i32;
+pub const MEAN_APOGEE: i32 = SE_MEAN_APOG as i32;
+pub const CHIRON: i32 = SE_CHIRON as i32;
+pub const CERES: i32 = SE_CERES as i32;
+pub const PALLAS: i32 = SE_PALLAS as i32;
+pub const JUNO: i32 = SE_JUNO as i32;
+pub const VESTA: i32 = SE_VESTA as i32;
+pub const ASTEROID_5550: i32 = (SE_AST_OFFSET + 5550) as i32;
+// Stored in place of the Sun by heliocentric kernels.
+pub const EARTH: i32 = SE_EARTH as i32;
+// Not a Swiss Ephemeris body; derived from TRUE_NODE by readers.
+pub const SOUTH_NODE: i32 = -TRUE_NODE;
+
+// Same order the original generators used, minus ASC/ARMC which are
+// location dependent and handled separately.
+pub const DEFAULT_BODIES: [i32; 18] = [
+    SUN, MOON, MERCURY, VENUS, MARS,
+    JUPITER, SATURN, URANUS, NEPTUNE, PLUTO,
+    CHIRON, TRUE_NODE, MEAN_APOGEE,
+    VESTA, JUNO, CERES, PALLAS, ASTEROID_5550,
+];
+
+pub const SIGNS: [&str; 12] = ["♈", "♉", "♊", "♋", "♌", "♍", "♎", "♏", "♐", "♑", "♒", "♓"];
+
+pub const SIGN_NAMES: [&str; 12] = [
+    "Aries", "Taurus", "Gemini", "Cancer", "Leo", "Virgo",
+    "Libra", "Scorpio", "Sagittarius", "Capricorn", "Aquarius", "Pisces",
+];
+
+pub fn body_name(body: i32) -> &'static str {
+    match body {
+        SUN => "Sun",
+        MOON => "Moon",
+        MERCURY => "Mercury",
+        VENUS => "Venus",
+        MARS => "Mars",
+        JUPITER => "Jupiter",
+        SATURN => "Saturn",
+        URANUS => "Uranus",
+        NEPTUNE => "Neptune",
+        PLUTO => "Pluto",
+        MEAN_NODE => "Mean Node",
+        TRUE_NODE => "True Node",
+        SOUTH_NODE => "South Node",
+        MEAN_APOGEE => "Mean Apogee",
+        CHIRON => "Chiron",
+        CERES => "Ceres",
+        PALLAS => "Pallas",
+        JUNO => "Juno",
+        VESTA => "Vesta",
+        ASTEROID_5550 => "15550",
+        EARTH => "Earth",
+        _ => "Unknown",
+    }
+}
+
+/// Looks a body up by name ("saturn", "true node") or Swiss Ephemeris number.
+pub fn body_from_name(name: &str) -> Option<i32> {
+    if let Ok(id) = name.parse::<i32>() {
+        return Some(id);
+    }
+    let wanted = name.to_ascii_lowercase().replace(['_', '-'], " ");
+    DEFAULT_BODIES.iter()
+        .chain([MEAN_NODE, SOUTH_NODE, EARTH].iter())
+        .copied()
+        .find(|&body| body_name(body).to_ascii_lowercase() == wanted)
+}
+
+pub fn body_symbol(body: i32) -> &'static str {
+    match body {
+        SUN => "☉",
+        MOON => "☽",
+        MERCURY => "☿",
+        VENUS => "♀",
+        MARS => "♂",
+        JUPITER => "♃",
+        SATURN => "♄",
+        URANUS => "♅",
+        NEPTUNE => "♆",
+        PLUTO => "⯓",
+        MEAN_NODE | TRUE_NODE => "☊",
+        SOUTH_NODE => "☋",
+        MEAN_APOGEE => "⚸",
+        CHIRON => "⚷",
+        CERES => "⚳",
+        PALLAS => "⚴",
+        JUNO => "⚵",
+        VESTA => "⚶",
+        EARTH => "⊕",
+        _ => "☄︎",
+    }
+}
+
+pub fn sign_index(longitude: f64) -> usize {
+    (longitude.rem_euclid(360.0) / 30.0) as usize % 12
+}
+
+pub fn decimal_to_dms(decimal_degrees: f64) -> (i32, i32, i32) {
+    let total_seconds = (decimal_degrees * 3600.0).round() as i32;
+    let degrees = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = (total_seconds % 3600) % 60;
+    (degrees, minutes, seconds)
+}
+
+pub fn format_position(deg: f64) -> String {
+    let (degrees, minutes, seconds) = decimal_to_dms(deg.rem_euclid(360.0));
+    let sign_num = ((degrees % 360) / 30) as usize;
+    let sign_deg = degrees % 30;
+    format!("{}{}°{:02}'{:02}\"", SIGNS[sign_num], sign_deg, minutes, seconds)
+}
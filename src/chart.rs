@@ -0,0 +1,39 @@
+use crate::coords::GeoLocation;
+use crate::houses::{HouseSystem, Houses};
+use crate::kernel::{Kernel, KernelError, Position};
+use crate::sidereal::Zodiac;
+
+/// Positions and houses for one moment and place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    pub jd: f64,
+    pub location: GeoLocation,
+    pub zodiac: Zodiac,
+    pub positions: Vec<(i32, Position)>,
+    pub houses: Houses,
+}
+
+impl Chart {
+    pub fn position(&self, body: i32) -> Option<&Position> {
+        self.positions.iter().find(|(b, _)| *b == body).map(|(_, pos)| pos)
+    }
+
+    pub fn house_of(&self, body: i32) -> Option<usize> {
+        self.position(body).map(|pos| self.houses.house_of(pos.longitude))
+    }
+}
+
+impl Kernel {
+    pub fn chart(&self, jd: f64, location: GeoLocation, system: HouseSystem,
+                 zodiac: Zodiac) -> Result<Chart, KernelError> {
+        // Houses are for an observer on Earth
+        self.require_earth_frame()?;
+        Ok(Chart {
+            jd,
+            location,
+            zodiac,
+            positions: self.positions_in(jd, zodiac)?,
+            houses: self.houses_in(jd, location, system, zodiac)?,
+        })
+    }
+}
@@ -0,0 +1,70 @@
+// Command-line flags as the bins take them: `--flag value` pairs and bare
+// switches, anywhere after the program name.
+
+/// The value after `flag`, or None when the flag is not given. A flag that
+/// ends the line or is followed by another `--flag` is an error rather than
+/// silently ignored.
+pub fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a str>, String> {
+    match args.iter().position(|a| a == flag) {
+        Some(i) => match args.get(i + 1) {
+            Some(value) if !value.starts_with("--") => Ok(Some(value)),
+            _ => Err(format!("{} needs a value", flag)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Like `flag_value`, but removes the flag and its value from `args`.
+pub fn take_value(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, String> {
+    let value = flag_value(args, flag)?.map(str::to_string);
+    if value.is_some() {
+        let i = args.iter().position(|a| a == flag).unwrap();
+        args.drain(i..i + 2);
+    }
+    Ok(value)
+}
+
+/// Removes `flag` from `args`, reporting whether it was there.
+pub fn take_switch(args: &mut Vec<String>, flag: &str) -> bool {
+    match args.iter().position(|a| a == flag) {
+        Some(i) => {
+            args.remove(i);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn reads_flag_values() {
+        let args = args("dasha 2000-01-01 --levels 3 -s lahiri --lon -105.5");
+        assert_eq!(flag_value(&args, "--levels"), Ok(Some("3")));
+        assert_eq!(flag_value(&args, "-s"), Ok(Some("lahiri")));
+        assert_eq!(flag_value(&args, "--lon"), Ok(Some("-105.5")));
+        assert_eq!(flag_value(&args, "--format"), Ok(None));
+    }
+
+    #[test]
+    fn reports_a_flag_without_its_value() {
+        assert_eq!(flag_value(&args("dasha 2000-01-01 --levels"), "--levels"), Err("--levels needs a value".to_string()));
+        assert!(flag_value(&args("dasha --levels --format json"), "--levels").is_err());
+    }
+
+    #[test]
+    fn takes_values_and_switches_out_of_the_line() {
+        let mut line = args("paraboladb --format json now --utc");
+        assert_eq!(take_value(&mut line, "--format"), Ok(Some("json".to_string())));
+        assert!(take_switch(&mut line, "--utc"));
+        assert!(!take_switch(&mut line, "--utc"));
+        assert_eq!(line, args("paraboladb now"));
+        assert!(take_value(&mut args("paraboladb --format"), "--format").is_err());
+    }
+}
@@ -0,0 +1,91 @@
+// Small coordinate helpers that need no ephemeris.
+
+pub const J2000: f64 = 2451545.0;
+
+/// Mean obliquity of the ecliptic (IAU 2006), degrees.
+pub fn mean_obliquity(jd: f64) -> f64 {
+    let t = (jd - J2000) / 36525.0;
+    let arcsec = 84381.406 - 46.836769 * t - 0.0001831 * t.powi(2)
+        + 0.00200340 * t.powi(3) - 0.000000576 * t.powi(4) - 0.0000000434 * t.powi(5);
+    arcsec / 3600.0
+}
+
+/// General precession in longitude (IAU 2006), degrees since J2000.
+pub fn precession(jd: f64) -> f64 {
+    let t = (jd - J2000) / 36525.0;
+    let arcsec = 5028.796195 * t + 1.1054348 * t.powi(2) + 0.00007964 * t.powi(3)
+        - 0.000023857 * t.powi(4) - 0.0000000383 * t.powi(5);
+    arcsec / 3600.0
+}
+
+/// Greenwich mean sidereal time (IAU 1982), degrees.
+pub fn sidereal_time(jd_ut: f64) -> f64 {
+    let d = jd_ut - J2000;
+    let t = d / 36525.0;
+    (280.46061837 + 360.98564736629 * d + 0.000387933 * t.powi(2) - t.powi(3) / 38710000.0).rem_euclid(360.0)
+}
+
+/// Declination from ecliptic longitude/latitude.
+pub fn declination(longitude: f64, latitude: f64, obliquity: f64) -> f64 {
+    let (lon, lat, eps) = (longitude.to_radians(), latitude.to_radians(), obliquity.to_radians());
+    (lat.sin() * eps.cos() + lat.cos() * eps.sin() * lon.sin()).asin().to_degrees()
+}
+
+/// Right ascension from ecliptic longitude/latitude, degrees in 0..360.
+pub fn right_ascension(longitude: f64, latitude: f64, obliquity: f64) -> f64 {
+    let (lon, lat, eps) = (longitude.to_radians(), latitude.to_radians(), obliquity.to_radians());
+    let y = lon.sin() * eps.cos() - lat.tan() * eps.sin();
+    y.atan2(lon.cos()).to_degrees().rem_euclid(360.0)
+}
+
+/// Diurnal semi-arc in degrees of right ascension; None for circumpolar
+/// points.
+pub fn semi_arc(declination: f64, latitude: f64) -> Option<f64> {
+    let x = -latitude.to_radians().tan() * declination.to_radians().tan();
+    (x.abs() < 1.0).then(|| x.acos().to_degrees())
+}
+
+/// Signed shortest arc from `from` to `to`, in (-180, 180].
+pub fn arc(from: f64, to: f64) -> f64 {
+    let d = (to - from).rem_euclid(360.0);
+    if d > 180.0 { d - 360.0 } else { d }
+}
+
+/// A place on Earth: longitude east-positive, latitude north-positive,
+/// altitude in metres.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoLocation {
+    pub longitude: f64,
+    pub latitude: f64,
+    pub altitude: f64,
+}
+
+impl GeoLocation {
+    pub fn new(latitude: f64, longitude: f64, altitude: f64) -> Self {
+        Self { longitude, latitude, altitude }
+    }
+
+    /// Parses "lat,lon" or "lat,lon,alt", with the latitude in ±90 and the
+    /// longitude in ±180.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let parts = s.split(',')
+            .map(|p| p.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("expected lat,lon[,alt], got {}", s))?;
+        let location = match parts[..] {
+            [latitude, longitude] => Self::new(latitude, longitude, 0.0),
+            [latitude, longitude, altitude] => Self::new(latitude, longitude, altitude),
+            _ => return Err(format!("expected lat,lon[,alt], got {}", s)),
+        };
+        if !(-90.0..=90.0).contains(&location.latitude) || !(-180.0..=180.0).contains(&location.longitude)
+            || !location.altitude.is_finite() {
+            return Err(format!("location out of range (latitude ±90, longitude ±180): {}", s));
+        }
+        Ok(location)
+    }
+
+    /// Longitude, latitude, altitude: the order Swiss Ephemeris expects.
+    pub fn geopos(&self) -> [f64; 3] {
+        [self.longitude, self.latitude, self.altitude]
+    }
+}
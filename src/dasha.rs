@@ -0,0 +1,291 @@
+use crate::bodies::MOON;
+use crate::kernel::{Kernel, KernelError};
+use crate::sidereal::Ayanamsa;
+use crate::vedic::{Graha, Nakshatra, NAKSHATRA_SPAN};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashaSystem {
+    Vimshottari,
+    Yogini,
+    Ashtottari,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum YearLength {
+    /// 365.25 days
+    Julian,
+    /// 360 days
+    Savana,
+    Days(f64),
+}
+
+impl YearLength {
+    pub fn days(&self) -> f64 {
+        match self {
+            YearLength::Julian => 365.25,
+            YearLength::Savana => 360.0,
+            YearLength::Days(days) => *days,
+        }
+    }
+}
+
+/// Deepest level `timeline` builds. Each level multiplies the periods by
+/// the length of the sequence, so deeper levels quickly run to millions.
+pub const MAX_LEVELS: u8 = 3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DashaOptions {
+    pub system: DashaSystem,
+    pub year_length: YearLength,
+    /// 1 = mahadasha only, 2 = with antardasha, 3 = with pratyantardasha
+    pub levels: u8,
+    /// How many years after birth the timeline must cover.
+    pub span_years: f64,
+}
+
+impl Default for DashaOptions {
+    fn default() -> Self {
+        Self {
+            system: DashaSystem::Vimshottari,
+            year_length: YearLength::Julian,
+            levels: 2,
+            span_years: 120.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DashaPeriod {
+    pub lord: Graha,
+    /// 1 = mahadasha, 2 = antardasha, 3 = pratyantardasha
+    pub level: u8,
+    pub start_jd: f64,
+    pub end_jd: f64,
+    pub sub_periods: Vec<DashaPeriod>,
+}
+
+const VIMSHOTTARI: [(Graha, f64); 9] = [
+    (Graha::Ketu, 7.0), (Graha::Venus, 20.0), (Graha::Sun, 6.0),
+    (Graha::Moon, 10.0), (Graha::Mars, 7.0), (Graha::Rahu, 18.0),
+    (Graha::Jupiter, 16.0), (Graha::Saturn, 19.0), (Graha::Mercury, 17.0),
+];
+
+const YOGINI: [(Graha, f64); 8] = [
+    (Graha::Moon, 1.0), (Graha::Sun, 2.0), (Graha::Jupiter, 3.0), (Graha::Mars, 4.0),
+    (Graha::Mercury, 5.0), (Graha::Saturn, 6.0), (Graha::Venus, 7.0), (Graha::Rahu, 8.0),
+];
+
+pub const YOGINI_NAMES: [&str; 8] = [
+    "Mangala", "Pingala", "Dhanya", "Bhramari", "Bhadrika", "Ulka", "Siddha", "Sankata",
+];
+
+const ASHTOTTARI: [(Graha, f64); 8] = [
+    (Graha::Sun, 6.0), (Graha::Moon, 15.0), (Graha::Mars, 8.0), (Graha::Mercury, 17.0),
+    (Graha::Saturn, 10.0), (Graha::Jupiter, 19.0), (Graha::Rahu, 12.0), (Graha::Venus, 21.0),
+];
+
+// Nakshatras per Ashtottari lord, counted from Ardra. Saturn's group also
+// holds Abhijit, which has no span of its own here.
+const ASHTOTTARI_GROUPS: [usize; 8] = [4, 3, 4, 3, 3, 3, 4, 3];
+const ARDRA: usize = 5;
+
+impl DashaSystem {
+    fn sequence(&self) -> &'static [(Graha, f64)] {
+        match self {
+            DashaSystem::Vimshottari => &VIMSHOTTARI,
+            DashaSystem::Yogini => &YOGINI,
+            DashaSystem::Ashtottari => &ASHTOTTARI,
+        }
+    }
+
+    pub fn total_years(&self) -> f64 {
+        self.sequence().iter().map(|(_, years)| years).sum()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DashaSystem::Vimshottari => "Vimshottari",
+            DashaSystem::Yogini => "Yogini",
+            DashaSystem::Ashtottari => "Ashtottari",
+        }
+    }
+
+    /// Index into the sequence of the dasha running at birth, and the
+    /// fraction of it already elapsed.
+    fn starting_point(&self, moon_longitude: f64) -> (usize, f64) {
+        let nakshatra = Nakshatra::from_longitude(moon_longitude);
+        match self {
+            DashaSystem::Vimshottari => (nakshatra.index % 9, nakshatra.elapsed),
+            // (nakshatra number + 3) mod 8, counted from Mangala
+            DashaSystem::Yogini => ((nakshatra.index + 3) % 8, nakshatra.elapsed),
+            DashaSystem::Ashtottari => {
+                // Degrees travelled since Ardra, then find the group
+                let lon = moon_longitude.rem_euclid(360.0);
+                let from_ardra = (lon - ARDRA as f64 * NAKSHATRA_SPAN).rem_euclid(360.0);
+                let mut group_start = 0.0;
+                for (i, &size) in ASHTOTTARI_GROUPS.iter().enumerate() {
+                    let span = size as f64 * NAKSHATRA_SPAN;
+                    if from_ardra < group_start + span || i == ASHTOTTARI_GROUPS.len() - 1 {
+                        return (i, ((from_ardra - group_start) / span).min(1.0));
+                    }
+                    group_start += span;
+                }
+                unreachable!()
+            }
+        }
+    }
+}
+
+pub fn yogini_name(lord: Graha) -> Option<&'static str> {
+    YOGINI.iter().position(|(g, _)| *g == lord).map(|i| YOGINI_NAMES[i])
+}
+
+/// Dasha timeline from the natal Moon's sidereal longitude.
+pub fn timeline(moon_longitude: f64, birth_jd: f64, options: &DashaOptions) -> Result<Vec<DashaPeriod>, KernelError> {
+    if !(1..=MAX_LEVELS).contains(&options.levels) {
+        return Err(KernelError::BadDashaLevels(options.levels));
+    }
+    let sequence = options.system.sequence();
+    let year = options.year_length.days();
+    let (mut index, elapsed) = options.system.starting_point(moon_longitude);
+
+    // The first period began before birth
+    let mut start = birth_jd - elapsed * sequence[index].1 * year;
+    let end_of_span = birth_jd + options.span_years * year;
+    let mut periods = Vec::new();
+
+    while start < end_of_span {
+        let (lord, years) = sequence[index];
+        let end = start + years * year;
+        periods.push(DashaPeriod {
+            lord,
+            level: 1,
+            start_jd: start,
+            end_jd: end,
+            sub_periods: sub_periods(options.system, index, start, end, 2, options.levels),
+        });
+        start = end;
+        index = (index + 1) % sequence.len();
+    }
+    Ok(periods)
+}
+
+// Each sub-period starts with the parent's lord and takes the parent's
+// length in proportion to its own years.
+fn sub_periods(system: DashaSystem, parent: usize, start: f64, end: f64,
+               level: u8, max_level: u8) -> Vec<DashaPeriod> {
+    if level > max_level {
+        return Vec::new();
+    }
+    let sequence = system.sequence();
+    let total = system.total_years();
+    let length = end - start;
+    let mut at = start;
+
+    (0..sequence.len())
+        .map(|k| {
+            let index = (parent + k) % sequence.len();
+            let (lord, years) = sequence[index];
+            let sub_end = at + length * years / total;
+            let period = DashaPeriod {
+                lord,
+                level,
+                start_jd: at,
+                end_jd: sub_end,
+                sub_periods: sub_periods(system, index, at, sub_end, level + 1, max_level),
+            };
+            at = sub_end;
+            period
+        })
+        .collect()
+}
+
+/// The chain of periods (maha, antar, ...) running at `jd`.
+pub fn running_at(periods: &[DashaPeriod], jd: f64) -> Vec<&DashaPeriod> {
+    let mut chain = Vec::new();
+    let mut level = periods;
+    while let Some(period) = level.iter().find(|p| p.start_jd <= jd && jd < p.end_jd) {
+        chain.push(period);
+        level = &period.sub_periods;
+    }
+    chain
+}
+
+impl Kernel {
+    pub fn dasha_timeline(&self, birth_jd: f64, mode: Ayanamsa, options: &DashaOptions) -> Result<Vec<DashaPeriod>, KernelError> {
+        let moon = self.sidereal_position_at(MOON, birth_jd, mode)?;
+        timeline(moon.longitude, birth_jd, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BIRTH: f64 = 2451545.0;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn vimshottari_balance_at_birth() {
+        let options = DashaOptions::default();
+        // Moon at the start of Ashwini: the whole Ketu dasha is still to run
+        let periods = timeline(0.0, BIRTH, &options).unwrap();
+        assert_eq!(periods[0].lord, Graha::Ketu);
+        assert!(close(periods[0].start_jd, BIRTH));
+        assert!(close(periods[0].end_jd, BIRTH + 7.0 * 365.25));
+        assert_eq!(periods[1].lord, Graha::Venus);
+
+        // Halfway through Bharani, half of Venus' 20 years are gone
+        let periods = timeline(1.5 * NAKSHATRA_SPAN, BIRTH, &options).unwrap();
+        assert_eq!(periods[0].lord, Graha::Venus);
+        assert!(close(periods[0].start_jd, BIRTH - 10.0 * 365.25));
+        assert!(periods.last().unwrap().end_jd >= BIRTH + 120.0 * 365.25);
+        assert_eq!(DashaSystem::Vimshottari.total_years(), 120.0);
+    }
+
+    #[test]
+    fn sub_periods_split_the_parent() {
+        let options = DashaOptions { levels: 3, ..DashaOptions::default() };
+        let periods = timeline(100.0, BIRTH, &options).unwrap();
+        for maha in &periods {
+            assert_eq!(maha.sub_periods.len(), 9);
+            assert_eq!(maha.sub_periods[0].lord, maha.lord);
+            assert!(close(maha.sub_periods[0].start_jd, maha.start_jd));
+            assert!(close(maha.sub_periods[8].end_jd, maha.end_jd));
+            let antar = &maha.sub_periods[1];
+            assert_eq!(antar.sub_periods[0].lord, antar.lord);
+            assert!(close(antar.sub_periods[8].end_jd, antar.end_jd));
+        }
+
+        let chain = running_at(&periods, BIRTH + 1000.0);
+        assert_eq!(chain.iter().map(|p| p.level).collect::<Vec<_>>(), [1, 2, 3]);
+        let mahadasha_only = DashaOptions { levels: 1, ..DashaOptions::default() };
+        assert!(timeline(100.0, BIRTH, &mahadasha_only).unwrap().iter().all(|p| p.sub_periods.is_empty()));
+
+        for levels in [0, MAX_LEVELS + 1, 8] {
+            let options = DashaOptions { levels, ..DashaOptions::default() };
+            assert!(matches!(timeline(100.0, BIRTH, &options), Err(KernelError::BadDashaLevels(l)) if l == levels));
+        }
+    }
+
+    #[test]
+    fn yogini_and_ashtottari_starting_points() {
+        // Ashwini is nakshatra 1: (1 + 3) mod 8 = 4th yogini, Bhramari
+        let yogini = DashaOptions { system: DashaSystem::Yogini, ..DashaOptions::default() };
+        let periods = timeline(0.0, BIRTH, &yogini).unwrap();
+        assert_eq!(yogini_name(periods[0].lord), Some("Bhramari"));
+        assert!(close(periods[0].end_jd - periods[0].start_jd, 4.0 * 365.25));
+
+        // Ashtottari counts from Ardra, where the Sun's group begins
+        let ashtottari = DashaOptions { system: DashaSystem::Ashtottari, year_length: YearLength::Savana,
+                                        ..DashaOptions::default() };
+        let periods = timeline(5.0 * NAKSHATRA_SPAN, BIRTH, &ashtottari).unwrap();
+        assert_eq!(periods[0].lord, Graha::Sun);
+        assert!(close(periods[0].start_jd, BIRTH));
+        assert!(close(periods[0].end_jd, BIRTH + 6.0 * 360.0));
+        assert_eq!(periods[1].lord, Graha::Moon);
+    }
+}
@@ -0,0 +1,377 @@
+use crate::coords::GeoLocation;
+use crate::ephemeris::{self, EphemerisSource};
+use crate::kernel::{read_f64, read_i32, read_u32, Kernel, KernelError};
+use crate::{SE_ECL_ANNULAR, SE_ECL_CENTRAL, SE_ECL_HYBRID, SE_ECL_MAX_VISIBLE, SE_ECL_PARTIAL,
+            SE_ECL_PENUMBRAL, SE_ECL_TOTAL, SE_ECL_VISIBLE};
+
+pub const ECLIPSE_TAG: [u8; 4] = *b"ECLI";
+const RECORD_SIZE: usize = 96;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EclipseKind {
+    Solar,
+    Lunar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EclipseType {
+    Total,
+    Annular,
+    /// Annular-total
+    Hybrid,
+    Partial,
+    Penumbral,
+}
+
+impl EclipseType {
+    pub fn from_flags(flags: i32) -> Self {
+        let flags = flags as u32;
+        if flags & SE_ECL_TOTAL != 0 {
+            EclipseType::Total
+        } else if flags & SE_ECL_HYBRID != 0 {
+            EclipseType::Hybrid
+        } else if flags & SE_ECL_ANNULAR != 0 {
+            EclipseType::Annular
+        } else if flags & SE_ECL_PENUMBRAL != 0 && flags & SE_ECL_PARTIAL == 0 {
+            EclipseType::Penumbral
+        } else {
+            EclipseType::Partial
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EclipseType::Total => "Total",
+            EclipseType::Annular => "Annular",
+            EclipseType::Hybrid => "Hybrid",
+            EclipseType::Partial => "Partial",
+            EclipseType::Penumbral => "Penumbral",
+        }
+    }
+}
+
+/// Contact times as JD UT; None where the phase does not occur.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Contacts {
+    /// First and last contact: partial phase for solar eclipses, penumbral
+    /// for lunar ones.
+    pub begin: Option<f64>,
+    pub end: Option<f64>,
+    /// Umbral partial phase (lunar only).
+    pub partial_begin: Option<f64>,
+    pub partial_end: Option<f64>,
+    /// Totality, or annularity for annular solar eclipses.
+    pub total_begin: Option<f64>,
+    pub total_end: Option<f64>,
+    /// Central line on Earth (solar only).
+    pub center_begin: Option<f64>,
+    pub center_end: Option<f64>,
+}
+
+impl Contacts {
+    fn to_array(self) -> [f64; 8] {
+        [self.begin, self.end, self.partial_begin, self.partial_end,
+         self.total_begin, self.total_end, self.center_begin, self.center_end]
+            .map(|t| t.unwrap_or(0.0))
+    }
+
+    fn from_array(times: [f64; 8]) -> Self {
+        let t = |i: usize| (times[i] != 0.0).then_some(times[i]);
+        Self {
+            begin: t(0),
+            end: t(1),
+            partial_begin: t(2),
+            partial_end: t(3),
+            total_begin: t(4),
+            total_end: t(5),
+            center_begin: t(6),
+            center_end: t(7),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eclipse {
+    pub kind: EclipseKind,
+    pub eclipse_type: EclipseType,
+    /// SE_ECL_* flags as returned by Swiss Ephemeris.
+    pub flags: i32,
+    pub maximum_jd: f64,
+    /// Fraction of the solar diameter covered, or umbral magnitude for lunar
+    /// eclipses (penumbral magnitude for penumbral ones).
+    pub magnitude: f64,
+    pub saros_series: i32,
+    pub saros_member: i32,
+    pub contacts: Contacts,
+}
+
+impl Eclipse {
+    pub fn is_central(&self) -> bool {
+        self.flags as u32 & SE_ECL_CENTRAL != 0
+    }
+}
+
+/// A solar eclipse as seen from one place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocalEclipse {
+    pub eclipse_type: EclipseType,
+    pub flags: i32,
+    pub maximum_jd: f64,
+    pub first_contact: Option<f64>,
+    pub second_contact: Option<f64>,
+    pub third_contact: Option<f64>,
+    pub fourth_contact: Option<f64>,
+    /// Fraction of the solar diameter covered.
+    pub magnitude: f64,
+    /// Fraction of the solar disc covered.
+    pub obscuration: f64,
+    /// True altitude of the Sun at maximum, degrees.
+    pub sun_altitude: f64,
+    pub saros_series: i32,
+    pub saros_member: i32,
+}
+
+impl LocalEclipse {
+    pub fn is_visible(&self) -> bool {
+        self.flags as u32 & SE_ECL_VISIBLE != 0
+    }
+
+    pub fn is_maximum_visible(&self) -> bool {
+        self.flags as u32 & SE_ECL_MAX_VISIBLE != 0
+    }
+}
+
+fn solar_eclipse_after(jd: f64, source: EphemerisSource) -> Result<Eclipse, String> {
+    let (flags, tret) = ephemeris::solar_eclipse_when_glob(jd, source)?;
+    let attr = ephemeris::solar_eclipse_where(tret[0], source)?;
+    Ok(Eclipse {
+        kind: EclipseKind::Solar,
+        eclipse_type: EclipseType::from_flags(flags),
+        flags,
+        maximum_jd: tret[0],
+        magnitude: attr[0],
+        saros_series: attr[9] as i32,
+        saros_member: attr[10] as i32,
+        contacts: Contacts::from_array([tret[2], tret[3], 0.0, 0.0, tret[4], tret[5], tret[6], tret[7]]),
+    })
+}
+
+fn lunar_eclipse_after(jd: f64, source: EphemerisSource) -> Result<Eclipse, String> {
+    let (flags, tret) = ephemeris::lunar_eclipse_when(jd, source)?;
+    let attr = ephemeris::lunar_eclipse_how(tret[0], source)?;
+    let eclipse_type = EclipseType::from_flags(flags);
+    Ok(Eclipse {
+        kind: EclipseKind::Lunar,
+        eclipse_type,
+        flags,
+        maximum_jd: tret[0],
+        magnitude: if eclipse_type == EclipseType::Penumbral { attr[1] } else { attr[0] },
+        saros_series: attr[9] as i32,
+        saros_member: attr[10] as i32,
+        contacts: Contacts::from_array([tret[6], tret[7], tret[2], tret[3], tret[4], tret[5], 0.0, 0.0]),
+    })
+}
+
+/// Every solar and lunar eclipse with its maximum in `start_jd..=end_jd`,
+/// sorted by maximum.
+pub fn compute_eclipses(start_jd: f64, end_jd: f64, source: EphemerisSource) -> Result<Vec<Eclipse>, String> {
+    let mut eclipses = Vec::new();
+    for next in [solar_eclipse_after, lunar_eclipse_after] {
+        let mut jd = start_jd;
+        loop {
+            let eclipse = next(jd, source)?;
+            if eclipse.maximum_jd > end_jd {
+                break;
+            }
+            eclipses.push(eclipse);
+            // Eclipses of one kind are at least a lunar month apart
+            jd = eclipse.maximum_jd + 1.0;
+        }
+    }
+    eclipses.sort_by(|a, b| a.maximum_jd.total_cmp(&b.maximum_jd));
+    Ok(eclipses)
+}
+
+/// Next solar eclipse visible from `location` after `jd`.
+pub fn next_local_solar_eclipse(jd: f64, location: GeoLocation, source: EphemerisSource) -> Result<LocalEclipse, String> {
+    let (flags, tret, attr) = ephemeris::solar_eclipse_when_loc(jd, location, source)?;
+    let t = |i: usize| (tret[i] != 0.0).then_some(tret[i]);
+    Ok(LocalEclipse {
+        eclipse_type: EclipseType::from_flags(flags),
+        flags,
+        maximum_jd: tret[0],
+        first_contact: t(1),
+        second_contact: t(2),
+        third_contact: t(3),
+        fourth_contact: t(4),
+        magnitude: attr[0],
+        obscuration: attr[2],
+        sun_altitude: attr[5],
+        saros_series: attr[9] as i32,
+        saros_member: attr[10] as i32,
+    })
+}
+
+// ECLI section layout:
+//   eclipse count u32, then per eclipse (96 bytes):
+//   kind u8 (0 solar, 1 lunar), 3 reserved bytes, flags i32,
+//   maximum f64, magnitude f64, saros series i32, saros member i32,
+//   contacts f64 × 8 (begin, end, partial, total, center pairs; 0 = none)
+pub fn build_section(start_jd: f64, end_jd: f64, source: EphemerisSource) -> Result<Vec<u8>, String> {
+    let eclipses = compute_eclipses(start_jd, end_jd, source)?;
+    Ok(encode_section(&eclipses))
+}
+
+pub fn encode_section(eclipses: &[Eclipse]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + RECORD_SIZE * eclipses.len());
+    out.extend_from_slice(&(eclipses.len() as u32).to_le_bytes());
+    for eclipse in eclipses {
+        out.push(match eclipse.kind {
+            EclipseKind::Solar => 0,
+            EclipseKind::Lunar => 1,
+        });
+        out.extend_from_slice(&[0; 3]);
+        out.extend_from_slice(&eclipse.flags.to_le_bytes());
+        out.extend_from_slice(&eclipse.maximum_jd.to_le_bytes());
+        out.extend_from_slice(&eclipse.magnitude.to_le_bytes());
+        out.extend_from_slice(&eclipse.saros_series.to_le_bytes());
+        out.extend_from_slice(&eclipse.saros_member.to_le_bytes());
+        for time in eclipse.contacts.to_array() {
+            out.extend_from_slice(&time.to_le_bytes());
+        }
+    }
+    out
+}
+
+fn decode_record(bytes: &[u8]) -> Eclipse {
+    let flags = read_i32(bytes, 4);
+    let mut times = [0.0; 8];
+    for (i, time) in times.iter_mut().enumerate() {
+        *time = read_f64(bytes, 32 + 8 * i);
+    }
+    Eclipse {
+        kind: if bytes[0] == 0 { EclipseKind::Solar } else { EclipseKind::Lunar },
+        eclipse_type: EclipseType::from_flags(flags),
+        flags,
+        maximum_jd: read_f64(bytes, 8),
+        magnitude: read_f64(bytes, 16),
+        saros_series: read_i32(bytes, 24),
+        saros_member: read_i32(bytes, 28),
+        contacts: Contacts::from_array(times),
+    }
+}
+
+impl Kernel {
+    /// All eclipses stored in the kernel, sorted by maximum.
+    pub fn stored_eclipses(&self) -> Result<Vec<Eclipse>, KernelError> {
+        let bad = || KernelError::BadSection("ECLI".to_string());
+        let bytes = self.section(&ECLIPSE_TAG).ok_or_else(bad)?;
+        if bytes.len() < 4 {
+            return Err(bad());
+        }
+        let count = read_u32(bytes, 0) as usize;
+        let len = count.checked_mul(RECORD_SIZE).ok_or_else(bad)?;
+        let records = bytes.get(4..).and_then(|records| records.get(..len)).ok_or_else(bad)?;
+        Ok(records.chunks_exact(RECORD_SIZE).map(decode_record).collect())
+    }
+
+    /// Eclipses with their maximum in `from..=to`, optionally of one kind.
+    pub fn eclipses(&self, from: f64, to: f64, kind: Option<EclipseKind>) -> Result<Vec<Eclipse>, KernelError> {
+        Ok(self.stored_eclipses()?
+            .into_iter()
+            .filter(|e| e.maximum_jd >= from && e.maximum_jd <= to)
+            .filter(|e| kind.is_none_or(|k| e.kind == k))
+            .collect())
+    }
+
+    pub fn next_eclipse(&self, jd: f64, kind: Option<EclipseKind>) -> Result<Option<Eclipse>, KernelError> {
+        Ok(self.stored_eclipses()?
+            .into_iter()
+            .find(|e| e.maximum_jd >= jd && kind.is_none_or(|k| e.kind == k)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::{KernelWriter, Position};
+
+    fn eclipse(kind: EclipseKind, flags: u32, maximum_jd: f64) -> Eclipse {
+        Eclipse {
+            kind,
+            eclipse_type: EclipseType::from_flags(flags as i32),
+            flags: flags as i32,
+            maximum_jd,
+            magnitude: 1.0566,
+            saros_series: 139,
+            saros_member: 30,
+            contacts: Contacts { begin: Some(maximum_jd - 0.1), end: Some(maximum_jd + 0.1),
+                                 total_begin: Some(maximum_jd - 0.01), total_end: Some(maximum_jd + 0.01),
+                                 ..Contacts::default() },
+        }
+    }
+
+    fn kernel_with(section: Option<Vec<u8>>) -> Kernel {
+        let mut writer = KernelWriter::new(2460300.5, 1.0, &[0]);
+        writer.push_record(&[Position::default()]);
+        if let Some(section) = section {
+            writer.add_section(ECLIPSE_TAG, section);
+        }
+        Kernel::from_bytes(writer.to_bytes()).unwrap()
+    }
+
+    // The 2024 eclipses: penumbral lunar, total solar, partial lunar, annular solar
+    fn stored() -> Vec<Eclipse> {
+        vec![
+            eclipse(EclipseKind::Lunar, SE_ECL_PENUMBRAL, 2460394.8),
+            eclipse(EclipseKind::Solar, SE_ECL_TOTAL | SE_ECL_CENTRAL, 2460409.26),
+            eclipse(EclipseKind::Lunar, SE_ECL_PARTIAL | SE_ECL_PENUMBRAL, 2460571.6),
+            eclipse(EclipseKind::Solar, SE_ECL_ANNULAR | SE_ECL_CENTRAL, 2460586.26),
+        ]
+    }
+
+    #[test]
+    fn reads_back_the_encoded_section() {
+        let kernel = kernel_with(Some(encode_section(&stored())));
+        let read = kernel.stored_eclipses().unwrap();
+        assert_eq!(read, stored());
+        assert_eq!(read[1].contacts.partial_begin, None);
+        assert!(read[1].is_central() && !read[0].is_central());
+    }
+
+    #[test]
+    fn filters_by_time_and_kind() {
+        let kernel = kernel_with(Some(encode_section(&stored())));
+        let solar = kernel.eclipses(2460300.0, 2460600.0, Some(EclipseKind::Solar)).unwrap();
+        assert_eq!(solar.iter().map(|e| e.eclipse_type).collect::<Vec<_>>(), [EclipseType::Total, EclipseType::Annular]);
+        assert_eq!(kernel.eclipses(2460400.0, 2460571.6, None).unwrap().len(), 2);
+
+        let next = kernel.next_eclipse(2460400.0, Some(EclipseKind::Lunar)).unwrap().unwrap();
+        assert_eq!((next.maximum_jd, next.eclipse_type), (2460571.6, EclipseType::Partial));
+        assert_eq!(kernel.next_eclipse(2460600.0, None).unwrap(), None);
+    }
+
+    #[test]
+    fn classifies_flags() {
+        assert_eq!(EclipseType::from_flags((SE_ECL_TOTAL | SE_ECL_CENTRAL) as i32), EclipseType::Total);
+        assert_eq!(EclipseType::from_flags(SE_ECL_HYBRID as i32), EclipseType::Hybrid);
+        assert_eq!(EclipseType::from_flags(SE_ECL_ANNULAR as i32), EclipseType::Annular);
+        assert_eq!(EclipseType::from_flags(SE_ECL_PENUMBRAL as i32), EclipseType::Penumbral);
+        assert_eq!(EclipseType::from_flags((SE_ECL_PARTIAL | SE_ECL_PENUMBRAL) as i32), EclipseType::Partial);
+    }
+
+    #[test]
+    fn rejects_missing_and_short_sections() {
+        let bad = |kernel: Kernel| matches!(kernel.stored_eclipses(), Err(KernelError::BadSection(_)));
+        assert!(bad(kernel_with(None)));
+        assert!(bad(kernel_with(Some(vec![1, 0]))));
+
+        let mut short = encode_section(&stored());
+        short.truncate(short.len() - 1);
+        assert!(bad(kernel_with(Some(short))));
+
+        let mut huge = encode_section(&stored());
+        huge[0..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(bad(kernel_with(Some(huge))));
+    }
+}
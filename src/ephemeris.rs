@@ -0,0 +1,199 @@
+use std::ffi::CStr;
+use crate::coords::GeoLocation;
+use crate::frame::Frame;
+use crate::kernel::Position;
+use crate::{swe_calc_ut, swe_fixstar2_mag, swe_fixstar2_ut, swe_get_ayanamsa_ut, swe_lun_eclipse_how,
+            swe_lun_eclipse_when, swe_rise_trans, swe_set_ephe_path, swe_set_jpl_file, swe_set_sid_mode,
+            swe_set_topo, swe_sol_eclipse_when_glob, swe_sol_eclipse_when_loc, swe_sol_eclipse_where,
+            SEFLG_JPLEPH, SEFLG_SPEED, SEFLG_SWIEPH, SE_ECL_NUT, SE_MAX_STNAME};
+
+// Thin safe wrappers over the Swiss Ephemeris calls the generators need.
+// Readers never touch these.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EphemerisSource {
+    Swiss,
+    Jpl,
+}
+
+impl EphemerisSource {
+    pub fn flag(&self) -> i32 {
+        match self {
+            EphemerisSource::Swiss => SEFLG_SWIEPH as i32,
+            EphemerisSource::Jpl => SEFLG_JPLEPH as i32,
+        }
+    }
+}
+
+pub fn init(source: EphemerisSource) {
+    unsafe {
+        swe_set_ephe_path(c"./ephe".as_ptr());
+        if source == EphemerisSource::Jpl {
+            swe_set_jpl_file(c"de441.eph".as_ptr());
+        }
+    }
+}
+
+pub fn calc(jd_ut: f64, body: i32, flags: i32) -> Result<[f64; 6], String> {
+    let mut xx = [0.0; 6];
+    let mut serr = [0i8; 256];
+    let ret = unsafe { swe_calc_ut(jd_ut, body, flags, xx.as_mut_ptr(), serr.as_mut_ptr()) };
+    if ret < 0 {
+        return Err(format!("swe_calc_ut failed for body {}: {}", body, error_text(&serr)));
+    }
+    Ok(xx)
+}
+
+fn error_text(serr: &[i8; 256]) -> String {
+    unsafe { CStr::from_ptr(serr.as_ptr()) }.to_string_lossy().into_owned()
+}
+
+pub fn position(jd_ut: f64, body: i32, source: EphemerisSource) -> Result<Position, String> {
+    let xx = calc(jd_ut, body, (SEFLG_SPEED as i32) | source.flag())?;
+    Ok(Position::from_xx(&xx))
+}
+
+/// Position of `body` as seen from `frame`'s origin.
+pub fn position_in_frame(jd_ut: f64, body: i32, source: EphemerisSource, frame: Frame) -> Result<Position, String> {
+    if let Frame::Topocentric(location) = frame {
+        unsafe { swe_set_topo(location.longitude, location.latitude, location.altitude) };
+    }
+    let xx = calc(jd_ut, body, (SEFLG_SPEED as i32) | source.flag() | frame.flags())?;
+    Ok(Position::from_xx(&xx))
+}
+
+/// Apparent position of a star from sefstars.txt at `jd_ut`, with the
+/// "name,designation" Swiss Ephemeris resolved it to.
+pub fn fixed_star(name: &str, jd_ut: f64, source: EphemerisSource) -> Result<(String, Position), String> {
+    let mut star = star_buffer(name);
+    let mut xx = [0.0; 6];
+    let mut serr = [0i8; 256];
+    let ret = unsafe {
+        swe_fixstar2_ut(star.as_mut_ptr(), jd_ut, (SEFLG_SPEED as i32) | source.flag(), xx.as_mut_ptr(), serr.as_mut_ptr())
+    };
+    if ret < 0 {
+        return Err(format!("swe_fixstar2_ut failed for {}: {}", name, error_text(&serr)));
+    }
+    let resolved = unsafe { CStr::from_ptr(star.as_ptr()) }.to_string_lossy().into_owned();
+    Ok((resolved, Position::from_xx(&xx)))
+}
+
+/// Visual magnitude of a star from sefstars.txt.
+pub fn fixed_star_magnitude(name: &str) -> Result<f64, String> {
+    let mut star = star_buffer(name);
+    let mut mag = 0.0;
+    let mut serr = [0i8; 256];
+    let ret = unsafe { swe_fixstar2_mag(star.as_mut_ptr(), &mut mag, serr.as_mut_ptr()) };
+    if ret < 0 {
+        return Err(format!("swe_fixstar2_mag failed for {}: {}", name, error_text(&serr)));
+    }
+    Ok(mag)
+}
+
+// swe_fixstar2_* write the resolved name back into the star argument.
+fn star_buffer(name: &str) -> [i8; 2 * SE_MAX_STNAME as usize + 1] {
+    let mut buffer = [0i8; 2 * SE_MAX_STNAME as usize + 1];
+    for (b, c) in buffer.iter_mut().zip(name.bytes().take(SE_MAX_STNAME as usize)) {
+        *b = c as i8;
+    }
+    buffer
+}
+
+/// Nutation in longitude (degrees) at `jd_ut`.
+pub fn nutation_longitude(jd_ut: f64, source: EphemerisSource) -> Result<f64, String> {
+    let xx = calc(jd_ut, SE_ECL_NUT, source.flag())?;
+    Ok(xx[2])
+}
+
+/// Mean ayanamsa (no nutation) for a predefined or user mode.
+pub fn ayanamsa(jd_ut: f64, sid_mode: i32, t0: f64, ayan_t0: f64) -> f64 {
+    unsafe {
+        swe_set_sid_mode(sid_mode, t0, ayan_t0);
+        swe_get_ayanamsa_ut(jd_ut)
+    }
+}
+
+/// Next solar eclipse anywhere on Earth after `jd_ut`: the SE_ECL_* type
+/// flags and the tret times of swe_sol_eclipse_when_glob.
+pub fn solar_eclipse_when_glob(jd_ut: f64, source: EphemerisSource) -> Result<(i32, [f64; 10]), String> {
+    let mut tret = [0.0; 10];
+    let mut serr = [0i8; 256];
+    let ret = unsafe { swe_sol_eclipse_when_glob(jd_ut, source.flag(), 0, tret.as_mut_ptr(), 0, serr.as_mut_ptr()) };
+    if ret < 0 {
+        return Err(format!("swe_sol_eclipse_when_glob failed: {}", error_text(&serr)));
+    }
+    Ok((ret, tret))
+}
+
+/// Attributes (magnitude, saros) of a solar eclipse at its maximum.
+pub fn solar_eclipse_where(jd_ut: f64, source: EphemerisSource) -> Result<[f64; 20], String> {
+    let mut geopos = [0.0; 10];
+    let mut attr = [0.0; 20];
+    let mut serr = [0i8; 256];
+    let ret = unsafe { swe_sol_eclipse_where(jd_ut, source.flag(), geopos.as_mut_ptr(), attr.as_mut_ptr(), serr.as_mut_ptr()) };
+    if ret < 0 {
+        return Err(format!("swe_sol_eclipse_where failed: {}", error_text(&serr)));
+    }
+    Ok(attr)
+}
+
+/// Next solar eclipse visible from `location`: type and visibility flags,
+/// local contact times and attributes.
+pub fn solar_eclipse_when_loc(jd_ut: f64, location: GeoLocation,
+                              source: EphemerisSource) -> Result<(i32, [f64; 10], [f64; 20]), String> {
+    let mut geopos = location.geopos();
+    let mut tret = [0.0; 10];
+    let mut attr = [0.0; 20];
+    let mut serr = [0i8; 256];
+    let ret = unsafe {
+        swe_sol_eclipse_when_loc(jd_ut, source.flag(), geopos.as_mut_ptr(), tret.as_mut_ptr(),
+                                 attr.as_mut_ptr(), 0, serr.as_mut_ptr())
+    };
+    if ret < 0 {
+        return Err(format!("swe_sol_eclipse_when_loc failed: {}", error_text(&serr)));
+    }
+    Ok((ret, tret, attr))
+}
+
+/// Next lunar eclipse after `jd_ut`: type flags and contact times.
+pub fn lunar_eclipse_when(jd_ut: f64, source: EphemerisSource) -> Result<(i32, [f64; 10]), String> {
+    let mut tret = [0.0; 10];
+    let mut serr = [0i8; 256];
+    let ret = unsafe { swe_lun_eclipse_when(jd_ut, source.flag(), 0, tret.as_mut_ptr(), 0, serr.as_mut_ptr()) };
+    if ret < 0 {
+        return Err(format!("swe_lun_eclipse_when failed: {}", error_text(&serr)));
+    }
+    Ok((ret, tret))
+}
+
+/// Attributes (umbral/penumbral magnitude, saros) of a lunar eclipse.
+pub fn lunar_eclipse_how(jd_ut: f64, source: EphemerisSource) -> Result<[f64; 20], String> {
+    let mut geopos = [0.0; 3];
+    let mut attr = [0.0; 20];
+    let mut serr = [0i8; 256];
+    let ret = unsafe { swe_lun_eclipse_how(jd_ut, source.flag(), geopos.as_mut_ptr(), attr.as_mut_ptr(), serr.as_mut_ptr()) };
+    if ret < 0 {
+        return Err(format!("swe_lun_eclipse_how failed: {}", error_text(&serr)));
+    }
+    Ok(attr)
+}
+
+/// Next rise, set or meridian transit (SE_CALC_* | SE_BIT_* in `rsmi`)
+/// after `jd_ut`; None when the body stays above or below the horizon.
+/// Pressure (hPa) and temperature (°C) feed the refraction model; zero
+/// pressure means the standard 1013.25.
+pub fn rise_trans(jd_ut: f64, body: i32, location: GeoLocation, rsmi: i32, pressure: f64,
+                  temperature: f64, source: EphemerisSource) -> Result<Option<f64>, String> {
+    let mut geopos = location.geopos();
+    let mut tret = [0.0; 10];
+    let mut serr = [0i8; 256];
+    let ret = unsafe {
+        swe_rise_trans(jd_ut, body, std::ptr::null_mut(), source.flag(), rsmi, geopos.as_mut_ptr(),
+                       pressure, temperature, tret.as_mut_ptr(), serr.as_mut_ptr())
+    };
+    match ret {
+        -2 => Ok(None),
+        r if r < 0 => Err(format!("swe_rise_trans failed for body {}: {}", body, error_text(&serr))),
+        _ => Ok(Some(tret[0])),
+    }
+}
@@ -0,0 +1,32 @@
+use crate::kernel::{Kernel, KernelError, Position};
+use crate::sidereal::Zodiac;
+
+/// One line of an ephemeris table: the selected bodies at `jd`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EphemerisRow {
+    pub jd: f64,
+    pub positions: Vec<(i32, Position)>,
+}
+
+/// `from`, `from + step`, ... up to and including `to`. Each time is
+/// computed from the start rather than accumulated, so long hourly tables
+/// stay on the hour.
+pub fn sample_times(from: f64, to: f64, step: f64) -> impl Iterator<Item = f64> {
+    // Tolerate the last step landing a hair past `to` from rounding
+    let count = ((to - from) / step + 1e-9).floor().max(-1.0) as i64 + 1;
+    (0..count).map(move |i| from + i as f64 * step)
+}
+
+impl Kernel {
+    /// Rows for `bodies` every `step` days from `from` through `to`,
+    /// computed lazily so a long table can be streamed.
+    pub fn ephemeris_rows<'a>(&'a self, bodies: &'a [i32], from: f64, to: f64, step: f64,
+                              zodiac: Zodiac) -> impl Iterator<Item = Result<EphemerisRow, KernelError>> + 'a {
+        sample_times(from, to, step).map(move |jd| {
+            let positions = bodies.iter()
+                .map(|&body| Ok((body, self.position_in(body, jd, zodiac)?)))
+                .collect::<Result<_, KernelError>>()?;
+            Ok(EphemerisRow { jd, positions })
+        })
+    }
+}
@@ -0,0 +1,278 @@
+use crate::bodies::SIGN_NAMES;
+use crate::coords::arc;
+use crate::kernel::{Kernel, KernelError};
+use crate::search::{find_roots, refine, SearchOptions};
+use crate::sidereal::Zodiac;
+use crate::vedic::{NAKSHATRAS, NAKSHATRA_SPAN};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary {
+    Sign,
+    Nakshatra,
+    /// Every `n` degrees; `n` should divide 360.
+    Degree(f64),
+}
+
+impl Boundary {
+    pub fn span(&self) -> f64 {
+        match *self {
+            Boundary::Sign => 30.0,
+            Boundary::Nakshatra => NAKSHATRA_SPAN,
+            Boundary::Degree(step) => step,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        (360.0 / self.span()).round() as usize
+    }
+
+    /// Accepts "sign", "nakshatra" or a degree step such as "1" or "10".
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "sign" | "signs" => Ok(Boundary::Sign),
+            "nakshatra" | "nakshatras" => Ok(Boundary::Nakshatra),
+            other => match other.trim_end_matches('°').parse::<f64>() {
+                Ok(step) if step > 0.0 && step <= 180.0 => Ok(Boundary::Degree(step)),
+                _ => Err(format!("unknown ingress boundary {}", s)),
+            },
+        }
+    }
+
+    /// Name of the division with the given index.
+    pub fn label(&self, index: usize) -> String {
+        match *self {
+            Boundary::Sign => SIGN_NAMES[index % 12].to_string(),
+            Boundary::Nakshatra => NAKSHATRAS[index % 27].to_string(),
+            Boundary::Degree(step) => format!("{}°", index as f64 * step),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventKind {
+    /// The body enters division `index`; `retrograde` when it backs into it.
+    Ingress { boundary: Boundary, index: usize, retrograde: bool },
+    /// Speed in longitude crosses zero: `retrograde` when turning retrograde,
+    /// otherwise turning direct.
+    Station { retrograde: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Event {
+    pub jd: f64,
+    pub body: i32,
+    pub kind: EventKind,
+    /// The cusp crossed for ingresses, the station point for stations.
+    pub longitude: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventOptions {
+    pub boundaries: Vec<Boundary>,
+    pub stations: bool,
+    pub zodiac: Zodiac,
+    pub search: SearchOptions,
+}
+
+impl Default for EventOptions {
+    fn default() -> Self {
+        Self {
+            boundaries: vec![Boundary::Sign],
+            stations: true,
+            zodiac: Zodiac::Tropical,
+            search: SearchOptions::default(),
+        }
+    }
+}
+
+// Step used to differentiate the interpolated speed.
+const ACCELERATION_STEP: f64 = 1.0 / 24.0;
+
+impl Kernel {
+    /// Ingresses and stations of `bodies` in `from..to`, sorted by time.
+    pub fn find_events(&self, bodies: &[i32], from: f64, to: f64, options: &EventOptions) -> Result<Vec<Event>, KernelError> {
+        let mut events = Vec::new();
+        for &body in bodies {
+            let stations = self.find_stations(body, from, to, options.zodiac, &options.search)?;
+            for boundary in &options.boundaries {
+                events.extend(self.ingresses_between_stations(body, *boundary, &stations, from, to, options)?);
+            }
+            if options.stations {
+                events.extend(stations);
+            }
+        }
+        events.sort_by(|a, b| a.jd.total_cmp(&b.jd));
+        Ok(events)
+    }
+
+    /// Exact retrograde and direct stations, found as roots of the speed in
+    /// longitude.
+    pub fn find_stations(&self, body: i32, from: f64, to: f64, zodiac: Zodiac,
+                         options: &SearchOptions) -> Result<Vec<Event>, KernelError> {
+        let f = |jd: f64| Ok((self.position_in(body, jd, zodiac)?.speed_longitude, self.acceleration(body, jd, zodiac)?));
+
+        let mut stations = Vec::new();
+        for jd in find_roots(&f, from, to, options)? {
+            stations.push(Event {
+                jd,
+                body,
+                kind: EventKind::Station { retrograde: self.acceleration(body, jd, zodiac)? < 0.0 },
+                longitude: self.position_in(body, jd, zodiac)?.longitude,
+            });
+        }
+        Ok(stations)
+    }
+
+    /// Exact crossings of every `boundary` cusp in `from..to`, including the
+    /// extra crossings of a retrograde loop.
+    pub fn find_ingresses(&self, body: i32, boundary: Boundary, from: f64, to: f64,
+                          options: &EventOptions) -> Result<Vec<Event>, KernelError> {
+        let stations = self.find_stations(body, from, to, options.zodiac, &options.search)?;
+        self.ingresses_between_stations(body, boundary, &stations, from, to, options)
+    }
+
+    // Between two stations the longitude is monotonic, so each cusp passed
+    // between two samples is crossed exactly once.
+    fn ingresses_between_stations(&self, body: i32, boundary: Boundary, stations: &[Event], from: f64, to: f64,
+                                  options: &EventOptions) -> Result<Vec<Event>, KernelError> {
+        let span = boundary.span();
+        let count = boundary.count() as i64;
+        let zodiac = options.zodiac;
+        let mut edges = vec![from];
+        edges.extend(stations.iter().map(|s| s.jd));
+        edges.push(to);
+
+        let mut events = Vec::new();
+        for segment in edges.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            let mut a = start;
+            let mut lon_a = self.position_in(body, a, zodiac)?.longitude;
+
+            while a < end {
+                let b = (a + options.search.scan_step).min(end);
+                let lon_b = self.position_in(body, b, zodiac)?.longitude;
+                let moved = arc(lon_a, lon_b);
+                let (lo, hi) = if moved < 0.0 { (lon_a + moved, lon_a) } else { (lon_a, lon_a + moved) };
+
+                // Cusps in (lo, hi]
+                for k in (lo / span).floor() as i64 + 1..=(hi / span).floor() as i64 {
+                    let cusp = (k as f64 * span).rem_euclid(360.0);
+                    let g = |jd: f64| {
+                        let pos = self.position_in(body, jd, zodiac)?;
+                        Ok((arc(cusp, pos.longitude), pos.speed_longitude))
+                    };
+                    let g_a = arc(cusp, lon_a);
+                    let jd = if g_a == 0.0 { a } else { refine(&g, a, b, g_a, &options.search)? };
+                    let retrograde = moved < 0.0;
+                    let entered = if retrograde { k - 1 } else { k };
+                    events.push(Event {
+                        jd,
+                        body,
+                        kind: EventKind::Ingress {
+                            boundary,
+                            index: entered.rem_euclid(count) as usize,
+                            retrograde,
+                        },
+                        longitude: cusp,
+                    });
+                }
+
+                a = b;
+                lon_a = lon_b;
+            }
+        }
+        Ok(events)
+    }
+
+    // Rate of change of the speed in longitude, degrees/day².
+    fn acceleration(&self, body: i32, jd: f64, zodiac: Zodiac) -> Result<f64, KernelError> {
+        let lo = (jd - ACCELERATION_STEP).max(self.header().start_jd);
+        let hi = (jd + ACCELERATION_STEP).min(self.header().end_jd());
+        let speed_lo = self.position_in(body, lo, zodiac)?.speed_longitude;
+        let speed_hi = self.position_in(body, hi, zodiac)?.speed_longitude;
+        Ok((speed_hi - speed_lo) / (hi - lo))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::{KernelWriter, Position};
+
+    // 0.5°/day with a 20° wobble: stations where cos(t/10) = -0.25, and a
+    // retrograde loop from about 53.5° back to 27.9° across the Taurus cusp.
+    fn looping(t: f64) -> (f64, f64) {
+        (25.0 + 0.5 * t + 20.0 * (t / 10.0).sin(), 0.5 + 2.0 * (t / 10.0).cos())
+    }
+
+    fn kernel() -> Kernel {
+        let mut writer = KernelWriter::new(0.0, 0.5, &[4]);
+        for i in 0..=200 {
+            let (longitude, speed) = looping(i as f64 * 0.5);
+            writer.push_record(&[Position { longitude: longitude.rem_euclid(360.0), distance: 1.0,
+                                            speed_longitude: speed, ..Position::default() }]);
+        }
+        Kernel::from_bytes(writer.to_bytes()).unwrap()
+    }
+
+    #[test]
+    fn finds_stations() {
+        let kernel = kernel();
+        let stations = kernel.find_stations(4, 0.0, 100.0, Zodiac::Tropical, &SearchOptions::default()).unwrap();
+        let first = (-0.25f64).acos() * 10.0;
+        let expected = [first, 20.0 * std::f64::consts::PI - first, 20.0 * std::f64::consts::PI + first];
+        assert_eq!(stations.len(), 3, "{:?}", stations);
+        for ((station, jd), retrograde) in stations.iter().zip(expected).zip([true, false, true]) {
+            assert!((station.jd - jd).abs() < 1e-3, "station at {} expected {}", station.jd, jd);
+            assert_eq!(station.kind, EventKind::Station { retrograde });
+            assert!((station.longitude - looping(jd).0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn finds_every_crossing_of_a_retrograde_loop() {
+        let kernel = kernel();
+        let ingresses = kernel.find_ingresses(4, Boundary::Sign, 0.0, 60.0, &EventOptions::default()).unwrap();
+        let summary: Vec<(usize, bool)> = ingresses.iter()
+            .map(|e| match e.kind {
+                EventKind::Ingress { index, retrograde, .. } => (index, retrograde),
+                EventKind::Station { .. } => unreachable!(),
+            })
+            .collect();
+        // Into Taurus, back into Aries, then into Taurus again; the loop
+        // turns at 53.5°, short of Gemini
+        assert_eq!(summary, [(1, false), (0, true), (1, false)]);
+        for event in &ingresses {
+            assert!(arc(event.longitude, looping(event.jd).0).abs() < 1e-6, "{:?}", event);
+            assert_eq!(event.longitude % 30.0, 0.0);
+        }
+        assert!(ingresses.windows(2).all(|w| w[0].jd < w[1].jd));
+    }
+
+    #[test]
+    fn merges_ingresses_and_stations_in_time_order() {
+        let kernel = kernel();
+        let options = EventOptions { boundaries: vec![Boundary::Sign, Boundary::Degree(10.0)], ..EventOptions::default() };
+        let events = kernel.find_events(&[4], 0.0, 60.0, &options).unwrap();
+        assert!(events.windows(2).all(|w| w[0].jd <= w[1].jd));
+        assert_eq!(events.iter().filter(|e| matches!(e.kind, EventKind::Station { .. })).count(), 2);
+
+        let no_stations = EventOptions { stations: false, ..EventOptions::default() };
+        assert!(kernel.find_events(&[4], 0.0, 60.0, &no_stations).unwrap().iter()
+            .all(|e| matches!(e.kind, EventKind::Ingress { .. })));
+    }
+
+    #[test]
+    fn parses_and_labels_boundaries() {
+        assert_eq!(Boundary::parse("Signs"), Ok(Boundary::Sign));
+        assert_eq!(Boundary::parse("nakshatra"), Ok(Boundary::Nakshatra));
+        assert_eq!(Boundary::parse("10°"), Ok(Boundary::Degree(10.0)));
+        assert!(Boundary::parse("0").is_err());
+        assert!(Boundary::parse("moon").is_err());
+
+        assert_eq!(Boundary::Nakshatra.count(), 27);
+        assert_eq!(Boundary::Sign.label(13), "Taurus");
+        assert_eq!(Boundary::Nakshatra.label(0), "Ashwini");
+        assert_eq!(Boundary::Degree(15.0).label(3), "45°");
+    }
+}
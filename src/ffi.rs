@@ -0,0 +1,236 @@
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use crate::coords::GeoLocation;
+use crate::houses::HouseSystem;
+use crate::kernel::{Kernel, KernelError, Position};
+use crate::sidereal::Zodiac;
+
+// C ABI for reading kernels, so C, C++ and ctypes callers share this reader
+// instead of re-implementing the byte layout. include/medusa.h is generated
+// from this file with `cbindgen --config cbindgen.toml --output include/medusa.h`;
+// examples/c/harness.c exercises it.
+//
+// Every function returns a MedusaStatus and writes its result through an
+// out pointer, which is left untouched on error. Nothing here unwinds into C:
+// a panic comes back as MEDUSA_STATUS_ERR_PANIC.
+
+/// Bumped whenever a signature or struct layout below changes.
+pub const MEDUSA_ABI_VERSION: u32 = 1;
+
+/// Opaque handle from `medusa_open`. Safe to share between threads for
+/// reading; free it with `medusa_close`.
+pub struct MedusaKernel(Kernel);
+
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MedusaStatus {
+    Ok = 0,
+    /// A required pointer was null.
+    ErrNull = 1,
+    /// Path is not UTF-8, or an unknown house system.
+    ErrInvalidArgument = 2,
+    ErrIo = 3,
+    ErrBadMagic = 4,
+    ErrUnsupportedVersion = 5,
+    ErrTruncated = 6,
+    ErrOutOfRange = 7,
+    ErrUnknownBody = 8,
+    ErrBadSection = 9,
+    ErrFrame = 10,
+    /// Any other kernel error.
+    ErrKernel = 11,
+    ErrPanic = 12,
+}
+
+impl From<&KernelError> for MedusaStatus {
+    fn from(e: &KernelError) -> Self {
+        match e {
+            KernelError::Io(_) => MedusaStatus::ErrIo,
+            KernelError::BadMagic => MedusaStatus::ErrBadMagic,
+            KernelError::UnsupportedVersion(_) => MedusaStatus::ErrUnsupportedVersion,
+            KernelError::Truncated { .. } => MedusaStatus::ErrTruncated,
+            KernelError::OutOfRange { .. } => MedusaStatus::ErrOutOfRange,
+            KernelError::UnknownBody(_) => MedusaStatus::ErrUnknownBody,
+            KernelError::BadSection(_) => MedusaStatus::ErrBadSection,
+            KernelError::UnknownFrame(_) | KernelError::FrameMismatch { .. } => MedusaStatus::ErrFrame,
+            _ => MedusaStatus::ErrKernel,
+        }
+    }
+}
+
+impl From<KernelError> for MedusaStatus {
+    fn from(e: KernelError) -> Self {
+        (&e).into()
+    }
+}
+
+/// Ecliptic position in the kernel's frame: degrees, AU, and per-day speeds.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MedusaPosition {
+    pub longitude: f64,
+    pub latitude: f64,
+    pub distance: f64,
+    pub speed_longitude: f64,
+    pub speed_latitude: f64,
+    pub speed_distance: f64,
+}
+
+impl From<Position> for MedusaPosition {
+    fn from(pos: Position) -> Self {
+        Self {
+            longitude: pos.longitude,
+            latitude: pos.latitude,
+            distance: pos.distance,
+            speed_longitude: pos.speed_longitude,
+            speed_latitude: pos.speed_latitude,
+            speed_distance: pos.speed_distance,
+        }
+    }
+}
+
+/// Tropical houses. `system` is the letter actually used: Placidus and Koch
+/// fall back to Porphyry ('O') inside the polar circles.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MedusaHouses {
+    pub system: c_char,
+    /// cusps[0] is the first house.
+    pub cusps: [f64; 12],
+    pub ascendant: f64,
+    pub mc: f64,
+    pub armc: f64,
+}
+
+// Runs `f`, turning errors and panics into status codes.
+fn guard(f: impl FnOnce() -> Result<(), MedusaStatus>) -> MedusaStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => MedusaStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => MedusaStatus::ErrPanic,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn medusa_abi_version() -> u32 {
+    MEDUSA_ABI_VERSION
+}
+
+const STATUS_MESSAGES: [(MedusaStatus, &[u8]); 13] = [
+    (MedusaStatus::Ok, b"ok\0"),
+    (MedusaStatus::ErrNull, b"null pointer argument\0"),
+    (MedusaStatus::ErrInvalidArgument, b"invalid argument\0"),
+    (MedusaStatus::ErrIo, b"I/O error\0"),
+    (MedusaStatus::ErrBadMagic, b"not a zenith kernel (bad magic)\0"),
+    (MedusaStatus::ErrUnsupportedVersion, b"unsupported kernel version\0"),
+    (MedusaStatus::ErrTruncated, b"kernel truncated\0"),
+    (MedusaStatus::ErrOutOfRange, b"JD outside kernel range\0"),
+    (MedusaStatus::ErrUnknownBody, b"body is not stored in this kernel\0"),
+    (MedusaStatus::ErrBadSection, b"kernel section missing or malformed\0"),
+    (MedusaStatus::ErrFrame, b"kernel frame does not support this request\0"),
+    (MedusaStatus::ErrKernel, b"kernel error\0"),
+    (MedusaStatus::ErrPanic, b"internal error\0"),
+];
+
+/// Static, NUL-terminated description of a status code. Takes a plain int
+/// so a stray value from C is not undefined behaviour.
+#[no_mangle]
+pub extern "C" fn medusa_status_message(status: i32) -> *const c_char {
+    let message: &'static [u8] = STATUS_MESSAGES.iter()
+        .find(|(s, _)| *s as i32 == status)
+        .map_or(b"unknown status\0", |(_, m)| m);
+    message.as_ptr().cast()
+}
+
+/// Maps the kernel at `path` and stores a handle in `*out`.
+///
+/// # Safety
+/// `path` must be a NUL-terminated string and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn medusa_open(path: *const c_char, out: *mut *mut MedusaKernel) -> MedusaStatus {
+    guard(|| {
+        if path.is_null() || out.is_null() {
+            return Err(MedusaStatus::ErrNull);
+        }
+        let path = CStr::from_ptr(path).to_str().map_err(|_| MedusaStatus::ErrInvalidArgument)?;
+        let kernel = Kernel::open(path)?;
+        *out = Box::into_raw(Box::new(MedusaKernel(kernel)));
+        Ok(())
+    })
+}
+
+/// First and last JD the kernel covers.
+///
+/// # Safety
+/// `kernel` must come from `medusa_open`; `start` and `end` must be valid
+/// pointers.
+#[no_mangle]
+pub unsafe extern "C" fn medusa_range(kernel: *const MedusaKernel, start: *mut f64, end: *mut f64) -> MedusaStatus {
+    guard(|| {
+        let kernel = kernel.as_ref().ok_or(MedusaStatus::ErrNull)?;
+        if start.is_null() || end.is_null() {
+            return Err(MedusaStatus::ErrNull);
+        }
+        *start = kernel.0.header().start_jd;
+        *end = kernel.0.header().end_jd();
+        Ok(())
+    })
+}
+
+/// Position of `body` (a Swiss Ephemeris body number) at `jd` (UT).
+///
+/// # Safety
+/// `kernel` must come from `medusa_open` and `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn medusa_position_at(kernel: *const MedusaKernel, body: i32, jd: f64,
+                                            out: *mut MedusaPosition) -> MedusaStatus {
+    guard(|| {
+        let kernel = kernel.as_ref().ok_or(MedusaStatus::ErrNull)?;
+        if out.is_null() {
+            return Err(MedusaStatus::ErrNull);
+        }
+        *out = kernel.0.position_at(body, jd)?.into();
+        Ok(())
+    })
+}
+
+/// Tropical houses at `jd` (UT) for a place in degrees (east and north
+/// positive) and metres. `system` is a house system letter: P, K, O, R, E
+/// or W. Like the CLIs' charts, houses come from the time alone, so `jd`
+/// need not be inside the kernel.
+///
+/// # Safety
+/// `kernel` must come from `medusa_open` and `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn medusa_houses_at(kernel: *const MedusaKernel, jd: f64, latitude: f64, longitude: f64,
+                                          altitude: f64, system: c_char, out: *mut MedusaHouses) -> MedusaStatus {
+    guard(|| {
+        let kernel = kernel.as_ref().ok_or(MedusaStatus::ErrNull)?;
+        if out.is_null() {
+            return Err(MedusaStatus::ErrNull);
+        }
+        let system = HouseSystem::parse(&(system as u8 as char).to_string())
+            .map_err(|_| MedusaStatus::ErrInvalidArgument)?;
+        let location = GeoLocation::new(latitude, longitude, altitude);
+        let houses = kernel.0.houses_in(jd, location, system, Zodiac::Tropical)?;
+        *out = MedusaHouses {
+            system: houses.system.code() as u8 as c_char,
+            cusps: houses.cusps,
+            ascendant: houses.ascendant,
+            mc: houses.mc,
+            armc: houses.armc,
+        };
+        Ok(())
+    })
+}
+
+/// Unmaps the kernel. Null is ignored.
+///
+/// # Safety
+/// `kernel` must come from `medusa_open` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn medusa_close(kernel: *mut MedusaKernel) {
+    if !kernel.is_null() {
+        drop(Box::from_raw(kernel));
+    }
+}
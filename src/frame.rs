@@ -0,0 +1,218 @@
+use std::fmt;
+use crate::coords::{arc, mean_obliquity, sidereal_time, GeoLocation};
+use crate::kernel::{read_f64, Kernel, KernelError, Position};
+use crate::sidereal::Zodiac;
+use crate::{SEFLG_BARYCTR, SEFLG_HELCTR, SEFLG_TOPOCTR};
+
+pub const TOPO_TAG: [u8; 4] = *b"TOPO";
+
+const AU_KM: f64 = 149_597_870.7;
+// WGS84
+const EARTH_RADIUS_KM: f64 = 6378.137;
+const EARTH_FLATTENING: f64 = 1.0 / 298.257223563;
+// Step for the finite-difference speeds of parallax-corrected positions.
+const PARALLAX_STEP: f64 = 1.0 / 1440.0;
+
+/// Where the kernel's positions are observed from. Stored in header byte 5,
+/// which older kernels leave at 0 (geocentric); topocentric kernels add a
+/// TOPO section with the observer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frame {
+    Geocentric,
+    Heliocentric,
+    Barycentric,
+    Topocentric(GeoLocation),
+}
+
+impl Frame {
+    pub fn code(&self) -> u8 {
+        match self {
+            Frame::Geocentric => 0,
+            Frame::Heliocentric => 1,
+            Frame::Barycentric => 2,
+            Frame::Topocentric(_) => 3,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Frame::Geocentric => "geocentric",
+            Frame::Heliocentric => "heliocentric",
+            Frame::Barycentric => "barycentric",
+            Frame::Topocentric(_) => "topocentric",
+        }
+    }
+
+    /// Accepts geo, helio, bary, or topo:lat,lon[,alt].
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let lower = s.to_ascii_lowercase();
+        let (kind, location) = match lower.split_once(':') {
+            Some((kind, location)) => (kind, Some(location)),
+            None => (lower.as_str(), None),
+        };
+        match (kind, location) {
+            ("geo" | "geocentric", None) => Ok(Frame::Geocentric),
+            ("helio" | "heliocentric", None) => Ok(Frame::Heliocentric),
+            ("bary" | "barycentric", None) => Ok(Frame::Barycentric),
+            ("topo" | "topocentric", Some(location)) => Ok(Frame::Topocentric(GeoLocation::parse(location)?)),
+            ("topo" | "topocentric", None) => Err("topocentric frame needs topo:lat,lon[,alt]".to_string()),
+            _ => Err(format!("unknown frame {}", s)),
+        }
+    }
+
+    /// SEFLG_* bits for swe_calc_ut.
+    pub fn flags(&self) -> i32 {
+        (match self {
+            Frame::Geocentric => 0,
+            Frame::Heliocentric => SEFLG_HELCTR,
+            Frame::Barycentric => SEFLG_BARYCTR,
+            Frame::Topocentric(_) => SEFLG_TOPOCTR,
+        }) as i32
+    }
+
+    /// Geocentric and topocentric frames have an observer on Earth, which
+    /// houses, phases and horizon work need.
+    pub fn is_earth_based(&self) -> bool {
+        matches!(self, Frame::Geocentric | Frame::Topocentric(_))
+    }
+
+    pub(crate) fn from_header(code: u8, topo: Option<&[u8]>) -> Result<Self, KernelError> {
+        match code {
+            0 => Ok(Frame::Geocentric),
+            1 => Ok(Frame::Heliocentric),
+            2 => Ok(Frame::Barycentric),
+            3 => match topo {
+                Some(bytes) if bytes.len() >= 24 => Ok(Frame::Topocentric(
+                    GeoLocation::new(read_f64(bytes, 0), read_f64(bytes, 8), read_f64(bytes, 16)))),
+                _ => Err(KernelError::BadSection("TOPO".to_string())),
+            },
+            _ => Err(KernelError::UnknownFrame(code)),
+        }
+    }
+
+    // TOPO section layout: latitude f64, longitude f64, altitude f64
+    pub(crate) fn topo_section(&self) -> Option<Vec<u8>> {
+        let Frame::Topocentric(location) = self else { return None };
+        Some([location.latitude, location.longitude, location.altitude]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect())
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Frame::Topocentric(location) => write!(f, "topocentric {}, {}", location.latitude, location.longitude),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
+/// Moves a geocentric ecliptic position (distance in AU) to an observer at
+/// `location`, using mean sidereal time and obliquity. Speeds are left as
+/// they are. The shift reaches about a degree for the Moon and a few
+/// arcseconds for the planets.
+pub fn apply_parallax(pos: &Position, jd_ut: f64, location: GeoLocation) -> Position {
+    let eps = mean_obliquity(jd_ut).to_radians();
+    let (lon, lat) = (pos.longitude.to_radians(), pos.latitude.to_radians());
+
+    // Ecliptic to equatorial cartesian
+    let x = pos.distance * lat.cos() * lon.cos();
+    let y = pos.distance * lat.cos() * lon.sin();
+    let z = pos.distance * lat.sin();
+    let (ye, ze) = (y * eps.cos() - z * eps.sin(), y * eps.sin() + z * eps.cos());
+
+    // Observer's geocentric position on the WGS84 ellipsoid
+    let phi = location.latitude.to_radians();
+    let c = 1.0 / (phi.cos().powi(2) + (1.0 - EARTH_FLATTENING).powi(2) * phi.sin().powi(2)).sqrt();
+    let s = (1.0 - EARTH_FLATTENING).powi(2) * c;
+    let h = location.altitude / 1000.0;
+    let rho_cos = (EARTH_RADIUS_KM * c + h) * phi.cos() / AU_KM;
+    let rho_sin = (EARTH_RADIUS_KM * s + h) * phi.sin() / AU_KM;
+    let theta = (sidereal_time(jd_ut) + location.longitude).to_radians();
+
+    let (xt, yt, zt) = (x - rho_cos * theta.cos(), ye - rho_cos * theta.sin(), ze - rho_sin);
+
+    // Back to ecliptic
+    let (y, z) = (yt * eps.cos() + zt * eps.sin(), -yt * eps.sin() + zt * eps.cos());
+    let distance = (xt * xt + y * y + z * z).sqrt();
+    Position {
+        longitude: y.atan2(xt).to_degrees().rem_euclid(360.0),
+        latitude: (z / distance).asin().to_degrees(),
+        distance,
+        ..*pos
+    }
+}
+
+impl Kernel {
+    pub fn frame(&self) -> Frame {
+        self.header().frame
+    }
+
+    pub fn require_frame(&self, frame: Frame) -> Result<(), KernelError> {
+        if self.frame() != frame {
+            return Err(KernelError::FrameMismatch { expected: frame.to_string(), found: self.frame().to_string() });
+        }
+        Ok(())
+    }
+
+    /// Fails for heliocentric and barycentric kernels.
+    pub fn require_earth_frame(&self) -> Result<(), KernelError> {
+        if !self.frame().is_earth_based() {
+            return Err(self.not_earth_frame());
+        }
+        Ok(())
+    }
+
+    fn not_earth_frame(&self) -> KernelError {
+        KernelError::FrameMismatch {
+            expected: "geocentric or topocentric".to_string(),
+            found: self.frame().to_string(),
+        }
+    }
+
+    /// Refuses to combine positions from kernels in different frames.
+    pub fn require_same_frame(&self, other: &Kernel) -> Result<(), KernelError> {
+        self.require_frame(other.frame())
+    }
+
+    /// Position of `body` seen from `location`. Geocentric kernels get the
+    /// parallax correction on read, with speeds by finite difference;
+    /// topocentric kernels must already be for `location`.
+    pub fn topocentric_position(&self, body: i32, jd: f64, location: GeoLocation) -> Result<Position, KernelError> {
+        match self.frame() {
+            Frame::Topocentric(_) => {
+                self.require_frame(Frame::Topocentric(location))?;
+                self.position_at(body, jd)
+            }
+            Frame::Geocentric => {
+                let corrected = |jd: f64| Ok::<_, KernelError>(apply_parallax(&self.position_at(body, jd)?, jd, location));
+                let (a, b) = if jd + PARALLAX_STEP <= self.header().end_jd() {
+                    (jd, jd + PARALLAX_STEP)
+                } else {
+                    (jd - PARALLAX_STEP, jd)
+                };
+                let (pa, pb) = (corrected(a)?, corrected(b)?);
+                let mut pos = corrected(jd)?;
+                pos.speed_longitude = arc(pa.longitude, pb.longitude) / PARALLAX_STEP;
+                pos.speed_latitude = (pb.latitude - pa.latitude) / PARALLAX_STEP;
+                pos.speed_distance = (pb.distance - pa.distance) / PARALLAX_STEP;
+                Ok(pos)
+            }
+            Frame::Heliocentric | Frame::Barycentric => Err(self.not_earth_frame()),
+        }
+    }
+
+    /// `topocentric_position` in the given zodiac.
+    pub fn topocentric_position_in(&self, body: i32, jd: f64, location: GeoLocation,
+                                   zodiac: Zodiac) -> Result<Position, KernelError> {
+        let mut pos = self.topocentric_position(body, jd, location)?;
+        if let Zodiac::Sidereal(mode) = zodiac {
+            let (ayanamsa, rate) = self.ayanamsa_with_rate(mode, jd)?;
+            pos.longitude = (pos.longitude - ayanamsa).rem_euclid(360.0);
+            pos.speed_longitude -= rate;
+        }
+        Ok(pos)
+    }
+}
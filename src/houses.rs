@@ -0,0 +1,324 @@
+use crate::coords::{mean_obliquity, sidereal_time, GeoLocation};
+use crate::kernel::{Kernel, KernelError};
+use crate::sidereal::Zodiac;
+
+// House cusps from sidereal time and obliquity alone, so readers can cast
+// charts for any place without the live ephemeris. Uses mean sidereal time
+// and mean obliquity with no nutation, so against the true equinox the
+// kernel positions use, the MC can be off by up to about 18″ and the other
+// cusps by more as latitude grows: about 40″ at 45°, 1′ at 55°, 1.5′ at 60°,
+// and several arcminutes close to the polar circles.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HouseSystem {
+    Placidus,
+    Koch,
+    Porphyry,
+    Regiomontanus,
+    Equal,
+    WholeSign,
+}
+
+impl HouseSystem {
+    pub const ALL: [HouseSystem; 6] = [
+        HouseSystem::Placidus, HouseSystem::Koch, HouseSystem::Porphyry,
+        HouseSystem::Regiomontanus, HouseSystem::Equal, HouseSystem::WholeSign,
+    ];
+
+    /// Swiss Ephemeris house system letter.
+    pub fn code(&self) -> char {
+        match self {
+            HouseSystem::Placidus => 'P',
+            HouseSystem::Koch => 'K',
+            HouseSystem::Porphyry => 'O',
+            HouseSystem::Regiomontanus => 'R',
+            HouseSystem::Equal => 'E',
+            HouseSystem::WholeSign => 'W',
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HouseSystem::Placidus => "Placidus",
+            HouseSystem::Koch => "Koch",
+            HouseSystem::Porphyry => "Porphyry",
+            HouseSystem::Regiomontanus => "Regiomontanus",
+            HouseSystem::Equal => "Equal",
+            HouseSystem::WholeSign => "Whole Sign",
+        }
+    }
+
+    /// Accepts the house system letter or name.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let wanted: String = s.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+        HouseSystem::ALL.into_iter()
+            .find(|system| {
+                wanted.eq_ignore_ascii_case(&system.code().to_string())
+                    || wanted.eq_ignore_ascii_case(&system.name().replace(' ', ""))
+            })
+            .ok_or_else(|| format!("unknown house system {}", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Houses {
+    /// The system actually used: Placidus and Koch fall back to Porphyry
+    /// inside the polar circles, as Swiss Ephemeris does.
+    pub system: HouseSystem,
+    /// cusps[0] is the first house.
+    pub cusps: [f64; 12],
+    pub ascendant: f64,
+    pub mc: f64,
+    /// Right ascension of the MC, degrees.
+    pub armc: f64,
+}
+
+impl Houses {
+    /// House (1..=12) containing `longitude`.
+    pub fn house_of(&self, longitude: f64) -> usize {
+        (0..12)
+            .find(|&i| {
+                let span = (self.cusps[(i + 1) % 12] - self.cusps[i]).rem_euclid(360.0);
+                (longitude - self.cusps[i]).rem_euclid(360.0) < span
+            })
+            .map_or(1, |i| i + 1)
+    }
+
+    /// The same houses measured from a zodiac shifted by `offset` degrees
+    /// (the ayanamsa for sidereal charts).
+    pub fn shifted(&self, offset: f64) -> Self {
+        let ascendant = (self.ascendant - offset).rem_euclid(360.0);
+        let cusps = match self.system {
+            HouseSystem::WholeSign => whole_sign_cusps(ascendant),
+            _ => self.cusps.map(|c| (c - offset).rem_euclid(360.0)),
+        };
+        Self { cusps, ascendant, mc: (self.mc - offset).rem_euclid(360.0), ..*self }
+    }
+}
+
+/// Houses at `jd_ut` for `location`, tropical.
+pub fn houses(jd_ut: f64, location: GeoLocation, system: HouseSystem) -> Houses {
+    let armc = (sidereal_time(jd_ut) + location.longitude).rem_euclid(360.0);
+    houses_from_armc(armc, location.latitude, mean_obliquity(jd_ut), system)
+}
+
+pub fn houses_from_armc(armc: f64, latitude: f64, obliquity: f64, system: HouseSystem) -> Houses {
+    let ascendant = ascendant_for(armc, latitude, obliquity);
+    let mc = longitude_from_ra(armc, obliquity);
+    let quadrant = |c11, c12, c2, c3| quadrant_cusps(ascendant, mc, [c11, c12, c2, c3]);
+    let asc_at = |ramc: f64, pole: f64| ascendant_for(ramc, pole, obliquity);
+
+    let (system, cusps) = match system {
+        HouseSystem::Placidus => match placidus(armc, latitude, obliquity) {
+            Some(c) => (system, quadrant(c[0], c[1], c[2], c[3])),
+            None => (HouseSystem::Porphyry, porphyry_cusps(ascendant, mc)),
+        },
+        HouseSystem::Koch => {
+            let declination = (obliquity.to_radians().sin() * mc.to_radians().sin()).asin();
+            let x = latitude.to_radians().tan() * declination.tan();
+            if x.abs() > 1.0 {
+                (HouseSystem::Porphyry, porphyry_cusps(ascendant, mc))
+            } else {
+                // Trisect the MC degree's semi-diurnal arc in time
+                let sda = 90.0 + x.asin().to_degrees();
+                (system, quadrant(
+                    asc_at(armc - 2.0 * sda / 3.0, latitude),
+                    asc_at(armc - sda / 3.0, latitude),
+                    asc_at(armc + sda / 3.0, latitude),
+                    asc_at(armc + 2.0 * sda / 3.0, latitude),
+                ))
+            }
+        }
+        HouseSystem::Regiomontanus => {
+            let tan_lat = latitude.to_radians().tan();
+            let pole_1 = (tan_lat * 0.5).atan().to_degrees();
+            let pole_2 = (tan_lat * 30f64.to_radians().cos()).atan().to_degrees();
+            (system, quadrant(
+                asc_at(armc - 60.0, pole_1),
+                asc_at(armc - 30.0, pole_2),
+                asc_at(armc + 30.0, pole_2),
+                asc_at(armc + 60.0, pole_1),
+            ))
+        }
+        HouseSystem::Porphyry => (system, porphyry_cusps(ascendant, mc)),
+        HouseSystem::Equal => (system, std::array::from_fn(|i| (ascendant + 30.0 * i as f64).rem_euclid(360.0))),
+        HouseSystem::WholeSign => (system, whole_sign_cusps(ascendant)),
+    };
+    Houses { system, cusps, ascendant, mc, armc }
+}
+
+// Ecliptic longitude rising when the MC has right ascension `ramc`, for a
+// horizon of pole height `pole`.
+fn ascendant_for(ramc: f64, pole: f64, obliquity: f64) -> f64 {
+    let (r, p, e) = (ramc.to_radians(), pole.to_radians(), obliquity.to_radians());
+    r.cos().atan2(-(r.sin() * e.cos() + p.tan() * e.sin())).to_degrees().rem_euclid(360.0)
+}
+
+// Ecliptic longitude with right ascension `ra`.
+fn longitude_from_ra(ra: f64, obliquity: f64) -> f64 {
+    let (r, e) = (ra.to_radians(), obliquity.to_radians());
+    r.sin().atan2(r.cos() * e.cos()).to_degrees().rem_euclid(360.0)
+}
+
+fn quadrant_cusps(ascendant: f64, mc: f64, [c11, c12, c2, c3]: [f64; 4]) -> [f64; 12] {
+    let opposite = |c: f64| (c + 180.0).rem_euclid(360.0);
+    [
+        ascendant, c2, c3, opposite(mc), opposite(c11), opposite(c12),
+        opposite(ascendant), opposite(c2), opposite(c3), mc, c11, c12,
+    ]
+}
+
+fn porphyry_cusps(ascendant: f64, mc: f64) -> [f64; 12] {
+    let upper = (ascendant - mc).rem_euclid(360.0);
+    let lower = 180.0 - upper;
+    let at = |from: f64, arc: f64| (from + arc).rem_euclid(360.0);
+    quadrant_cusps(ascendant, mc, [
+        at(mc, upper / 3.0),
+        at(mc, 2.0 * upper / 3.0),
+        at(ascendant, lower / 3.0),
+        at(ascendant, 2.0 * lower / 3.0),
+    ])
+}
+
+fn whole_sign_cusps(ascendant: f64) -> [f64; 12] {
+    let first = (ascendant / 30.0).floor() * 30.0;
+    std::array::from_fn(|i| (first + 30.0 * i as f64).rem_euclid(360.0))
+}
+
+// Cusps 11, 12, 2 and 3: each point has covered its fraction of its own
+// semi-arc, solved by iterating on the cusp's declination. None inside the
+// polar circles, where some points never rise.
+fn placidus(armc: f64, latitude: f64, obliquity: f64) -> Option<[f64; 4]> {
+    let tan_lat = latitude.to_radians().tan();
+    let sin_eps = obliquity.to_radians().sin();
+    // (offset from ARMC, fraction of the semi-diurnal arc)
+    let cusps = [(0.0, 1.0 / 3.0), (0.0, 2.0 / 3.0), (60.0, 2.0 / 3.0), (120.0, 1.0 / 3.0)];
+
+    let mut out = [0.0; 4];
+    for (slot, &(offset, fraction)) in out.iter_mut().zip(&cusps) {
+        let mut longitude = longitude_from_ra(armc + offset + 90.0 * fraction, obliquity);
+        for _ in 0..100 {
+            let declination = (sin_eps * longitude.to_radians().sin()).asin();
+            let x = -tan_lat * declination.tan();
+            if x.abs() > 1.0 {
+                return None;
+            }
+            let sda = x.acos().to_degrees();
+            let next = longitude_from_ra(armc + offset + fraction * sda, obliquity);
+            let done = (next - longitude).abs() < 1e-10;
+            longitude = next;
+            if done {
+                break;
+            }
+        }
+        *slot = longitude;
+    }
+    Some(out)
+}
+
+impl Kernel {
+    /// Houses at `jd` for `location` in the given zodiac. Sidereal cusps
+    /// subtract the stored ayanamsa.
+    pub fn houses_in(&self, jd: f64, location: GeoLocation, system: HouseSystem,
+                     zodiac: Zodiac) -> Result<Houses, KernelError> {
+        let tropical = houses(jd, location, system);
+        match zodiac {
+            Zodiac::Tropical => Ok(tropical),
+            Zodiac::Sidereal(mode) => Ok(tropical.shifted(self.ayanamsa_at(mode, jd)?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::arc;
+
+    const OBLIQUITY: f64 = 23.44;
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        arc(a, b).abs() < tolerance
+    }
+
+    #[test]
+    fn angles_from_armc() {
+        // At the equator with ARMC 0 the MC is 0° Aries and Cancer rises
+        let houses = houses_from_armc(0.0, 0.0, OBLIQUITY, HouseSystem::Porphyry);
+        assert!(close(houses.mc, 0.0, 1e-9) && close(houses.ascendant, 90.0, 1e-9));
+        // tan(51.5°) sin(23.44°) ≈ 0.5, so the ascendant is atan2(1, -0.5)
+        let london = houses_from_armc(0.0, 51.5, OBLIQUITY, HouseSystem::Porphyry);
+        assert!(close(london.ascendant, 116.57, 0.01), "{}", london.ascendant);
+    }
+
+    #[test]
+    fn quadrant_systems_share_the_angles() {
+        for system in [HouseSystem::Placidus, HouseSystem::Koch, HouseSystem::Porphyry, HouseSystem::Regiomontanus] {
+            for armc in [0.0, 75.0, 200.0, 333.0] {
+                let houses = houses_from_armc(armc, 40.0, OBLIQUITY, system);
+                assert_eq!(houses.system, system);
+                assert!(close(houses.cusps[0], houses.ascendant, 1e-9) && close(houses.cusps[9], houses.mc, 1e-9));
+                for i in 0..6 {
+                    assert!(close(houses.cusps[i + 6], houses.cusps[i] + 180.0, 1e-9), "{:?} {:?}", system, houses.cusps);
+                }
+                // Cusps run forward around the zodiac
+                let total: f64 = (0..12).map(|i| (houses.cusps[(i + 1) % 12] - houses.cusps[i]).rem_euclid(360.0)).sum();
+                assert!((total - 360.0).abs() < 1e-6, "{:?} {:?}", system, houses.cusps);
+            }
+        }
+    }
+
+    #[test]
+    fn quadrant_systems_agree_on_the_equator() {
+        // Every semi-arc is 90° there, so time and space trisections coincide
+        let placidus = houses_from_armc(123.0, 0.0, OBLIQUITY, HouseSystem::Placidus);
+        for system in [HouseSystem::Koch, HouseSystem::Regiomontanus] {
+            let other = houses_from_armc(123.0, 0.0, OBLIQUITY, system);
+            assert!((0..12).all(|i| close(placidus.cusps[i], other.cusps[i], 1e-6)), "{:?}", system);
+        }
+    }
+
+    #[test]
+    fn polar_fallback_to_porphyry() {
+        let houses = houses_from_armc(0.0, 75.0, OBLIQUITY, HouseSystem::Placidus);
+        assert_eq!(houses.system, HouseSystem::Porphyry);
+        assert_eq!(houses.cusps, houses_from_armc(0.0, 75.0, OBLIQUITY, HouseSystem::Porphyry).cusps);
+        // Koch only fails once the MC degree itself is circumpolar
+        assert_eq!(houses_from_armc(0.0, 75.0, OBLIQUITY, HouseSystem::Koch).system, HouseSystem::Koch);
+        assert_eq!(houses_from_armc(90.0, 75.0, OBLIQUITY, HouseSystem::Koch).system, HouseSystem::Porphyry);
+    }
+
+    #[test]
+    fn equal_and_whole_sign() {
+        let equal = houses_from_armc(75.0, 40.0, OBLIQUITY, HouseSystem::Equal);
+        assert!((0..12).all(|i| close(equal.cusps[i], equal.ascendant + 30.0 * i as f64, 1e-9)));
+
+        let whole = houses_from_armc(75.0, 40.0, OBLIQUITY, HouseSystem::WholeSign);
+        assert_eq!(whole.cusps[0], (whole.ascendant / 30.0).floor() * 30.0);
+        assert!(whole.cusps.iter().all(|c| c % 30.0 == 0.0));
+        assert_eq!(whole.house_of(whole.ascendant), 1);
+        assert_eq!(whole.house_of(whole.cusps[0] - 0.5), 12);
+
+        // Shifting into a sidereal zodiac moves whole signs with the ascendant
+        let shifted = whole.shifted(24.0);
+        assert!(close(shifted.ascendant, whole.ascendant - 24.0, 1e-9));
+        assert_eq!(shifted.cusps[0], (shifted.ascendant / 30.0).floor() * 30.0);
+    }
+
+    #[test]
+    fn house_of_wraps_through_aries() {
+        let houses = houses_from_armc(0.0, 51.5, OBLIQUITY, HouseSystem::Placidus);
+        for house in 0..12 {
+            let next = houses.cusps[(house + 1) % 12];
+            let middle = houses.cusps[house] + (next - houses.cusps[house]).rem_euclid(360.0) / 2.0;
+            assert_eq!(houses.house_of(middle.rem_euclid(360.0)), house + 1);
+        }
+    }
+
+    #[test]
+    fn parses_letters_and_names() {
+        assert_eq!(HouseSystem::parse("P"), Ok(HouseSystem::Placidus));
+        assert_eq!(HouseSystem::parse("whole sign"), Ok(HouseSystem::WholeSign));
+        assert_eq!(HouseSystem::parse("regiomontanus"), Ok(HouseSystem::Regiomontanus));
+        assert!(HouseSystem::parse("Z").is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use std::fmt;
+use crate::aspects::Aspect;
+use crate::bodies::{body_name, sign_index, SIGN_NAMES};
+use crate::chart::Chart;
+use crate::events::{Event, EventKind};
+use crate::houses::Houses;
+use crate::kernel::Position;
+use crate::synastry::{HouseOverlay, Relationship};
+use crate::time::jd_to_datetime;
+
+// Just enough JSON for the server and machine-readable output, so the crate
+// does not need serde.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// Non-finite numbers are written as null.
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Fields keep their insertion order.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn object<I, K>(fields: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Json)>,
+        K: Into<String>,
+    {
+        Json::Object(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    /// Appends a field; does nothing for values that are not objects.
+    pub fn push(&mut self, key: impl Into<String>, value: Json) {
+        if let Json::Object(fields) = self {
+            fields.push((key.into(), value));
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+fn write_escaped(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) if n.is_finite() => write!(f, "{}", n),
+            Json::Number(_) => f.write_str("null"),
+            Json::String(s) => write_escaped(f, s),
+            Json::Array(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str("]")
+            }
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_escaped(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl From<f64> for Json {
+    fn from(n: f64) -> Self {
+        Json::Number(n)
+    }
+}
+
+impl From<i32> for Json {
+    fn from(n: i32) -> Self {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(n: u32) -> Self {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(n: usize) -> Self {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Json::Bool(b)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Self {
+        Json::String(s)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(items: Vec<T>) -> Self {
+        Json::Array(items.into_iter().map(Into::into).collect())
+    }
+}
+
+/// UTC date/time as RFC 3339, or null.
+pub fn datetime_json(jd: Option<f64>) -> Json {
+    jd.map(|jd| jd_to_datetime(jd).format("%Y-%m-%dT%H:%M:%SZ").to_string()).into()
+}
+
+/// JD with its UTC date/time alongside.
+pub fn time_json(jd: f64) -> Vec<(&'static str, Json)> {
+    vec![("jd", jd.into()), ("datetime", datetime_json(Some(jd)))]
+}
+
+/// Sign name and degree within the sign.
+pub fn sign_json(longitude: f64) -> [(&'static str, Json); 2] {
+    [
+        ("sign", SIGN_NAMES[sign_index(longitude)].into()),
+        ("degree", longitude.rem_euclid(30.0).into()),
+    ]
+}
+
+pub fn position_json(body: i32, pos: &Position) -> Json {
+    let [sign, degree] = sign_json(pos.longitude);
+    Json::object([
+        ("body", body.into()),
+        ("name", body_name(body).into()),
+        ("longitude", pos.longitude.into()),
+        ("latitude", pos.latitude.into()),
+        ("distance", pos.distance.into()),
+        ("speed", pos.speed_longitude.into()),
+        sign,
+        degree,
+        ("retrograde", pos.is_retrograde().into()),
+    ])
+}
+
+pub fn houses_json(houses: &Houses) -> Json {
+    Json::object([
+        ("system", houses.system.name().into()),
+        ("ascendant", houses.ascendant.into()),
+        ("mc", houses.mc.into()),
+        ("armc", houses.armc.into()),
+        ("cusps", houses.cusps.to_vec().into()),
+    ])
+}
+
+pub fn chart_json(chart: &Chart) -> Json {
+    let mut fields = time_json(chart.jd);
+    fields.extend([
+        ("latitude", chart.location.latitude.into()),
+        ("longitude", chart.location.longitude.into()),
+        ("zodiac", chart.zodiac.name().into()),
+        ("positions", Json::Array(chart.positions.iter()
+            .map(|(body, pos)| {
+                let mut json = position_json(*body, pos);
+                json.push("house", chart.houses.house_of(pos.longitude).into());
+                json
+            })
+            .collect())),
+        ("houses", houses_json(&chart.houses)),
+    ]);
+    Json::object(fields)
+}
+
+pub fn aspect_json(aspect: &Aspect) -> Json {
+    Json::object([
+        ("body_a", aspect.body_a.into()),
+        ("name_a", body_name(aspect.body_a).into()),
+        ("body_b", aspect.body_b.into()),
+        ("name_b", body_name(aspect.body_b).into()),
+        ("aspect", aspect.kind.name.as_str().into()),
+        ("angle", aspect.kind.angle.into()),
+        ("orb", aspect.orb.into()),
+        ("applying", aspect.applying.into()),
+    ])
+}
+
+pub fn overlay_json(overlay: &HouseOverlay) -> Json {
+    Json::object([
+        ("body", overlay.body.into()),
+        ("name", body_name(overlay.body).into()),
+        ("longitude", overlay.longitude.into()),
+        ("house", overlay.house.into()),
+    ])
+}
+
+/// Both natal charts, the synastry (aspects from A to B and each person's
+/// bodies in the other's houses) and the composite and Davison charts.
+pub fn relationship_json(relationship: &Relationship) -> Json {
+    let synastry = &relationship.synastry;
+    Json::object([
+        ("a", chart_json(&relationship.a)),
+        ("b", chart_json(&relationship.b)),
+        ("aspects", Json::Array(synastry.aspects.iter().map(aspect_json).collect())),
+        ("a_in_b", Json::Array(synastry.a_in_b.iter().map(overlay_json).collect())),
+        ("b_in_a", Json::Array(synastry.b_in_a.iter().map(overlay_json).collect())),
+        ("composite", chart_json(&relationship.composite)),
+        ("davison", chart_json(&relationship.davison)),
+    ])
+}
+
+pub fn event_json(event: &Event) -> Json {
+    let mut fields = time_json(event.jd);
+    fields.extend([
+        ("body", event.body.into()),
+        ("name", body_name(event.body).into()),
+        ("longitude", event.longitude.into()),
+    ]);
+    fields.extend(sign_json(event.longitude));
+    match event.kind {
+        EventKind::Ingress { boundary, index, retrograde } => fields.extend([
+            ("type", "ingress".into()),
+            ("enters", boundary.label(index).into()),
+            ("index", index.into()),
+            ("retrograde", retrograde.into()),
+        ]),
+        EventKind::Station { retrograde } => fields.extend([
+            ("type", "station".into()),
+            ("retrograde", retrograde.into()),
+        ]),
+    }
+    Json::object(fields)
+}
@@ -0,0 +1,676 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use memmap2::{Mmap, MmapOptions};
+use thiserror::Error;
+use crate::bodies::{DEFAULT_BODIES, SOUTH_NODE, TRUE_NODE};
+use crate::frame::{Frame, TOPO_TAG};
+use crate::sidereal::{snapshot_section, Ayanamsa, AYANAMSA_TAG, DEFAULT_MODES};
+
+// ZNTH v2 layout (all little-endian):
+//
+//   0   magic "ZNTH"
+//   4   version u8
+//   5   frame u8 (see frame.rs; 0 = geocentric)
+//   6   body count u16
+//   8   start JD f64
+//   16  step in days f64
+//   24  record count u32
+//   28  section count u32
+//   32  body ids i32 × body count
+//   ..  section table: tag [u8; 4], offset u64, length u64
+//   ..  records: record count × body count × [lon, lat, dist, speed lon, speed lat, speed dist]
+//   ..  section payloads
+pub const MAGIC: &[u8; 4] = b"ZNTH";
+pub const VERSION: u8 = 2;
+pub const VALUES_PER_BODY: usize = 6;
+
+const HEADER_LEN: usize = 32;
+const SECTION_ENTRY_LEN: usize = 20;
+
+// v1 kernels, as the original medusajpl_sidereal wrote them, have no header:
+// a single snapshot of JD f64 and 20 longitudes f64 in the old generator
+// order, where slots 17 and 18 (ASC, ARMC) were really computed as bodies 0
+// and 1. Later files append the DEFAULT_MODES ayanamsas. Readers load them
+// as one-record v2 kernels with zero speeds, valid at that JD only.
+const V1_LEN: usize = 8 + 20 * 8;
+const V1_AYANAMSA_LEN: usize = V1_LEN + 8 * DEFAULT_MODES.len();
+const V1_SLOTS: [usize; 18] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 19];
+
+#[derive(Debug, Error)]
+pub enum KernelError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("not a zenith kernel (bad magic)")]
+    BadMagic,
+    #[error("unsupported kernel version {0}")]
+    UnsupportedVersion(u8),
+    #[error("kernel truncated: needed {needed} bytes, have {have}")]
+    Truncated { needed: usize, have: usize },
+    #[error("JD {jd} outside kernel range {start}..{end}")]
+    OutOfRange { jd: f64, start: f64, end: f64 },
+    #[error("body {0} is not stored in this kernel")]
+    UnknownBody(i32),
+    #[error("section {0} is missing or malformed")]
+    BadSection(String),
+    #[error("ayanamsa mode {0} is not stored in this kernel")]
+    AyanamsaNotStored(i32),
+    #[error("kernel was generated for {latitude}, {longitude}, not this location")]
+    LocationMismatch { latitude: f64, longitude: f64 },
+    #[error("fixed star {0} is not stored in this kernel")]
+    UnknownStar(String),
+    #[error("scan step must be a positive number of days, got {0}")]
+    BadStep(f64),
+    #[error("dasha levels must be 1 to 3, got {0}")]
+    BadDashaLevels(u8),
+    #[error("unknown frame code {0}")]
+    UnknownFrame(u8),
+    #[error("kernel frame is {found}, expected {expected}")]
+    FrameMismatch { expected: String, found: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Position {
+    pub longitude: f64,
+    pub latitude: f64,
+    pub distance: f64,
+    pub speed_longitude: f64,
+    pub speed_latitude: f64,
+    pub speed_distance: f64,
+}
+
+impl Position {
+    pub fn from_xx(xx: &[f64; 6]) -> Self {
+        Self {
+            longitude: xx[0].rem_euclid(360.0),
+            latitude: xx[1],
+            distance: xx[2],
+            speed_longitude: xx[3],
+            speed_latitude: xx[4],
+            speed_distance: xx[5],
+        }
+    }
+
+    pub fn is_retrograde(&self) -> bool {
+        self.speed_longitude < 0.0
+    }
+
+    pub(crate) fn values(&self) -> [f64; VALUES_PER_BODY] {
+        [self.longitude, self.latitude, self.distance,
+         self.speed_longitude, self.speed_latitude, self.speed_distance]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SectionEntry {
+    pub tag: [u8; 4],
+    pub offset: u64,
+    pub length: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct KernelHeader {
+    pub version: u8,
+    pub frame: Frame,
+    pub start_jd: f64,
+    pub step: f64,
+    pub record_count: u32,
+    pub bodies: Vec<i32>,
+    pub sections: Vec<SectionEntry>,
+}
+
+impl KernelHeader {
+    pub fn end_jd(&self) -> f64 {
+        self.start_jd + self.step * (self.record_count.saturating_sub(1)) as f64
+    }
+
+    fn records_offset(&self) -> usize {
+        HEADER_LEN + 4 * self.bodies.len() + SECTION_ENTRY_LEN * self.sections.len()
+    }
+
+    fn record_len(&self) -> usize {
+        self.bodies.len() * VALUES_PER_BODY * 8
+    }
+}
+
+enum KernelBytes {
+    #[cfg(not(target_arch = "wasm32"))]
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+pub struct Kernel {
+    bytes: KernelBytes,
+    header: KernelHeader,
+}
+
+impl Kernel {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, KernelError> {
+        let file = File::open(path)?;
+        let map = unsafe { MmapOptions::new().map(&file)? };
+        if let Some(bytes) = upgrade_v1(&map) {
+            return Self::from_bytes(bytes);
+        }
+        let header = parse_header(&map)?;
+        Ok(Self { bytes: KernelBytes::Mapped(map), header })
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, KernelError> {
+        let bytes = upgrade_v1(&bytes).unwrap_or(bytes);
+        let header = parse_header(&bytes)?;
+        Ok(Self { bytes: KernelBytes::Owned(bytes), header })
+    }
+
+    pub fn header(&self) -> &KernelHeader {
+        &self.header
+    }
+
+    pub fn bodies(&self) -> &[i32] {
+        &self.header.bodies
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        match &self.bytes {
+            #[cfg(not(target_arch = "wasm32"))]
+            KernelBytes::Mapped(map) => map,
+            KernelBytes::Owned(vec) => vec,
+        }
+    }
+
+    pub fn body_index(&self, body: i32) -> Result<usize, KernelError> {
+        self.header.bodies.iter()
+            .position(|&b| b == body)
+            .ok_or(KernelError::UnknownBody(body))
+    }
+
+    pub fn section(&self, tag: &[u8; 4]) -> Option<&[u8]> {
+        let entry = self.header.sections.iter().find(|s| &s.tag == tag)?;
+        let start = usize::try_from(entry.offset).ok()?;
+        let end = start.checked_add(usize::try_from(entry.length).ok()?)?;
+        self.bytes().get(start..end)
+    }
+
+    /// Raw stored values for one body at record `index`.
+    pub fn record(&self, index: usize, body_index: usize) -> Position {
+        let offset = self.header.records_offset()
+            + index * self.header.record_len()
+            + body_index * VALUES_PER_BODY * 8;
+        let bytes = self.bytes();
+        let mut xx = [0.0; VALUES_PER_BODY];
+        for (i, value) in xx.iter_mut().enumerate() {
+            *value = read_f64(bytes, offset + i * 8);
+        }
+        Position {
+            longitude: xx[0],
+            latitude: xx[1],
+            distance: xx[2],
+            speed_longitude: xx[3],
+            speed_latitude: xx[4],
+            speed_distance: xx[5],
+        }
+    }
+
+    pub fn record_jd(&self, index: usize) -> f64 {
+        self.header.start_jd + index as f64 * self.header.step
+    }
+
+    pub fn check_range(&self, jd: f64) -> Result<(), KernelError> {
+        let (start, end) = (self.header.start_jd, self.header.end_jd());
+        if !(start..=end).contains(&jd) || self.header.record_count == 0 {
+            return Err(KernelError::OutOfRange { jd, start, end });
+        }
+        Ok(())
+    }
+
+    /// Position of `body` at `jd`, Hermite-interpolated between the two
+    /// surrounding records using the stored speeds. SOUTH_NODE is derived
+    /// from TRUE_NODE.
+    pub fn position_at(&self, body: i32, jd: f64) -> Result<Position, KernelError> {
+        if body == SOUTH_NODE {
+            let mut pos = self.position_at(TRUE_NODE, jd)?;
+            pos.longitude = (pos.longitude + 180.0).rem_euclid(360.0);
+            pos.latitude = -pos.latitude;
+            pos.speed_latitude = -pos.speed_latitude;
+            return Ok(pos);
+        }
+        let body_index = self.body_index(body)?;
+        self.check_range(jd)?;
+
+        let step = self.header.step;
+        let last = self.header.record_count as usize - 1;
+        let index = (((jd - self.header.start_jd) / step).floor() as usize).min(last.saturating_sub(1));
+        let a = self.record(index, body_index);
+        if last == 0 {
+            return Ok(a);
+        }
+        let b = self.record(index + 1, body_index);
+        let t = (jd - self.record_jd(index)) / step;
+
+        // Unwrap the longitude so 359° -> 1° interpolates across 0°
+        let lon_b = a.longitude + (b.longitude - a.longitude + 180.0).rem_euclid(360.0) - 180.0;
+        let (longitude, speed_longitude) = hermite(a.longitude, a.speed_longitude, lon_b, b.speed_longitude, t, step);
+        let (latitude, speed_latitude) = hermite(a.latitude, a.speed_latitude, b.latitude, b.speed_latitude, t, step);
+        let (distance, speed_distance) = hermite(a.distance, a.speed_distance, b.distance, b.speed_distance, t, step);
+
+        Ok(Position {
+            longitude: longitude.rem_euclid(360.0),
+            latitude,
+            distance,
+            speed_longitude,
+            speed_latitude,
+            speed_distance,
+        })
+    }
+
+    pub fn positions_at(&self, jd: f64) -> Result<Vec<(i32, Position)>, KernelError> {
+        self.header.bodies.iter()
+            .map(|&body| Ok((body, self.position_at(body, jd)?)))
+            .collect()
+    }
+}
+
+// Cubic Hermite on the unit interval; derivatives are per day, so they are
+// scaled by the step. Returns the value and its derivative per day.
+fn hermite(p0: f64, v0: f64, p1: f64, v1: f64, t: f64, step: f64) -> (f64, f64) {
+    let (m0, m1) = (v0 * step, v1 * step);
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let value = (2.0 * t3 - 3.0 * t2 + 1.0) * p0
+        + (t3 - 2.0 * t2 + t) * m0
+        + (-2.0 * t3 + 3.0 * t2) * p1
+        + (t3 - t2) * m1;
+    let deriv = (6.0 * t2 - 6.0 * t) * p0
+        + (3.0 * t2 - 4.0 * t + 1.0) * m0
+        + (-6.0 * t2 + 6.0 * t) * p1
+        + (3.0 * t2 - 2.0 * t) * m1;
+    (value, deriv / step)
+}
+
+pub fn read_f64(bytes: &[u8], offset: usize) -> f64 {
+    f64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+pub fn read_i32(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+pub fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+fn ensure_len(bytes: &[u8], needed: usize) -> Result<(), KernelError> {
+    if bytes.len() < needed {
+        return Err(KernelError::Truncated { needed, have: bytes.len() });
+    }
+    Ok(())
+}
+
+// The v2 bytes for a v1 snapshot, or None when `bytes` is not one.
+fn upgrade_v1(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.starts_with(MAGIC) || (bytes.len() != V1_LEN && bytes.len() != V1_AYANAMSA_LEN) {
+        return None;
+    }
+    let jd = read_f64(bytes, 0);
+    if !jd.is_finite() {
+        return None;
+    }
+    let positions: Vec<Position> = V1_SLOTS.iter()
+        .map(|slot| Position { longitude: read_f64(bytes, 8 + 8 * slot).rem_euclid(360.0), ..Position::default() })
+        .collect();
+    let mut writer = KernelWriter::new(jd, 1.0, &DEFAULT_BODIES);
+    writer.push_record(&positions);
+    if bytes.len() == V1_AYANAMSA_LEN {
+        let modes: Vec<(i32, f64)> = DEFAULT_MODES.iter()
+            .enumerate()
+            .filter_map(|(i, mode)| match mode {
+                Ayanamsa::Predefined(sid_mode) => Some((*sid_mode, read_f64(bytes, V1_LEN + 8 * i))),
+                Ayanamsa::User { .. } => None,
+            })
+            .collect();
+        writer.add_section(AYANAMSA_TAG, snapshot_section(jd, &modes));
+    }
+    Some(writer.to_bytes())
+}
+
+fn parse_header(bytes: &[u8]) -> Result<KernelHeader, KernelError> {
+    ensure_len(bytes, HEADER_LEN)?;
+    if &bytes[0..4] != MAGIC {
+        return Err(KernelError::BadMagic);
+    }
+    let version = bytes[4];
+    if version != VERSION {
+        return Err(KernelError::UnsupportedVersion(version));
+    }
+
+    let body_count = u16::from_le_bytes([bytes[6], bytes[7]]) as usize;
+    let start_jd = read_f64(bytes, 8);
+    let step = read_f64(bytes, 16);
+    let record_count = read_u32(bytes, 24);
+    let section_count = read_u32(bytes, 28) as usize;
+    if !start_jd.is_finite() || !step.is_finite() || step <= 0.0 {
+        return Err(KernelError::BadSection("header".to_string()));
+    }
+
+    // Every size below comes from the file, so none of it may overflow
+    let table_len = SECTION_ENTRY_LEN.checked_mul(section_count)
+        .and_then(|len| len.checked_add(HEADER_LEN + 4 * body_count))
+        .ok_or(KernelError::BadSection("section table".to_string()))?;
+    ensure_len(bytes, table_len)?;
+    let bodies = (0..body_count)
+        .map(|i| read_i32(bytes, HEADER_LEN + 4 * i))
+        .collect();
+
+    let table = HEADER_LEN + 4 * body_count;
+    let sections = (0..section_count)
+        .map(|i| {
+            let at = table + SECTION_ENTRY_LEN * i;
+            SectionEntry {
+                tag: bytes[at..at + 4].try_into().unwrap(),
+                offset: read_u64(bytes, at + 4),
+                length: read_u64(bytes, at + 12),
+            }
+        })
+        .collect();
+
+    let mut header = KernelHeader { version, frame: Frame::Geocentric, start_jd, step, record_count, bodies, sections };
+    let records_end = header.record_len().checked_mul(record_count as usize)
+        .and_then(|len| len.checked_add(header.records_offset()))
+        .ok_or(KernelError::BadSection("records".to_string()))?;
+    ensure_len(bytes, records_end)?;
+    let mut topo = None;
+    for section in &header.sections {
+        let tag = String::from_utf8_lossy(&section.tag).into_owned();
+        let range = usize::try_from(section.offset).ok()
+            .zip(usize::try_from(section.length).ok())
+            .and_then(|(offset, length)| Some(offset..offset.checked_add(length)?))
+            .ok_or(KernelError::BadSection(tag))?;
+        ensure_len(bytes, range.end)?;
+        if section.tag == TOPO_TAG {
+            topo.get_or_insert(&bytes[range]);
+        }
+    }
+    header.frame = Frame::from_header(bytes[5], topo)?;
+    Ok(header)
+}
+
+pub struct KernelWriter {
+    start_jd: f64,
+    step: f64,
+    bodies: Vec<i32>,
+    frame: Frame,
+    records: Vec<f64>,
+    sections: Vec<([u8; 4], Vec<u8>)>,
+}
+
+impl KernelWriter {
+    pub fn new(start_jd: f64, step: f64, bodies: &[i32]) -> Self {
+        Self {
+            start_jd,
+            step,
+            bodies: bodies.to_vec(),
+            frame: Frame::Geocentric,
+            records: Vec::new(),
+            sections: Vec::new(),
+        }
+    }
+
+    /// Appends one record; `positions` must follow the body order given to `new`.
+    pub fn push_record(&mut self, positions: &[Position]) {
+        assert_eq!(positions.len(), self.bodies.len(), "record does not match body list");
+        for pos in positions {
+            self.records.extend_from_slice(&pos.values());
+        }
+    }
+
+    /// Records the observer frame; topocentric frames add a TOPO section.
+    pub fn set_frame(&mut self, frame: Frame) {
+        self.sections.retain(|(tag, _)| *tag != TOPO_TAG);
+        if let Some(topo) = frame.topo_section() {
+            self.sections.push((TOPO_TAG, topo));
+        }
+        self.frame = frame;
+    }
+
+    pub fn add_section(&mut self, tag: [u8; 4], payload: Vec<u8>) {
+        self.sections.push((tag, payload));
+    }
+
+    pub fn record_count(&self) -> u32 {
+        (self.records.len() / (self.bodies.len() * VALUES_PER_BODY).max(1)) as u32
+    }
+
+    /// The finished kernel, for writing or for `Kernel::from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(self.frame.code());
+        out.extend_from_slice(&(self.bodies.len() as u16).to_le_bytes());
+        out.extend_from_slice(&self.start_jd.to_le_bytes());
+        out.extend_from_slice(&self.step.to_le_bytes());
+        out.extend_from_slice(&self.record_count().to_le_bytes());
+        out.extend_from_slice(&(self.sections.len() as u32).to_le_bytes());
+        for body in &self.bodies {
+            out.extend_from_slice(&body.to_le_bytes());
+        }
+
+        let mut payload_offset = (HEADER_LEN
+            + 4 * self.bodies.len()
+            + SECTION_ENTRY_LEN * self.sections.len()
+            + 8 * self.records.len()) as u64;
+        for (tag, payload) in &self.sections {
+            out.extend_from_slice(tag);
+            out.extend_from_slice(&payload_offset.to_le_bytes());
+            out.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+            payload_offset += payload.len() as u64;
+        }
+
+        for value in &self.records {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        for (_, payload) in &self.sections {
+            out.extend_from_slice(payload);
+        }
+        out
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<u64, KernelError> {
+        let out = self.to_bytes();
+        let mut file = File::create(path)?;
+        file.write_all(&out)?;
+        Ok(out.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bodies::{ASTEROID_5550, MOON, SUN};
+
+    const START: f64 = 2451545.0;
+    const STEP: f64 = 0.5;
+
+    // A cubic in time, so Hermite interpolation from the stored speeds
+    // reproduces it exactly between records.
+    fn cubic(t: f64) -> (f64, f64) {
+        (100.0 + 2.0 * t + 0.3 * t * t - 0.05 * t * t * t, 2.0 + 0.6 * t - 0.15 * t * t)
+    }
+
+    // The Sun follows `cubic`; the Moon runs at 13°/day from 350°, so it
+    // wraps through 0° during the second record.
+    fn sample_kernel() -> Vec<u8> {
+        let mut writer = KernelWriter::new(START, STEP, &[SUN, MOON]);
+        for i in 0..5 {
+            let t = i as f64 * STEP;
+            let (lon, speed) = cubic(t);
+            writer.push_record(&[
+                Position { longitude: lon, latitude: 0.1 * t, distance: 0.98, speed_longitude: speed, speed_latitude: 0.1, ..Position::default() },
+                Position { longitude: (350.0 + 13.0 * t) % 360.0, distance: 0.0026, speed_longitude: 13.0, ..Position::default() },
+            ]);
+        }
+        writer.add_section(*b"TEST", vec![1, 2, 3]);
+        writer.add_section(*b"MORE", vec![4, 5]);
+        writer.to_bytes()
+    }
+
+    fn set_f64(bytes: &mut [u8], offset: usize, value: f64) {
+        bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn v1_snapshot(jd: f64, with_ayanamsas: bool) -> Vec<u8> {
+        let mut bytes = jd.to_le_bytes().to_vec();
+        for slot in 0..20 {
+            bytes.extend_from_slice(&(10.0 * slot as f64 + 0.5).to_le_bytes());
+        }
+        if with_ayanamsas {
+            for ayanamsa in [24.7, 23.85, 23.86] {
+                bytes.extend_from_slice(&f64::to_le_bytes(ayanamsa));
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn round_trips_through_the_writer() {
+        let kernel = Kernel::from_bytes(sample_kernel()).unwrap();
+        let header = kernel.header();
+        assert_eq!(header.version, VERSION);
+        assert_eq!(header.frame, Frame::Geocentric);
+        assert_eq!(header.record_count, 5);
+        assert_eq!((header.start_jd, header.step, header.end_jd()), (START, STEP, START + 2.0));
+        assert_eq!(kernel.bodies(), &[SUN, MOON]);
+
+        let (lon, speed) = cubic(1.5);
+        let stored = kernel.record(3, 0);
+        assert_eq!((stored.longitude, stored.speed_longitude, stored.distance), (lon, speed, 0.98));
+        assert_eq!(kernel.record_jd(3), START + 1.5);
+    }
+
+    #[test]
+    fn looks_sections_up_by_tag() {
+        let kernel = Kernel::from_bytes(sample_kernel()).unwrap();
+        assert_eq!(kernel.section(b"TEST"), Some(&[1u8, 2, 3][..]));
+        assert_eq!(kernel.section(b"MORE"), Some(&[4u8, 5][..]));
+        assert_eq!(kernel.section(b"NONE"), None);
+    }
+
+    #[test]
+    fn interpolates_at_and_between_records() {
+        let kernel = Kernel::from_bytes(sample_kernel()).unwrap();
+        for t in [0.0, 0.5, 0.8, 1.25, 1.9, 2.0] {
+            let pos = kernel.position_at(SUN, START + t).unwrap();
+            let (lon, speed) = cubic(t);
+            assert!((pos.longitude - lon).abs() < 1e-9, "longitude at {}: {} vs {}", t, pos.longitude, lon);
+            assert!((pos.speed_longitude - speed).abs() < 1e-9, "speed at {}: {} vs {}", t, pos.speed_longitude, speed);
+            assert!((pos.latitude - 0.1 * t).abs() < 1e-9, "latitude at {}: {}", t, pos.latitude);
+        }
+
+        // 350° + 13° × 0.75 crosses 0°
+        let moon = kernel.position_at(MOON, START + 0.75).unwrap();
+        assert!((moon.longitude - 359.75).abs() < 1e-9, "{}", moon.longitude);
+        let moon = kernel.position_at(MOON, START + 1.0).unwrap();
+        assert!((moon.longitude - 3.0).abs() < 1e-9, "{}", moon.longitude);
+    }
+
+    #[test]
+    fn rejects_times_and_bodies_it_does_not_hold() {
+        let kernel = Kernel::from_bytes(sample_kernel()).unwrap();
+        assert!(matches!(kernel.position_at(SUN, START - 0.1), Err(KernelError::OutOfRange { .. })));
+        assert!(matches!(kernel.position_at(SUN, START + 2.1), Err(KernelError::OutOfRange { .. })));
+        assert!(matches!(kernel.position_at(SUN, f64::NAN), Err(KernelError::OutOfRange { .. })));
+        assert!(matches!(kernel.position_at(ASTEROID_5550, START), Err(KernelError::UnknownBody(_))));
+    }
+
+    #[test]
+    fn rejects_bad_magic_and_truncated_files() {
+        let bytes = sample_kernel();
+        let mut bad = bytes.clone();
+        bad[0..4].copy_from_slice(b"ZNTX");
+        assert!(matches!(Kernel::from_bytes(bad), Err(KernelError::BadMagic)));
+
+        let mut old = bytes.clone();
+        old[4] = 3;
+        assert!(matches!(Kernel::from_bytes(old), Err(KernelError::UnsupportedVersion(3))));
+
+        assert!(matches!(Kernel::from_bytes(bytes[..20].to_vec()), Err(KernelError::Truncated { needed: 32, .. })));
+        // Header and table intact, records cut short
+        assert!(matches!(Kernel::from_bytes(bytes[..100].to_vec()), Err(KernelError::Truncated { .. })));
+        // Last section payload cut short
+        assert!(matches!(Kernel::from_bytes(bytes[..bytes.len() - 1].to_vec()), Err(KernelError::Truncated { .. })));
+    }
+
+    #[test]
+    fn rejects_sizes_that_overflow_or_run_past_the_end() {
+        let bytes = sample_kernel();
+
+        let mut records = bytes.clone();
+        records[24..28].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(Kernel::from_bytes(records), Err(KernelError::Truncated { .. })));
+
+        let mut sections = bytes.clone();
+        sections[28..32].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(Kernel::from_bytes(sections), Err(KernelError::Truncated { .. })));
+
+        // The first table entry sits after the two body ids; offset + length
+        // overflows u64
+        let entry = HEADER_LEN + 4 * 2;
+        let mut offset = bytes.clone();
+        offset[entry + 4..entry + 12].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+        assert!(matches!(Kernel::from_bytes(offset), Err(KernelError::BadSection(tag)) if tag == "TEST"));
+    }
+
+    #[test]
+    fn rejects_nan_and_non_positive_steps() {
+        for step in [f64::NAN, f64::INFINITY, 0.0, -1.0] {
+            let mut bytes = sample_kernel();
+            set_f64(&mut bytes, 16, step);
+            assert!(matches!(Kernel::from_bytes(bytes), Err(KernelError::BadSection(_))), "step {}", step);
+        }
+        let mut bytes = sample_kernel();
+        set_f64(&mut bytes, 8, f64::NAN);
+        assert!(matches!(Kernel::from_bytes(bytes), Err(KernelError::BadSection(_))));
+    }
+
+    #[test]
+    fn loads_v1_snapshots_as_one_record() {
+        for with_ayanamsas in [false, true] {
+            let bytes = v1_snapshot(START, with_ayanamsas);
+            assert_eq!(bytes.len(), if with_ayanamsas { V1_AYANAMSA_LEN } else { V1_LEN });
+            let kernel = Kernel::from_bytes(bytes).unwrap();
+            assert_eq!(kernel.header().record_count, 1);
+            assert_eq!((kernel.header().start_jd, kernel.header().end_jd()), (START, START));
+            assert_eq!(kernel.bodies(), &DEFAULT_BODIES);
+
+            // Slots 17 and 18 (ASC, ARMC) are skipped, so 5550 reads slot 19
+            assert_eq!(kernel.position_at(SUN, START).unwrap().longitude, 0.5);
+            assert_eq!(kernel.position_at(MOON, START).unwrap().longitude, 10.5);
+            assert_eq!(kernel.position_at(ASTEROID_5550, START).unwrap().longitude, 190.5);
+            assert!(matches!(kernel.position_at(SUN, START + 1.0), Err(KernelError::OutOfRange { .. })));
+
+            let lahiri = kernel.ayanamsa_at(DEFAULT_MODES[1], START);
+            if with_ayanamsas {
+                assert_eq!(lahiri.unwrap(), 23.85);
+            } else {
+                assert!(lahiri.is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn leaves_other_headerless_files_alone() {
+        assert!(matches!(Kernel::from_bytes(vec![0; V1_LEN + 8]), Err(KernelError::BadMagic)));
+        let mut nan = v1_snapshot(START, false);
+        set_f64(&mut nan, 0, f64::NAN);
+        assert!(matches!(Kernel::from_bytes(nan), Err(KernelError::BadMagic)));
+    }
+}
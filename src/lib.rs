@@ -2,6 +2,52 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
+// Swiss Ephemeris is only called from ephemeris.rs and the modules built on
+// it, which are left out of wasm32 builds along with mmap and the filesystem;
+// what remains reads kernels from bytes.
 include!("bindings.rs");
 
-mod bindings;
\ No newline at end of file
+mod bindings;
+
+pub mod aspects;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod batch;
+pub mod bodies;
+pub mod chart;
+pub mod cli;
+pub mod coords;
+pub mod dasha;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod eclipse;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ephemeris;
+pub mod ephemeris_table;
+pub mod events;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ffi;
+pub mod frame;
+pub mod houses;
+pub mod json;
+pub mod kernel;
+pub mod lots;
+pub mod lunar;
+pub mod midpoints;
+pub mod output;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod planetary_hours;
+pub mod progressions;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod render;
+pub mod returns;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod riseset;
+pub mod search;
+pub mod sidereal;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod stars;
+pub mod synastry;
+pub mod time;
+pub mod vedic;
+#[cfg(feature = "wasm")]
+pub mod wasm;
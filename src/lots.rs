@@ -0,0 +1,231 @@
+use crate::bodies::{body_from_name, JUPITER, MARS, MERCURY, MOON, SATURN, SUN, VENUS};
+use crate::chart::Chart;
+
+/// A term in a lot formula.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LotPoint {
+    Body(i32),
+    Ascendant,
+    Mc,
+    /// House cusp, 1..=12.
+    Cusp(usize),
+    /// Another lot in the same catalog, by name.
+    Lot(String),
+}
+
+impl LotPoint {
+    /// Accepts asc, mc, dsc, ic, h1..h12, a body name, or else a lot name.
+    pub fn parse(s: &str) -> Self {
+        let lower = s.trim().to_ascii_lowercase();
+        match lower.as_str() {
+            "asc" | "ascendant" => return LotPoint::Ascendant,
+            "mc" | "midheaven" => return LotPoint::Mc,
+            "dsc" | "descendant" => return LotPoint::Cusp(7),
+            "ic" => return LotPoint::Cusp(4),
+            _ => {}
+        }
+        if let Some(house) = lower.strip_prefix('h').and_then(|n| n.parse().ok()).filter(|n| (1..=12).contains(n)) {
+            return LotPoint::Cusp(house);
+        }
+        match body_from_name(&lower) {
+            Some(body) => LotPoint::Body(body),
+            None => LotPoint::Lot(s.trim().to_string()),
+        }
+    }
+}
+
+/// base + add - subtract, with add and subtract swapped in night charts
+/// when `reverse` is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lot {
+    pub name: String,
+    pub base: LotPoint,
+    pub add: LotPoint,
+    pub subtract: LotPoint,
+    pub reverse: bool,
+}
+
+impl Lot {
+    pub fn new(name: &str, base: LotPoint, add: LotPoint, subtract: LotPoint, reverse: bool) -> Self {
+        Self { name: name.to_string(), base, add, subtract, reverse }
+    }
+
+    /// Parses "Name=base+add-subtract", reversed by night unless followed
+    /// by ";fixed", e.g. "Marriage=asc+venus-saturn;fixed".
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (name, formula) = s.split_once('=').ok_or_else(|| format!("lot {} needs name=formula", s))?;
+        let (formula, reverse) = match formula.strip_suffix(";fixed") {
+            Some(f) => (f, false),
+            None => (formula, true),
+        };
+        let (base, rest) = formula.split_once('+').ok_or_else(|| format!("lot {} needs base+add-subtract", s))?;
+        let (add, subtract) = rest.split_once('-').ok_or_else(|| format!("lot {} needs base+add-subtract", s))?;
+        Ok(Self::new(name.trim(), LotPoint::parse(base), LotPoint::parse(add), LotPoint::parse(subtract), reverse))
+    }
+}
+
+/// Fortune, Spirit and the other Hermetic lots of Paulus Alexandrinus,
+/// plus the lots of the Father and Mother.
+pub fn traditional_lots() -> Vec<Lot> {
+    use LotPoint::{Ascendant as Asc, Body};
+    let lot = |name: &str| LotPoint::Lot(name.to_string());
+    vec![
+        Lot::new("Fortune", Asc, Body(MOON), Body(SUN), true),
+        Lot::new("Spirit", Asc, Body(SUN), Body(MOON), true),
+        Lot::new("Eros", Asc, Body(VENUS), lot("Spirit"), true),
+        Lot::new("Necessity", Asc, lot("Fortune"), Body(MERCURY), true),
+        Lot::new("Courage", Asc, lot("Fortune"), Body(MARS), true),
+        Lot::new("Victory", Asc, Body(JUPITER), lot("Spirit"), true),
+        Lot::new("Nemesis", Asc, lot("Fortune"), Body(SATURN), true),
+        Lot::new("Father", Asc, Body(SATURN), Body(SUN), true),
+        Lot::new("Mother", Asc, Body(MOON), Body(VENUS), true),
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LotPosition {
+    pub name: String,
+    pub longitude: f64,
+    /// House (1..=12) in the chart.
+    pub house: usize,
+}
+
+/// A day chart has the Sun above the horizon, in houses 7 to 12.
+pub fn is_day_chart(chart: &Chart) -> bool {
+    chart.house_of(SUN).is_some_and(|house| house >= 7)
+}
+
+impl Chart {
+    /// Every lot in `catalog`, in catalog order. Lots may refer to other
+    /// lots in the catalog in any order, but not in a cycle.
+    pub fn lots(&self, catalog: &[Lot]) -> Result<Vec<LotPosition>, String> {
+        let day = is_day_chart(self);
+        catalog.iter()
+            .map(|lot| {
+                let longitude = self.lot_longitude(lot, catalog, day, 0)?;
+                Ok(LotPosition { name: lot.name.clone(), longitude, house: self.houses.house_of(longitude) })
+            })
+            .collect()
+    }
+
+    fn lot_longitude(&self, lot: &Lot, catalog: &[Lot], day: bool, depth: usize) -> Result<f64, String> {
+        if depth > catalog.len() {
+            return Err(format!("lot {} refers to itself", lot.name));
+        }
+        let point = |p: &LotPoint| -> Result<f64, String> {
+            match p {
+                LotPoint::Body(body) => self.position(*body)
+                    .map(|pos| pos.longitude)
+                    .ok_or_else(|| format!("lot {} needs body {}, which the chart lacks", lot.name, body)),
+                LotPoint::Ascendant => Ok(self.houses.ascendant),
+                LotPoint::Mc => Ok(self.houses.mc),
+                LotPoint::Cusp(house) => Ok(self.houses.cusps[(house - 1) % 12]),
+                LotPoint::Lot(name) => {
+                    let other = catalog.iter()
+                        .find(|l| l.name.eq_ignore_ascii_case(name))
+                        .ok_or_else(|| format!("lot {} refers to unknown point {}", lot.name, name))?;
+                    self.lot_longitude(other, catalog, day, depth + 1)
+                }
+            }
+        };
+        let (add, subtract) = if lot.reverse && !day {
+            (&lot.subtract, &lot.add)
+        } else {
+            (&lot.add, &lot.subtract)
+        };
+        Ok((point(&lot.base)? + point(add)? - point(subtract)?).rem_euclid(360.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coords::GeoLocation;
+    use crate::houses::{HouseSystem, Houses};
+    use crate::kernel::Position;
+    use crate::sidereal::Zodiac;
+
+    /// Equal houses from an Ascendant at 15°, with the given longitudes.
+    fn chart(positions: &[(i32, f64)]) -> Chart {
+        let cusps = std::array::from_fn(|i| 15.0 + 30.0 * i as f64);
+        Chart {
+            jd: 2451545.0,
+            location: GeoLocation::new(0.0, 0.0, 0.0),
+            zodiac: Zodiac::Tropical,
+            positions: positions.iter()
+                .map(|&(body, longitude)| (body, Position { longitude, ..Default::default() }))
+                .collect(),
+            houses: Houses { system: HouseSystem::Equal, cusps, ascendant: 15.0, mc: 280.0, armc: 282.0 },
+        }
+    }
+
+    fn longitude(lots: &[LotPosition], name: &str) -> f64 {
+        lots.iter().find(|l| l.name == name).unwrap().longitude
+    }
+
+    #[test]
+    fn parses_points_and_formulas() {
+        assert_eq!(LotPoint::parse("ASC"), LotPoint::Ascendant);
+        assert_eq!(LotPoint::parse("midheaven"), LotPoint::Mc);
+        assert_eq!(LotPoint::parse("dsc"), LotPoint::Cusp(7));
+        assert_eq!(LotPoint::parse("ic"), LotPoint::Cusp(4));
+        assert_eq!(LotPoint::parse("h11"), LotPoint::Cusp(11));
+        assert_eq!(LotPoint::parse("Venus"), LotPoint::Body(VENUS));
+        assert_eq!(LotPoint::parse(" Spirit "), LotPoint::Lot("Spirit".to_string()));
+        assert_eq!(LotPoint::parse("h13"), LotPoint::Lot("h13".to_string()));
+
+        let marriage = Lot::parse("Marriage=asc+venus-saturn;fixed").unwrap();
+        assert_eq!(marriage, Lot::new("Marriage", LotPoint::Ascendant, LotPoint::Body(VENUS), LotPoint::Body(SATURN), false));
+        assert!(Lot::parse("Fortune=asc+moon-sun").unwrap().reverse);
+        assert!(Lot::parse("asc+moon-sun").is_err());
+        assert!(Lot::parse("Fortune=asc-sun").is_err());
+        assert!(Lot::parse("Fortune=asc+moon").is_err());
+    }
+
+    #[test]
+    fn reverses_by_night() {
+        let day = chart(&[(SUN, 200.0), (MOON, 50.0), (VENUS, 220.0)]);
+        assert!(is_day_chart(&day));
+        let catalog: Vec<Lot> = traditional_lots().into_iter().take(3).collect();
+        let lots = day.lots(&catalog).unwrap();
+        assert_eq!(longitude(&lots, "Fortune"), 225.0);
+        assert_eq!(lots[0].house, 8);
+        assert_eq!(longitude(&lots, "Spirit"), 165.0);
+        assert_eq!(longitude(&lots, "Eros"), 70.0);
+
+        let night = chart(&[(SUN, 100.0), (MOON, 50.0), (VENUS, 220.0)]);
+        assert!(!is_day_chart(&night));
+        let lots = night.lots(&catalog).unwrap();
+        assert_eq!(longitude(&lots, "Fortune"), 65.0);
+        assert_eq!(longitude(&lots, "Spirit"), 325.0);
+        // Eros by night: Asc + Spirit - Venus.
+        assert_eq!(longitude(&lots, "Eros"), 120.0);
+    }
+
+    #[test]
+    fn resolves_lots_and_cusps_in_any_order() {
+        let chart = chart(&[(SUN, 200.0), (MOON, 50.0)]);
+        let catalog = [
+            Lot::parse("Late=h10+Fortune-mc;fixed").unwrap(),
+            Lot::parse("Fortune=asc+moon-sun").unwrap(),
+        ];
+        let lots = chart.lots(&catalog).unwrap();
+        assert_eq!(lots[0].name, "Late");
+        // The tenth cusp of equal houses is Asc + 270, not the MC.
+        assert_eq!(lots[0].longitude, 230.0);
+    }
+
+    #[test]
+    fn reports_bad_catalogs() {
+        let chart = chart(&[(SUN, 200.0), (MOON, 50.0)]);
+        let cycle = [
+            Lot::parse("A=asc+B-sun").unwrap(),
+            Lot::parse("B=asc+A-moon").unwrap(),
+        ];
+        assert!(chart.lots(&cycle).unwrap_err().contains("refers to itself"));
+        let unknown = [Lot::parse("A=asc+Nowhere-sun").unwrap()];
+        assert_eq!(chart.lots(&unknown).unwrap_err(), "lot A refers to unknown point Nowhere");
+        let missing = [Lot::parse("A=asc+venus-sun").unwrap()];
+        assert!(chart.lots(&missing).unwrap_err().contains("which the chart lacks"));
+    }
+}
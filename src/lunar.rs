@@ -0,0 +1,306 @@
+use crate::bodies::{JUPITER, MARS, MERCURY, MOON, SATURN, SUN, VENUS};
+use crate::events::{Boundary, EventKind, EventOptions};
+use crate::kernel::{Kernel, KernelError};
+use crate::search::{AspectHit, SearchOptions, Target};
+use crate::sidereal::Zodiac;
+
+/// Mean synodic month, days.
+pub const SYNODIC_MONTH: f64 = 29.530588861;
+// New moon of 2000-01-06 18:14 UTC, Brown lunation 953.
+const LUNATION_EPOCH: f64 = 2451550.09766;
+const BROWN_OFFSET: i64 = 953;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LunarPhase {
+    NewMoon,
+    FirstQuarter,
+    FullMoon,
+    LastQuarter,
+}
+
+impl LunarPhase {
+    pub const ALL: [LunarPhase; 4] = [
+        LunarPhase::NewMoon, LunarPhase::FirstQuarter, LunarPhase::FullMoon, LunarPhase::LastQuarter,
+    ];
+
+    /// Moon minus Sun longitude at the exact phase.
+    pub fn elongation(&self) -> f64 {
+        match self {
+            LunarPhase::NewMoon => 0.0,
+            LunarPhase::FirstQuarter => 90.0,
+            LunarPhase::FullMoon => 180.0,
+            LunarPhase::LastQuarter => 270.0,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LunarPhase::NewMoon => "New Moon",
+            LunarPhase::FirstQuarter => "First Quarter",
+            LunarPhase::FullMoon => "Full Moon",
+            LunarPhase::LastQuarter => "Last Quarter",
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            LunarPhase::NewMoon => "🌑",
+            LunarPhase::FirstQuarter => "🌓",
+            LunarPhase::FullMoon => "🌕",
+            LunarPhase::LastQuarter => "🌗",
+        }
+    }
+
+    fn from_angle(angle: f64) -> Self {
+        match angle.rem_euclid(360.0).round() as i32 {
+            90 => LunarPhase::FirstQuarter,
+            180 => LunarPhase::FullMoon,
+            270 => LunarPhase::LastQuarter,
+            _ => LunarPhase::NewMoon,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseEvent {
+    pub jd: f64,
+    pub phase: LunarPhase,
+    pub moon_longitude: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lunation {
+    /// Brown lunation number (1 = new moon of 1923-01-17).
+    pub number: i64,
+    pub new_moon: f64,
+    /// Later phases are None when they fall past the end of the kernel.
+    pub first_quarter: Option<f64>,
+    pub full_moon: Option<f64>,
+    pub last_quarter: Option<f64>,
+    pub next_new_moon: Option<f64>,
+}
+
+impl Lunation {
+    pub fn length(&self) -> Option<f64> {
+        self.next_new_moon.map(|next| next - self.new_moon)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VoidOptions {
+    /// Aspect angles that end a void, e.g. the Ptolemaic aspects.
+    pub aspects: Vec<f64>,
+    pub bodies: Vec<i32>,
+    pub zodiac: Zodiac,
+    pub search: SearchOptions,
+}
+
+impl Default for VoidOptions {
+    fn default() -> Self {
+        Self {
+            aspects: vec![0.0, 60.0, 90.0, 120.0, 180.0],
+            bodies: vec![SUN, MERCURY, VENUS, MARS, JUPITER, SATURN],
+            zodiac: Zodiac::Tropical,
+            search: SearchOptions::default(),
+        }
+    }
+}
+
+/// The Moon is void of course from its last exact aspect in a sign until it
+/// enters the next sign.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoidOfCourse {
+    pub start_jd: f64,
+    pub end_jd: f64,
+    /// None when the Moon made no aspect at all in the sign; the void then
+    /// starts at its previous ingress.
+    pub last_aspect: Option<AspectHit>,
+    pub sign: usize,
+    pub next_sign: usize,
+}
+
+pub fn lunation_number(new_moon_jd: f64) -> i64 {
+    ((new_moon_jd - LUNATION_EPOCH) / SYNODIC_MONTH).round() as i64 + BROWN_OFFSET
+}
+
+impl Kernel {
+    /// New, first quarter, full and last quarter moons in `from..to`.
+    pub fn find_lunar_phases(&self, from: f64, to: f64, options: &SearchOptions) -> Result<Vec<PhaseEvent>, KernelError> {
+        self.require_earth_frame()?;
+        let mut events = Vec::new();
+        for angle in [0.0, 90.0, 180.0] {
+            for hit in self.find_aspect_hits(MOON, Target::Body(SUN), angle, from, to, options)? {
+                events.push(PhaseEvent {
+                    jd: hit.jd,
+                    phase: LunarPhase::from_angle(hit.angle),
+                    moon_longitude: self.position_at(MOON, hit.jd)?.longitude,
+                });
+            }
+        }
+        events.sort_by(|a, b| a.jd.total_cmp(&b.jd));
+        Ok(events)
+    }
+
+    /// Every lunation whose new moon falls in `from..to`.
+    pub fn lunations(&self, from: f64, to: f64, options: &SearchOptions) -> Result<Vec<Lunation>, KernelError> {
+        let search_end = (to + SYNODIC_MONTH + 1.0).min(self.header().end_jd());
+        let phases = self.find_lunar_phases(from, search_end, options)?;
+
+        let mut lunations = Vec::new();
+        for (i, new_moon) in phases.iter().enumerate() {
+            if new_moon.phase != LunarPhase::NewMoon || new_moon.jd > to {
+                continue;
+            }
+            let rest = &phases[i + 1..];
+            let next_new = rest.iter().position(|p| p.phase == LunarPhase::NewMoon);
+            let month = &rest[..next_new.unwrap_or(rest.len())];
+            let find = |phase: LunarPhase| month.iter().find(|p| p.phase == phase).map(|p| p.jd);
+            lunations.push(Lunation {
+                number: lunation_number(new_moon.jd),
+                new_moon: new_moon.jd,
+                first_quarter: find(LunarPhase::FirstQuarter),
+                full_moon: find(LunarPhase::FullMoon),
+                last_quarter: find(LunarPhase::LastQuarter),
+                next_new_moon: next_new.map(|j| rest[j].jd),
+            });
+        }
+        Ok(lunations)
+    }
+
+    /// Void-of-course periods ending (at a Moon ingress) in `from..to`.
+    pub fn void_of_course(&self, from: f64, to: f64, options: &VoidOptions) -> Result<Vec<VoidOfCourse>, KernelError> {
+        self.require_earth_frame()?;
+        // Look back far enough to find the ingress before the first one
+        let lookback = (from - 3.0).max(self.header().start_jd);
+        let event_options = EventOptions {
+            boundaries: vec![Boundary::Sign],
+            stations: false,
+            zodiac: options.zodiac,
+            search: options.search,
+        };
+        let ingresses: Vec<(f64, usize)> = self.find_ingresses(MOON, Boundary::Sign, lookback, to, &event_options)?
+            .into_iter()
+            .filter_map(|event| match event.kind {
+                EventKind::Ingress { index, .. } => Some((event.jd, index)),
+                _ => None,
+            })
+            .collect();
+
+        let mut voids = Vec::new();
+        for pair in ingresses.windows(2) {
+            let ((entered_jd, sign), (end_jd, next_sign)) = (pair[0], pair[1]);
+            if end_jd < from {
+                continue;
+            }
+
+            let mut last_aspect: Option<AspectHit> = None;
+            for &body in &options.bodies {
+                for &angle in &options.aspects {
+                    let hits = self.find_aspect_hits(MOON, Target::Body(body), angle, entered_jd, end_jd, &options.search)?;
+                    if let Some(hit) = hits.last() {
+                        if last_aspect.is_none_or(|last| hit.jd > last.jd) {
+                            last_aspect = Some(*hit);
+                        }
+                    }
+                }
+            }
+
+            voids.push(VoidOfCourse {
+                start_jd: last_aspect.map_or(entered_jd, |hit| hit.jd),
+                end_jd,
+                last_aspect,
+                sign,
+                next_sign,
+            });
+        }
+        Ok(voids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::{KernelWriter, Position};
+
+    const START: f64 = LUNATION_EPOCH - 5.0;
+
+    // Sun at 1°/day, Moon at 13°/day, so the elongation is 12(t - 5): new
+    // moons at t = 5 and 35, a quarter phase every 7.5 days.
+    fn kernel() -> Kernel {
+        let mut writer = KernelWriter::new(START, 0.5, &[SUN, MOON]);
+        for i in 0..=160 {
+            let t = i as f64 * 0.5;
+            writer.push_record(&[
+                Position { longitude: (280.0 + t) % 360.0, distance: 1.0, speed_longitude: 1.0, ..Position::default() },
+                Position { longitude: (220.0 + 13.0 * t) % 360.0, distance: 0.0026, speed_longitude: 13.0, ..Position::default() },
+            ]);
+        }
+        Kernel::from_bytes(writer.to_bytes()).unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn finds_the_four_phases() {
+        let phases = kernel().find_lunar_phases(START, START + 35.0, &SearchOptions::default()).unwrap();
+        let found: Vec<(LunarPhase, f64)> = phases.iter().map(|p| (p.phase, p.jd - START)).collect();
+        let expected = [(LunarPhase::NewMoon, 5.0), (LunarPhase::FirstQuarter, 12.5), (LunarPhase::FullMoon, 20.0),
+                        (LunarPhase::LastQuarter, 27.5), (LunarPhase::NewMoon, 35.0)];
+        assert_eq!(found.len(), expected.len(), "{:?}", found);
+        for ((phase, t), (expected_phase, expected_t)) in found.iter().zip(expected) {
+            assert_eq!(*phase, expected_phase);
+            assert!(close(*t, expected_t), "{:?} at {} expected {}", phase, t, expected_t);
+        }
+        assert!(close(phases[2].moon_longitude, 220.0 + 13.0 * 20.0 - 360.0));
+    }
+
+    #[test]
+    fn groups_phases_into_lunations() {
+        let lunations = kernel().lunations(START, START + 40.0, &SearchOptions::default()).unwrap();
+        assert_eq!(lunations.len(), 2);
+        let first = &lunations[0];
+        assert_eq!(first.number, BROWN_OFFSET);
+        assert!(close(first.new_moon, LUNATION_EPOCH));
+        assert!(close(first.full_moon.unwrap(), START + 20.0));
+        assert!(close(first.length().unwrap(), 30.0));
+        assert_eq!(lunations[1].number, BROWN_OFFSET + 1);
+        assert!(close(lunations[1].last_quarter.unwrap(), START + 57.5));
+
+        // The kernel ends at t = 80, before the third month is over
+        let last = kernel().lunations(START + 60.0, START + 70.0, &SearchOptions::default()).unwrap();
+        assert_eq!(last.len(), 1);
+        assert!(last[0].full_moon.is_some() && last[0].next_new_moon.is_none());
+    }
+
+    #[test]
+    fn numbers_lunations_from_1923() {
+        assert_eq!(lunation_number(LUNATION_EPOCH), 953);
+        assert_eq!(lunation_number(LUNATION_EPOCH + SYNODIC_MONTH + 0.4), 954);
+        assert_eq!(lunation_number(LUNATION_EPOCH - 952.0 * SYNODIC_MONTH), 1);
+    }
+
+    #[test]
+    fn voids_run_from_the_last_aspect_to_the_ingress() {
+        // Moon ingresses where 220 + 13t crosses a multiple of 30; aspects to
+        // the Sun where 12(t - 5) is 0, ±60, ±90, ±120 or 180
+        let options = VoidOptions { bodies: vec![SUN], ..VoidOptions::default() };
+        let voids = kernel().void_of_course(START + 2.0, START + 13.5, &options).unwrap();
+        let ingress = |k: f64| (30.0 * k - 220.0) / 13.0;
+        let expected = [
+            (ingress(8.0), None, ingress(9.0), 8),
+            (5.0, Some(0.0), ingress(10.0), 9),
+            (ingress(10.0), None, ingress(11.0), 10),
+            (10.0, Some(60.0), ingress(12.0), 11),
+            (12.5, Some(90.0), ingress(13.0), 0),
+        ];
+        assert_eq!(voids.len(), expected.len(), "{:?}", voids);
+        for (void, (start, angle, end, sign)) in voids.iter().zip(expected) {
+            assert!(close(void.start_jd - START, start), "{:?}", void);
+            assert!(close(void.end_jd - START, end), "{:?}", void);
+            assert_eq!(void.last_aspect.map(|hit| hit.angle.rem_euclid(360.0)), angle);
+            assert_eq!((void.sign, void.next_sign), (sign, (sign + 1) % 12));
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::coords::arc;
+use crate::kernel::{Kernel, KernelError, Position};
+use crate::sidereal::Zodiac;
+
+/// Dial sizes used in Uranian work. The 90° dial folds every hard aspect
+/// onto a conjunction; the 45° dial adds the semisquare and sesquiquadrate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dial {
+    Full,
+    Ninety,
+    FortyFive,
+}
+
+impl Dial {
+    pub fn modulus(&self) -> f64 {
+        match self {
+            Dial::Full => 360.0,
+            Dial::Ninety => 90.0,
+            Dial::FortyFive => 45.0,
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim_end_matches('°') {
+            "360" => Ok(Dial::Full),
+            "90" => Ok(Dial::Ninety),
+            "45" => Ok(Dial::FortyFive),
+            _ => Err(format!("unknown dial {} (360, 90 or 45)", s)),
+        }
+    }
+
+    /// Position of `longitude` on the dial, 0..modulus.
+    pub fn position(&self, longitude: f64) -> f64 {
+        longitude.rem_euclid(self.modulus())
+    }
+
+    /// Shortest distance between two longitudes on the dial.
+    pub fn distance(&self, a: f64, b: f64) -> f64 {
+        let m = self.modulus();
+        let d = (a - b).rem_euclid(m);
+        d.min(m - d)
+    }
+}
+
+/// The nearer midpoint of two bodies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Midpoint {
+    pub a: i32,
+    pub b: i32,
+    pub longitude: f64,
+}
+
+pub fn midpoint(a: f64, b: f64) -> f64 {
+    (a + arc(a, b) / 2.0).rem_euclid(360.0)
+}
+
+/// Every pairwise midpoint, in body order.
+pub fn midpoints(positions: &[(i32, Position)]) -> Vec<Midpoint> {
+    let mut result = Vec::new();
+    for (i, (a, pa)) in positions.iter().enumerate() {
+        for (b, pb) in &positions[i + 1..] {
+            result.push(Midpoint { a: *a, b: *b, longitude: midpoint(pa.longitude, pb.longitude) });
+        }
+    }
+    result
+}
+
+/// A body or midpoint placed on a dial.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DialPoint {
+    Body(i32),
+    Midpoint(i32, i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DialEntry {
+    pub point: DialPoint,
+    pub longitude: f64,
+    /// Position on the dial.
+    pub position: f64,
+}
+
+/// Bodies and their midpoints sorted by dial position, the usual midpoint
+/// sort list.
+pub fn sort_on_dial(positions: &[(i32, Position)], dial: Dial) -> Vec<DialEntry> {
+    let bodies = positions.iter().map(|(body, pos)| (DialPoint::Body(*body), pos.longitude));
+    let mids = midpoints(positions).into_iter().map(|m| (DialPoint::Midpoint(m.a, m.b), m.longitude));
+    let mut entries: Vec<DialEntry> = bodies.chain(mids)
+        .map(|(point, longitude)| DialEntry { point, longitude, position: dial.position(longitude) })
+        .collect();
+    entries.sort_by(|a, b| a.position.total_cmp(&b.position));
+    entries
+}
+
+/// Midpoints within `orb` of `body` on the dial, closest first: the
+/// body's midpoint tree. Midpoints involving the body itself are left out.
+pub fn midpoint_tree(body: i32, positions: &[(i32, Position)], dial: Dial, orb: f64) -> Vec<(Midpoint, f64)> {
+    let Some((_, focus)) = positions.iter().find(|(b, _)| *b == body) else {
+        return Vec::new();
+    };
+    let mut tree: Vec<(Midpoint, f64)> = midpoints(positions).into_iter()
+        .filter(|m| m.a != body && m.b != body)
+        .map(|m| (m, dial.distance(m.longitude, focus.longitude)))
+        .filter(|(_, distance)| *distance <= orb)
+        .collect();
+    tree.sort_by(|x, y| x.1.total_cmp(&y.1));
+    tree
+}
+
+/// A + B - C = D: D sits on the sensitive point A + B - C, or
+/// equivalently A/B and C/D share a midpoint on the dial.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanetaryPicture {
+    pub a: i32,
+    pub b: i32,
+    pub c: i32,
+    pub d: i32,
+    /// Longitude of A + B - C.
+    pub longitude: f64,
+    pub orb: f64,
+}
+
+/// Planetary pictures among four distinct bodies within `orb` on the dial,
+/// tightest first. Each picture is listed once, though A + B - C = D also
+/// reads as A + B - D = C.
+pub fn planetary_pictures(positions: &[(i32, Position)], dial: Dial, orb: f64) -> Vec<PlanetaryPicture> {
+    let n = positions.len();
+    let lon = |i: usize| positions[i].1.longitude;
+    let mut pictures = Vec::new();
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in a + 1..n {
+                for d in c + 1..n {
+                    if c == b || d == b {
+                        continue;
+                    }
+                    let point = (lon(a) + lon(b) - lon(c)).rem_euclid(360.0);
+                    let distance = dial.distance(point, lon(d));
+                    if distance <= orb {
+                        pictures.push(PlanetaryPicture {
+                            a: positions[a].0,
+                            b: positions[b].0,
+                            c: positions[c].0,
+                            d: positions[d].0,
+                            longitude: point,
+                            orb: distance,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    pictures.sort_by(|x, y| x.orb.total_cmp(&y.orb));
+    pictures
+}
+
+/// The nth harmonic chart: longitudes and speeds multiplied by `n`.
+pub fn harmonic_positions(positions: &[(i32, Position)], n: u32) -> Vec<(i32, Position)> {
+    let n = n as f64;
+    positions.iter()
+        .map(|&(body, mut pos)| {
+            pos.longitude = (pos.longitude * n).rem_euclid(360.0);
+            pos.speed_longitude *= n;
+            (body, pos)
+        })
+        .collect()
+}
+
+impl Kernel {
+    pub fn midpoints_at(&self, jd: f64, zodiac: Zodiac) -> Result<Vec<Midpoint>, KernelError> {
+        Ok(midpoints(&self.positions_in(jd, zodiac)?))
+    }
+
+    pub fn planetary_pictures_at(&self, jd: f64, zodiac: Zodiac, dial: Dial,
+                                 orb: f64) -> Result<Vec<PlanetaryPicture>, KernelError> {
+        Ok(planetary_pictures(&self.positions_in(jd, zodiac)?, dial, orb))
+    }
+
+    pub fn harmonic_chart(&self, jd: f64, n: u32, zodiac: Zodiac) -> Result<Vec<(i32, Position)>, KernelError> {
+        Ok(harmonic_positions(&self.positions_in(jd, zodiac)?, n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(longitudes: &[f64]) -> Vec<(i32, Position)> {
+        longitudes.iter().enumerate()
+            .map(|(i, &longitude)| (i as i32, Position { longitude, speed_longitude: 1.0, ..Default::default() }))
+            .collect()
+    }
+
+    #[test]
+    fn dials() {
+        assert_eq!(Dial::parse("90°"), Ok(Dial::Ninety));
+        assert_eq!(Dial::parse("45"), Ok(Dial::FortyFive));
+        assert!(Dial::parse("30").is_err());
+        assert_eq!(Dial::Ninety.position(200.0), 20.0);
+        assert_eq!(Dial::Full.distance(350.0, 10.0), 20.0);
+        assert_eq!(Dial::Ninety.distance(0.0, 95.0), 5.0);
+        assert_eq!(Dial::FortyFive.distance(0.0, 135.0), 0.0);
+    }
+
+    #[test]
+    fn nearer_midpoint_across_aries() {
+        assert_eq!(midpoint(350.0, 10.0), 0.0);
+        assert_eq!(midpoint(10.0, 350.0), 0.0);
+        assert_eq!(midpoint(10.0, 100.0), 55.0);
+        let mids = midpoints(&positions(&[10.0, 100.0, 200.0]));
+        let pairs: Vec<(i32, i32, f64)> = mids.iter().map(|m| (m.a, m.b, m.longitude)).collect();
+        assert_eq!(pairs, [(0, 1, 55.0), (0, 2, 285.0), (1, 2, 150.0)]);
+    }
+
+    #[test]
+    fn sorts_bodies_and_midpoints_on_the_dial() {
+        let sorted = sort_on_dial(&positions(&[10.0, 95.0]), Dial::Ninety);
+        let points: Vec<(DialPoint, f64)> = sorted.iter().map(|e| (e.point, e.position)).collect();
+        assert_eq!(points, [(DialPoint::Body(1), 5.0), (DialPoint::Body(0), 10.0), (DialPoint::Midpoint(0, 1), 52.5)]);
+    }
+
+    #[test]
+    fn midpoint_tree_leaves_out_the_focus() {
+        let chart = positions(&[10.0, 50.0, 20.0, 40.0, 115.0]);
+        let tree = midpoint_tree(4, &chart, Dial::Ninety, 1.0);
+        assert_eq!(tree.len(), 1);
+        assert_eq!((tree[0].0.a, tree[0].0.b, tree[0].1), (0, 3, 0.0));
+        assert!(midpoint_tree(4, &chart, Dial::Full, 1.0).is_empty());
+        assert!(midpoint_tree(9, &chart, Dial::Full, 1.0).is_empty());
+    }
+
+    #[test]
+    fn finds_each_picture_once() {
+        let pictures = planetary_pictures(&positions(&[10.0, 50.0, 20.0, 40.5]), Dial::Full, 1.0);
+        assert_eq!(pictures.len(), 1);
+        let p = pictures[0];
+        assert_eq!((p.a, p.b, p.c, p.d), (0, 1, 2, 3));
+        assert_eq!(p.longitude, 40.0);
+        assert_eq!(p.orb, 0.5);
+        assert!(planetary_pictures(&positions(&[10.0, 50.0, 20.0, 42.0]), Dial::Full, 1.0).is_empty());
+    }
+
+    #[test]
+    fn harmonics_scale_longitude_and_speed() {
+        let fourth = harmonic_positions(&positions(&[100.0]), 4);
+        assert_eq!(fourth[0].1.longitude, 40.0);
+        assert_eq!(fourth[0].1.speed_longitude, 4.0);
+    }
+}
@@ -0,0 +1,251 @@
+use std::io::{self, Write};
+use crate::chart::Chart;
+use crate::houses::Houses;
+use crate::json::{position_json, sign_json, time_json, Json};
+use crate::kernel::Position;
+
+/// How reader CLIs print their results. `Table` is each tool's own
+/// human-readable layout; the others write flat records through
+/// [`write_records`] so scripts never have to scrape glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    Csv,
+    Ndjson,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "ndjson" | "jsonl" => Ok(Format::Ndjson),
+            _ => Err(format!("unknown format {} (table, json, csv, ndjson)", s)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Table => "table",
+            Format::Json => "json",
+            Format::Csv => "csv",
+            Format::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// One body at `jd` in the shared schema: jd, datetime, body, name,
+/// longitude, latitude, distance, speed, sign, degree, retrograde, house.
+/// `house` is null when no chart was cast.
+pub fn position_record(jd: f64, body: i32, pos: &Position, house: Option<usize>) -> Json {
+    let mut record = Json::object(time_json(jd));
+    if let Json::Object(fields) = position_json(body, pos) {
+        for (key, value) in fields {
+            record.push(key, value);
+        }
+    }
+    record.push("house", house.into());
+    record
+}
+
+/// A chart point that is not a body (angle, cusp, lot) in the same schema,
+/// with the body-only fields left null.
+pub fn point_record(jd: f64, name: &str, longitude: f64, house: Option<usize>) -> Json {
+    let [sign, degree] = sign_json(longitude);
+    let mut fields = time_json(jd);
+    fields.extend([
+        ("body", Json::Null),
+        ("name", name.into()),
+        ("longitude", longitude.into()),
+        ("latitude", Json::Null),
+        ("distance", Json::Null),
+        ("speed", Json::Null),
+        sign,
+        degree,
+        ("retrograde", Json::Null),
+        ("house", house.into()),
+    ]);
+    Json::object(fields)
+}
+
+/// The Ascendant, MC and the twelve cusps as point records.
+pub fn house_records(jd: f64, houses: &Houses) -> Vec<Json> {
+    let mut records = vec![
+        point_record(jd, "Ascendant", houses.ascendant, Some(1)),
+        point_record(jd, "MC", houses.mc, Some(houses.house_of(houses.mc))),
+    ];
+    for (i, cusp) in houses.cusps.iter().enumerate() {
+        records.push(point_record(jd, &format!("Cusp {}", i + 1), *cusp, Some(i + 1)));
+    }
+    records
+}
+
+/// Bodies with their houses, followed by [`house_records`].
+pub fn chart_records(chart: &Chart) -> Vec<Json> {
+    let mut records: Vec<Json> = chart.positions.iter()
+        .map(|(body, pos)| position_record(chart.jd, *body, pos, Some(chart.houses.house_of(pos.longitude))))
+        .collect();
+    records.extend(house_records(chart.jd, &chart.houses));
+    records
+}
+
+// Nested objects become dotted columns ("contacts.begin"); arrays stay JSON.
+fn flatten<'a>(prefix: &str, json: &'a Json, out: &mut Vec<(String, &'a Json)>) {
+    match json {
+        Json::Object(fields) => {
+            for (key, value) in fields {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(&key, value, out);
+            }
+        }
+        value => out.push((prefix.to_string(), value)),
+    }
+}
+
+fn cell(value: &Json) -> String {
+    match value {
+        Json::Null => String::new(),
+        Json::Number(n) if !n.is_finite() => String::new(),
+        Json::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) || s.starts_with(' ') || s.ends_with(' ') {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// Columns in order of first appearance, so records of different shapes
+// still share one header.
+fn columns(rows: &[Vec<(String, &Json)>]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for row in rows {
+        for (key, _) in row {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    columns
+}
+
+fn flatten_all(records: &[Json]) -> Vec<Vec<(String, &Json)>> {
+    records.iter()
+        .map(|record| {
+            let mut row = Vec::new();
+            flatten("", record, &mut row);
+            row
+        })
+        .collect()
+}
+
+fn cells(rows: &[Vec<(String, &Json)>], columns: &[String]) -> Vec<Vec<String>> {
+    rows.iter()
+        .map(|row| columns.iter()
+            .map(|c| row.iter().find(|(k, _)| k == c).map_or(String::new(), |(_, v)| cell(v)))
+            .collect())
+        .collect()
+}
+
+/// Writes records batch by batch, so long runs can stream their output.
+/// The JSON array and the CSV header span every batch; CSV columns are
+/// fixed by the first batch, and a table is aligned one batch at a time.
+pub struct RecordWriter<W: Write> {
+    out: W,
+    format: Format,
+    columns: Option<Vec<String>>,
+    count: usize,
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(out: W, format: Format) -> Self {
+        Self { out, format, columns: None, count: 0 }
+    }
+
+    pub fn write(&mut self, records: &[Json]) -> io::Result<()> {
+        match self.format {
+            Format::Json => {
+                for record in records {
+                    write!(self.out, "{}  {}", if self.count == 0 { "[\n" } else { ",\n" }, record)?;
+                    self.count += 1;
+                }
+            }
+            Format::Ndjson => {
+                for record in records {
+                    writeln!(self.out, "{}", record)?;
+                }
+                self.count += records.len();
+            }
+            Format::Csv => {
+                let rows = flatten_all(records);
+                if self.columns.is_none() && !rows.is_empty() {
+                    let columns = columns(&rows);
+                    writeln!(self.out, "{}", columns.iter().map(|c| csv_field(c)).collect::<Vec<_>>().join(","))?;
+                    self.columns = Some(columns);
+                }
+                for row in cells(&rows, self.columns.as_deref().unwrap_or_default()) {
+                    writeln!(self.out, "{}", row.iter().map(|c| csv_field(c)).collect::<Vec<_>>().join(","))?;
+                }
+                self.count += records.len();
+            }
+            Format::Table => {
+                let rows = flatten_all(records);
+                let columns = columns(&rows);
+                let cells = cells(&rows, &columns);
+                let widths: Vec<usize> = columns.iter().enumerate()
+                    .map(|(i, c)| cells.iter().map(|row| row[i].chars().count()).fold(c.chars().count(), usize::max))
+                    .collect();
+                let line = |values: &[String]| values.iter().zip(&widths)
+                    .map(|(v, w)| format!("{}{}", v, " ".repeat(w - v.chars().count())))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                if self.count > 0 {
+                    writeln!(self.out)?;
+                }
+                writeln!(self.out, "{}", line(&columns).trim_end())?;
+                for row in &cells {
+                    writeln!(self.out, "{}", line(row).trim_end())?;
+                }
+                self.count += records.len();
+            }
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    /// Closes the JSON array (an empty one if nothing was written) and
+    /// flushes.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.format == Format::Json {
+            writeln!(self.out, "{}", if self.count == 0 { "[]" } else { "\n]" })?;
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Writes `records` (JSON objects) as a JSON array, one object per line,
+/// CSV with a header row, or a plain aligned table.
+pub fn write_records<W: Write>(out: &mut W, format: Format, records: &[Json]) -> io::Result<()> {
+    let mut writer = RecordWriter::new(out, format);
+    writer.write(records)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// [`write_records`] to stdout.
+pub fn print_records(format: Format, records: &[Json]) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    write_records(&mut out, format, records)
+}
@@ -0,0 +1,113 @@
+use chrono::{Datelike, NaiveDate, TimeZone, Weekday};
+use crate::bodies::{JUPITER, MARS, MERCURY, MOON, SATURN, SUN, VENUS};
+use crate::coords::GeoLocation;
+use crate::ephemeris::{self, EphemerisSource};
+use crate::kernel::{Kernel, KernelError};
+use crate::time::local_midnight_jd;
+use crate::{SE_CALC_RISE, SE_CALC_SET};
+
+/// Slowest to fastest; planetary hours run through this order.
+pub const CHALDEAN_ORDER: [i32; 7] = [SATURN, JUPITER, MARS, SUN, VENUS, MERCURY, MOON];
+
+pub fn day_ruler(weekday: Weekday) -> i32 {
+    match weekday {
+        Weekday::Sun => SUN,
+        Weekday::Mon => MOON,
+        Weekday::Tue => MARS,
+        Weekday::Wed => MERCURY,
+        Weekday::Thu => JUPITER,
+        Weekday::Fri => VENUS,
+        Weekday::Sat => SATURN,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanetaryHour {
+    /// 1..=12 are the day hours, 13..=24 the night hours.
+    pub number: u8,
+    pub ruler: i32,
+    pub start_jd: f64,
+    pub end_jd: f64,
+}
+
+impl PlanetaryHour {
+    pub fn is_day(&self) -> bool {
+        self.number <= 12
+    }
+}
+
+/// A planetary day runs from sunrise to the next sunrise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanetaryDay {
+    pub date: NaiveDate,
+    pub day_ruler: i32,
+    pub sunrise: f64,
+    pub sunset: f64,
+    pub next_sunrise: f64,
+    pub hours: Vec<PlanetaryHour>,
+}
+
+impl PlanetaryDay {
+    pub fn hour_at(&self, jd: f64) -> Option<&PlanetaryHour> {
+        self.hours.iter().find(|h| jd >= h.start_jd && jd < h.end_jd)
+    }
+}
+
+/// Twelve unequal day hours from sunrise to sunset and twelve night hours
+/// to the next sunrise, ruled in Chaldean order from the day ruler.
+pub fn planetary_hours(date: NaiveDate, sunrise: f64, sunset: f64, next_sunrise: f64) -> PlanetaryDay {
+    let ruler = day_ruler(date.weekday());
+    let first = CHALDEAN_ORDER.iter().position(|&b| b == ruler).unwrap();
+    let day_length = (sunset - sunrise) / 12.0;
+    let night_length = (next_sunrise - sunset) / 12.0;
+
+    let hours = (0..24)
+        .map(|i| {
+            let start_jd = if i < 12 {
+                sunrise + i as f64 * day_length
+            } else {
+                sunset + (i - 12) as f64 * night_length
+            };
+            PlanetaryHour {
+                number: i as u8 + 1,
+                ruler: CHALDEAN_ORDER[(first + i) % 7],
+                start_jd,
+                end_jd: start_jd + if i < 12 { day_length } else { night_length },
+            }
+        })
+        .collect();
+
+    PlanetaryDay { date, day_ruler: ruler, sunrise, sunset, next_sunrise, hours }
+}
+
+/// Planetary hours for the local `date` at `location`, with sunrise and
+/// sunset from swe_rise_trans. Fails where the Sun does not rise or set.
+pub fn planetary_day<T: TimeZone>(date: NaiveDate, location: GeoLocation, tz: &T,
+                                  source: EphemerisSource) -> Result<PlanetaryDay, String> {
+    let midnight = local_midnight_jd(date, tz).ok_or_else(|| format!("no local midnight on {}", date))?;
+    let next = |jd: f64, rsmi: u32| ephemeris::rise_trans(jd, SUN, location, rsmi as i32, 0.0, 0.0, source)?
+        .ok_or_else(|| format!("the Sun does not rise and set on {} at this latitude", date));
+
+    let sunrise = next(midnight, SE_CALC_RISE)?;
+    let sunset = next(sunrise, SE_CALC_SET)?;
+    let next_sunrise = next(sunset, SE_CALC_RISE)?;
+    Ok(planetary_hours(date, sunrise, sunset, next_sunrise))
+}
+
+impl Kernel {
+    /// Planetary hours for `date` from stored rise/set tables instead of
+    /// live swe calls. Days are the tables' local mean days, which agree
+    /// with the civil date for any sunrise away from midnight.
+    pub fn planetary_day(&self, date: NaiveDate, location: GeoLocation) -> Result<PlanetaryDay, KernelError> {
+        let missing = || KernelError::BadSection(format!("RISE: the Sun does not rise and set on {}", date));
+        let today = self.rise_set(SUN, date, location)?;
+        let tomorrow = self.rise_set(SUN, date.succ_opt().ok_or_else(missing)?, location)?;
+
+        let sunrise = today.rise.ok_or_else(missing)?;
+        let sunset = today.set.filter(|&set| set > sunrise)
+            .or(tomorrow.set)
+            .ok_or_else(missing)?;
+        let next_sunrise = tomorrow.rise.ok_or_else(missing)?;
+        Ok(planetary_hours(date, sunrise, sunset, next_sunrise))
+    }
+}
@@ -0,0 +1,343 @@
+use crate::bodies::SUN;
+use crate::chart::Chart;
+use crate::coords::{arc, declination, mean_obliquity, right_ascension, semi_arc, GeoLocation};
+use crate::houses::{houses, houses_from_armc, HouseSystem};
+use crate::kernel::{Kernel, KernelError};
+use crate::sidereal::Zodiac;
+
+/// Mean motion of the Sun, degrees per year (59'08.33").
+pub const NAIBOD_RATE: f64 = 0.98564733;
+/// Mean tropical year, days.
+pub const TROPICAL_YEAR: f64 = 365.24219;
+
+// Difference step, in progressed days, for the solar arc rate
+const RATE_STEP: f64 = 0.1;
+
+/// How an arc of direction converts to years of life.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// One degree per year.
+    Ptolemy,
+    /// The Sun's mean daily motion per year.
+    Naibod,
+    /// The true motion of the secondary progressed Sun.
+    SolarArc,
+}
+
+impl Key {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Key::Ptolemy => "Ptolemy",
+            Key::Naibod => "Naibod",
+            Key::SolarArc => "Solar arc",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "ptolemy" => Ok(Key::Ptolemy),
+            "naibod" => Ok(Key::Naibod),
+            "solararc" | "truesolararc" => Ok(Key::SolarArc),
+            _ => Err(format!("unknown key {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressionOptions {
+    pub key: Key,
+    pub zodiac: Zodiac,
+    pub house_system: HouseSystem,
+    /// Days of life per progressed day.
+    pub year_length: f64,
+}
+
+impl Default for ProgressionOptions {
+    fn default() -> Self {
+        Self {
+            key: Key::Naibod,
+            zodiac: Zodiac::Tropical,
+            house_system: HouseSystem::Placidus,
+            year_length: TROPICAL_YEAR,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Significator {
+    Ascendant,
+    Mc,
+    Descendant,
+    Ic,
+    Body(i32),
+}
+
+/// A promissor carried by primary motion to a significator's Placidus
+/// mundane position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrimaryDirection {
+    pub promissor: i32,
+    pub significator: Significator,
+    /// Arc of direction in right ascension, degrees.
+    pub arc: f64,
+    pub years: f64,
+    pub jd: f64,
+}
+
+/// The day-for-a-year date for `target_jd`.
+pub fn progressed_jd(natal_jd: f64, target_jd: f64, options: &ProgressionOptions) -> f64 {
+    natal_jd + (target_jd - natal_jd) / options.year_length
+}
+
+// Placidus mundane position: 0 at the ascendant, 90 at the MC, 180 at the
+// descendant, 270 at the IC, each quadrant split by the point's own
+// semi-arc. Hour angles are west-positive.
+fn mundane_position(hour_angle: f64, sda: f64) -> f64 {
+    let ha = arc(0.0, hour_angle);
+    let nsa = 180.0 - sda;
+    if ha <= -sda {
+        270.0 + 90.0 * (ha + 180.0) / nsa
+    } else if ha <= 0.0 {
+        90.0 * (1.0 + ha / sda)
+    } else if ha <= sda {
+        90.0 + 90.0 * ha / sda
+    } else {
+        180.0 + 90.0 * (ha - sda) / nsa
+    }
+}
+
+fn hour_angle_at(position: f64, sda: f64) -> f64 {
+    let p = position.rem_euclid(360.0);
+    let nsa = 180.0 - sda;
+    if p < 90.0 {
+        (p / 90.0 - 1.0) * sda
+    } else if p < 180.0 {
+        (p - 90.0) / 90.0 * sda
+    } else if p < 270.0 {
+        sda + (p - 180.0) / 90.0 * nsa
+    } else {
+        -180.0 + (p - 270.0) / 90.0 * nsa
+    }
+}
+
+impl Kernel {
+    // Sun's right ascension at `jd`, against a fixed obliquity.
+    fn sun_right_ascension(&self, jd: f64, obliquity: f64) -> Result<f64, KernelError> {
+        let sun = self.position_at(SUN, jd)?;
+        Ok(right_ascension(sun.longitude, sun.latitude, obliquity))
+    }
+
+    /// Arc in right ascension for the angles after `years` of life.
+    pub fn angle_arc(&self, natal_jd: f64, years: f64, options: &ProgressionOptions) -> Result<f64, KernelError> {
+        match options.key {
+            Key::Ptolemy => Ok(years),
+            Key::Naibod => Ok(years * NAIBOD_RATE),
+            Key::SolarArc => {
+                let eps = mean_obliquity(natal_jd);
+                let natal = self.sun_right_ascension(natal_jd, eps)?;
+                let progressed = self.sun_right_ascension(natal_jd + years, eps)?;
+                Ok((progressed - natal).rem_euclid(360.0))
+            }
+        }
+    }
+
+    /// Arc in longitude for solar arc directions after `years` of life.
+    pub fn solar_arc(&self, natal_jd: f64, years: f64, options: &ProgressionOptions) -> Result<f64, KernelError> {
+        match options.key {
+            Key::Ptolemy => Ok(years),
+            Key::Naibod => Ok(years * NAIBOD_RATE),
+            Key::SolarArc => {
+                let natal = self.position_in(SUN, natal_jd, options.zodiac)?.longitude;
+                let progressed = self.position_in(SUN, natal_jd + years, options.zodiac)?.longitude;
+                Ok((progressed - natal).rem_euclid(360.0))
+            }
+        }
+    }
+
+    /// Secondary progressed chart for `target_jd`: positions on the
+    /// day-for-a-year date, angles advanced from the natal ARMC by the key.
+    /// The chart's `jd` is the progressed date.
+    pub fn progressed_chart(&self, natal_jd: f64, location: GeoLocation, target_jd: f64,
+                            options: &ProgressionOptions) -> Result<Chart, KernelError> {
+        self.require_earth_frame()?;
+        let progressed = progressed_jd(natal_jd, target_jd, options);
+        let years = progressed - natal_jd;
+        let natal_armc = houses(natal_jd, location, options.house_system).armc;
+        let armc = (natal_armc + self.angle_arc(natal_jd, years, options)?).rem_euclid(360.0);
+
+        let mut progressed_houses = houses_from_armc(armc, location.latitude, mean_obliquity(natal_jd), options.house_system);
+        if let Zodiac::Sidereal(mode) = options.zodiac {
+            progressed_houses = progressed_houses.shifted(self.ayanamsa_at(mode, progressed)?);
+        }
+        Ok(Chart {
+            jd: progressed,
+            location,
+            zodiac: options.zodiac,
+            positions: self.positions_in(progressed, options.zodiac)?,
+            houses: progressed_houses,
+        })
+    }
+
+    /// Natal chart with every position and angle advanced by the solar arc
+    /// for `target_jd`. The chart's `jd` is `target_jd`.
+    pub fn solar_arc_chart(&self, natal_jd: f64, location: GeoLocation, target_jd: f64,
+                           options: &ProgressionOptions) -> Result<Chart, KernelError> {
+        let years = progressed_jd(natal_jd, target_jd, options) - natal_jd;
+        let solar_arc = self.solar_arc(natal_jd, years, options)?;
+        let natal = self.chart(natal_jd, location, options.house_system, options.zodiac)?;
+
+        let positions = natal.positions.into_iter()
+            .map(|(body, mut pos)| {
+                pos.longitude = (pos.longitude + solar_arc).rem_euclid(360.0);
+                (body, pos)
+            })
+            .collect();
+        Ok(Chart {
+            jd: target_jd,
+            location,
+            zodiac: options.zodiac,
+            positions,
+            houses: natal.houses.shifted(-solar_arc),
+        })
+    }
+
+    /// Direct Placidus semi-arc directions of every body to the angles and
+    /// to every other body, up to `max_years`, sorted by arc. Promissors
+    /// keep their ecliptic latitude (directions in mundo).
+    pub fn primary_directions(&self, natal_jd: f64, location: GeoLocation, max_years: f64,
+                              options: &ProgressionOptions) -> Result<Vec<PrimaryDirection>, KernelError> {
+        let eps = mean_obliquity(natal_jd);
+        let armc = houses(natal_jd, location, options.house_system).armc;
+        // The key's arc only grows with time, so longer arcs are never timed
+        let max_arc = self.angle_arc(natal_jd, max_years, options)?;
+
+        // (body, hour angle, semi-arc) for every body that rises and sets
+        let mut points = Vec::new();
+        for (body, pos) in self.positions_at(natal_jd)? {
+            let decl = declination(pos.longitude, pos.latitude, eps);
+            if let Some(sda) = semi_arc(decl, location.latitude) {
+                let ra = right_ascension(pos.longitude, pos.latitude, eps);
+                points.push((body, armc - ra, sda));
+            }
+        }
+
+        let mut targets: Vec<(Significator, f64)> = vec![
+            (Significator::Ascendant, 0.0),
+            (Significator::Mc, 90.0),
+            (Significator::Descendant, 180.0),
+            (Significator::Ic, 270.0),
+        ];
+        targets.extend(points.iter().map(|&(body, ha, sda)| (Significator::Body(body), mundane_position(ha, sda))));
+
+        let mut directions = Vec::new();
+        for &(promissor, ha, sda) in &points {
+            for &(significator, position) in &targets {
+                if significator == Significator::Body(promissor) {
+                    continue;
+                }
+                let arc = (hour_angle_at(position, sda) - ha).rem_euclid(360.0);
+                if arc > max_arc {
+                    continue;
+                }
+                let years = self.years_for_arc(natal_jd, arc, options)?;
+                if years <= max_years {
+                    directions.push(PrimaryDirection {
+                        promissor,
+                        significator,
+                        arc,
+                        years,
+                        jd: natal_jd + years * options.year_length,
+                    });
+                }
+            }
+        }
+        directions.sort_by(|a, b| a.arc.total_cmp(&b.arc));
+        Ok(directions)
+    }
+
+    /// Years of life for an arc in right ascension under the key.
+    pub fn years_for_arc(&self, natal_jd: f64, arc: f64, options: &ProgressionOptions) -> Result<f64, KernelError> {
+        match options.key {
+            Key::Ptolemy => Ok(arc),
+            Key::Naibod => Ok(arc / NAIBOD_RATE),
+            Key::SolarArc => {
+                // Newton on the progressed Sun's right ascension, which moves
+                // close to the Naibod rate. Starts inside the kernel; an arc
+                // the progressed Sun only reaches past its end is out of range.
+                let last = self.header().end_jd() - natal_jd - RATE_STEP;
+                let mut years = (arc / NAIBOD_RATE).min(last).max(0.0);
+                for _ in 0..20 {
+                    let here = self.angle_arc(natal_jd, years, options)?;
+                    let error = here - arc;
+                    let rate = (self.angle_arc(natal_jd, years + RATE_STEP, options)? - here) / RATE_STEP;
+                    let step = error / rate;
+                    years -= step;
+                    if step.abs() < 1e-9 {
+                        break;
+                    }
+                }
+                Ok(years)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::{KernelWriter, Position};
+
+    const NATAL: f64 = 2451545.0;
+
+    // Sun and Moon on the ecliptic at 1° and 13° a day, for 100 days
+    fn kernel() -> Kernel {
+        let mut writer = KernelWriter::new(NATAL, 1.0, &[0, 1]);
+        for day in 0..100 {
+            let day = day as f64;
+            writer.push_record(&[
+                Position { longitude: (280.0 + day) % 360.0, distance: 1.0, speed_longitude: 1.0, ..Position::default() },
+                Position { longitude: (40.0 + 13.0 * day) % 360.0, distance: 0.0026, speed_longitude: 13.0, ..Position::default() },
+            ]);
+        }
+        Kernel::from_bytes(writer.to_bytes()).unwrap()
+    }
+
+    #[test]
+    fn solar_arc_years_invert_the_arc() {
+        let kernel = kernel();
+        let options = ProgressionOptions { key: Key::SolarArc, ..ProgressionOptions::default() };
+        for arc in [0.0, 10.0, 42.5, 80.0] {
+            let years = kernel.years_for_arc(NATAL, arc, &options).unwrap();
+            let back = kernel.angle_arc(NATAL, years, &options).unwrap();
+            assert!((back - arc).abs() < 1e-6, "arc {} -> {} years -> {}", arc, years, back);
+        }
+        let naibod = ProgressionOptions::default();
+        assert_eq!(kernel.years_for_arc(NATAL, 10.0, &naibod).unwrap(), 10.0 / NAIBOD_RATE);
+    }
+
+    #[test]
+    fn solar_arc_past_the_kernel_is_out_of_range() {
+        let kernel = kernel();
+        let options = ProgressionOptions { key: Key::SolarArc, ..ProgressionOptions::default() };
+        assert!(matches!(kernel.years_for_arc(NATAL, 150.0, &options), Err(KernelError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn directions_are_dated_with_the_year_length() {
+        let kernel = kernel();
+        let location = GeoLocation { latitude: 40.0, longitude: -105.0, altitude: 0.0 };
+        let options = ProgressionOptions { key: Key::Ptolemy, year_length: 360.0, ..ProgressionOptions::default() };
+        let directions = kernel.primary_directions(NATAL, location, 90.0, &options).unwrap();
+        assert!(!directions.is_empty());
+        for direction in &directions {
+            assert!(direction.years <= 90.0);
+            assert_eq!(direction.jd, NATAL + direction.years * 360.0);
+        }
+
+        // Solar arc directions stop where the kernel does
+        let solar_arc = ProgressionOptions { key: Key::SolarArc, ..ProgressionOptions::default() };
+        let directions = kernel.primary_directions(NATAL, location, 60.0, &solar_arc).unwrap();
+        assert!(directions.iter().all(|d| d.years <= 60.0 + 1e-9));
+        assert!(kernel.primary_directions(NATAL, location, 150.0, &solar_arc).is_err());
+    }
+}
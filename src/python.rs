@@ -0,0 +1,229 @@
+use std::path::PathBuf;
+use numpy::ndarray::{Array2, Array3};
+use numpy::{AllowTypeChange, IntoPyArray, PyArray2, PyArray3, PyArrayLike1};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use crate::bodies::body_from_name;
+use crate::coords::GeoLocation;
+use crate::events::{Boundary, EventOptions};
+use crate::houses::HouseSystem;
+use crate::json::{chart_json, event_json, houses_json, Json};
+use crate::kernel::{Kernel, KernelError};
+use crate::sidereal::{Ayanamsa, Zodiac};
+
+// Python bindings (the `python` feature), built with maturin from
+// pyproject.toml. Everything goes through the same reader as the CLIs, and
+// dict results are built from the same JSON the server and --format json
+// emit, so the numbers match exactly.
+
+create_exception!(medusa, MedusaError, PyException, "A kernel read failed (out of range, unknown body, wrong frame...).");
+
+fn kernel_error(e: KernelError) -> PyErr {
+    MedusaError::new_err(e.to_string())
+}
+
+fn value_error(e: String) -> PyErr {
+    PyValueError::new_err(e)
+}
+
+/// A body as its Swiss Ephemeris number or a name like "mars".
+#[derive(FromPyObject)]
+enum BodyArg {
+    Id(i32),
+    Name(String),
+}
+
+fn parse_zodiac(zodiac: Option<&str>) -> PyResult<Zodiac> {
+    match zodiac {
+        None | Some("tropical") => Ok(Zodiac::Tropical),
+        Some(mode) => Ok(Zodiac::Sidereal(Ayanamsa::parse(mode).map_err(value_error)?)),
+    }
+}
+
+// Numbers that print as integers in our JSON come back as ints, as
+// json.loads would give them.
+fn to_py(py: Python<'_>, json: &Json) -> PyResult<PyObject> {
+    Ok(match json {
+        Json::Null => py.None(),
+        Json::Bool(b) => b.into_py(py),
+        Json::Number(n) if !n.is_finite() => py.None(),
+        Json::Number(n) if n.fract() == 0.0 && n.abs() < 9.0e15 => (*n as i64).into_py(py),
+        Json::Number(n) => n.into_py(py),
+        Json::String(s) => s.into_py(py),
+        Json::Array(items) => {
+            let items = items.iter().map(|item| to_py(py, item)).collect::<PyResult<Vec<_>>>()?;
+            PyList::new_bound(py, items).into_py(py)
+        }
+        Json::Object(fields) => {
+            let dict = PyDict::new_bound(py);
+            for (key, value) in fields {
+                dict.set_item(key, to_py(py, value)?)?;
+            }
+            dict.into_py(py)
+        }
+    })
+}
+
+/// A memory-mapped Zenith kernel.
+///
+/// ```python
+/// k = medusa.Kernel("zenith.kernel")
+/// pos = k.positions(np.arange(2451545.0, 2451910.0), ["sun", "moon"])
+/// pos[:, 1, 0]   # Moon longitudes
+/// ```
+#[pyclass(name = "Kernel", module = "medusa", frozen)]
+pub struct PyKernel(Kernel);
+
+impl PyKernel {
+    fn bodies_or_all(&self, bodies: Option<Vec<BodyArg>>) -> PyResult<Vec<i32>> {
+        match bodies {
+            Some(list) => list.into_iter()
+                .map(|body| match body {
+                    BodyArg::Id(id) => Ok(id),
+                    BodyArg::Name(name) => body_from_name(&name)
+                        .ok_or_else(|| PyValueError::new_err(format!("unknown body {}", name))),
+                })
+                .collect(),
+            None => Ok(self.0.bodies().to_vec()),
+        }
+    }
+}
+
+// Keyword arguments with defaults read naturally from Python, however many
+#[allow(clippy::too_many_arguments)]
+#[pymethods]
+impl PyKernel {
+    #[new]
+    fn open(path: PathBuf) -> PyResult<Self> {
+        Kernel::open(path).map(Self).map_err(kernel_error)
+    }
+
+    /// A kernel from its bytes, say one fetched over the network.
+    #[staticmethod]
+    fn from_bytes(data: Vec<u8>) -> PyResult<Self> {
+        Kernel::from_bytes(data).map(Self).map_err(kernel_error)
+    }
+
+    #[getter]
+    fn start_jd(&self) -> f64 {
+        self.0.header().start_jd
+    }
+
+    #[getter]
+    fn end_jd(&self) -> f64 {
+        self.0.header().end_jd()
+    }
+
+    #[getter]
+    fn bodies(&self) -> Vec<i32> {
+        self.0.bodies().to_vec()
+    }
+
+    #[getter]
+    fn frame(&self) -> String {
+        self.0.frame().to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Kernel(JD {}..{}, {} bodies, {})", self.start_jd(), self.end_jd(), self.0.bodies().len(), self.frame())
+    }
+
+    /// Positions as a float64 array of shape (len(jd), len(bodies), 6):
+    /// longitude, latitude, distance, and their speeds per day. `bodies`
+    /// defaults to every body in the kernel, in kernel order; `zodiac` is
+    /// "tropical" or an ayanamsa such as "lahiri".
+    #[pyo3(signature = (jd, bodies=None, zodiac=None))]
+    fn positions<'py>(&self, py: Python<'py>, jd: PyArrayLike1<'py, f64, AllowTypeChange>,
+                      bodies: Option<Vec<BodyArg>>, zodiac: Option<&str>) -> PyResult<Bound<'py, PyArray3<f64>>> {
+        let bodies = self.bodies_or_all(bodies)?;
+        let zodiac = parse_zodiac(zodiac)?;
+        let jds: Vec<f64> = jd.as_array().iter().copied().collect();
+        let values = py.allow_threads(|| {
+            let mut values = Vec::with_capacity(jds.len() * bodies.len() * 6);
+            for &jd in &jds {
+                for &body in &bodies {
+                    let pos = self.0.position_in(body, jd, zodiac)?;
+                    values.extend([pos.longitude, pos.latitude, pos.distance,
+                                   pos.speed_longitude, pos.speed_latitude, pos.speed_distance]);
+                }
+            }
+            Ok::<_, KernelError>(values)
+        }).map_err(kernel_error)?;
+        let array = Array3::from_shape_vec((jds.len(), bodies.len(), 6), values).expect("one value per cell");
+        Ok(array.into_pyarray_bound(py))
+    }
+
+    /// House (1-12) of each body at each time, shape (len(jd), len(bodies)).
+    #[pyo3(signature = (jd, latitude, longitude, bodies=None, altitude=0.0, system="P", zodiac=None))]
+    fn houses_of<'py>(&self, py: Python<'py>, jd: PyArrayLike1<'py, f64, AllowTypeChange>, latitude: f64, longitude: f64,
+                      bodies: Option<Vec<BodyArg>>, altitude: f64, system: &str,
+                      zodiac: Option<&str>) -> PyResult<Bound<'py, PyArray2<u8>>> {
+        let bodies = self.bodies_or_all(bodies)?;
+        let zodiac = parse_zodiac(zodiac)?;
+        let system = HouseSystem::parse(system).map_err(value_error)?;
+        let location = GeoLocation::new(latitude, longitude, altitude);
+        let jds: Vec<f64> = jd.as_array().iter().copied().collect();
+        let values = py.allow_threads(|| {
+            let mut values = Vec::with_capacity(jds.len() * bodies.len());
+            for &jd in &jds {
+                let houses = self.0.houses_in(jd, location, system, zodiac)?;
+                for &body in &bodies {
+                    values.push(houses.house_of(self.0.position_in(body, jd, zodiac)?.longitude) as u8);
+                }
+            }
+            Ok::<_, KernelError>(values)
+        }).map_err(kernel_error)?;
+        let array = Array2::from_shape_vec((jds.len(), bodies.len()), values).expect("one value per cell");
+        Ok(array.into_pyarray_bound(py))
+    }
+
+    /// Cusps, Ascendant, MC and ARMC at one time and place, as a dict.
+    #[pyo3(signature = (jd, latitude, longitude, altitude=0.0, system="P", zodiac=None))]
+    fn houses(&self, py: Python<'_>, jd: f64, latitude: f64, longitude: f64, altitude: f64,
+              system: &str, zodiac: Option<&str>) -> PyResult<PyObject> {
+        let system = HouseSystem::parse(system).map_err(value_error)?;
+        let location = GeoLocation::new(latitude, longitude, altitude);
+        let houses = self.0.houses_in(jd, location, system, parse_zodiac(zodiac)?).map_err(kernel_error)?;
+        to_py(py, &houses_json(&houses))
+    }
+
+    /// The chart dict the server's /chart endpoint returns.
+    #[pyo3(signature = (jd, latitude, longitude, altitude=0.0, system="P", zodiac=None))]
+    fn chart(&self, py: Python<'_>, jd: f64, latitude: f64, longitude: f64, altitude: f64,
+             system: &str, zodiac: Option<&str>) -> PyResult<PyObject> {
+        let system = HouseSystem::parse(system).map_err(value_error)?;
+        let location = GeoLocation::new(latitude, longitude, altitude);
+        let chart = self.0.chart(jd, location, system, parse_zodiac(zodiac)?).map_err(kernel_error)?;
+        to_py(py, &chart_json(&chart))
+    }
+
+    /// Ingresses and stations in `start..end`, sorted by time, as dicts.
+    /// `kind` is "all", "ingress" or "station"; `boundaries` takes "sign",
+    /// "nakshatra" or a degree step like "1".
+    #[pyo3(signature = (start, end, bodies=None, kind="all", boundaries=None, zodiac=None))]
+    fn events(&self, py: Python<'_>, start: f64, end: f64, bodies: Option<Vec<BodyArg>>, kind: &str,
+              boundaries: Option<Vec<String>>, zodiac: Option<&str>) -> PyResult<PyObject> {
+        let bodies = self.bodies_or_all(bodies)?;
+        let mut options = EventOptions { zodiac: parse_zodiac(zodiac)?, ..EventOptions::default() };
+        if let Some(list) = boundaries {
+            options.boundaries = list.iter().map(|b| Boundary::parse(b)).collect::<Result<_, _>>().map_err(value_error)?;
+        }
+        match kind {
+            "all" => {}
+            "ingress" => options.stations = false,
+            "station" => options.boundaries.clear(),
+            other => return Err(value_error(format!("unknown event kind {} (ingress, station, all)", other))),
+        }
+        let events = py.allow_threads(|| self.0.find_events(&bodies, start, end, &options)).map_err(kernel_error)?;
+        to_py(py, &Json::Array(events.iter().map(event_json).collect()))
+    }
+}
+
+#[pymodule]
+fn medusa(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyKernel>()?;
+    m.add("MedusaError", m.py().get_type_bound::<MedusaError>())?;
+    Ok(())
+}
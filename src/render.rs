@@ -0,0 +1,262 @@
+use std::fmt::Write;
+use crate::aspects::{find_aspects, find_aspects_between, Aspect, AspectConfig};
+use crate::bodies::{body_name, body_symbol, SIGNS, SIGN_NAMES};
+use crate::chart::Chart;
+use crate::coords::arc;
+use crate::kernel::Position;
+
+// Ring radii as fractions of the wheel radius, outside in.
+const ZODIAC_INNER: f64 = 0.85;
+const SINGLE_GLYPHS: f64 = 0.72;
+const SINGLE_HOUSES: f64 = 0.58;
+const OUTER_GLYPHS: f64 = 0.785;
+const BI_RING: f64 = 0.70;
+const INNER_GLYPHS: f64 = 0.625;
+const BI_HOUSES: f64 = 0.53;
+const TICK: f64 = 0.02;
+const HOUSE_RING_WIDTH: f64 = 0.09;
+
+// Fire, earth, air, water
+const ELEMENT_FILLS: [&str; 4] = ["#fbe3dc", "#e4f0dc", "#fdf6d8", "#dde8f6"];
+
+#[derive(Debug, Clone)]
+pub struct WheelOptions {
+    /// Width and height of the image, pixels.
+    pub size: f64,
+    pub aspects: AspectConfig,
+    /// Degree and minute labels beside each glyph.
+    pub show_degrees: bool,
+    /// Extra <style> rules appended to the defaults, for theming.
+    pub style: Option<String>,
+}
+
+impl Default for WheelOptions {
+    fn default() -> Self {
+        Self { size: 600.0, aspects: AspectConfig::default(), show_degrees: true, style: None }
+    }
+}
+
+/// Display longitudes for glyphs at `longitudes`, pushed apart until
+/// neighbours are at least `spacing` degrees from each other. Order is kept
+/// and each glyph stays as close to its true place as the crowding allows.
+pub fn spread_glyphs(longitudes: &[f64], spacing: f64) -> Vec<f64> {
+    let n = longitudes.len();
+    if n < 2 {
+        return longitudes.to_vec();
+    }
+    let spacing = spacing.min(360.0 / n as f64);
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| longitudes[a].rem_euclid(360.0).total_cmp(&longitudes[b].rem_euclid(360.0)));
+
+    // Unwrapped positions in sorted order, so a cluster can straddle 0° Aries
+    let mut shown: Vec<f64> = order.iter().map(|&i| longitudes[i].rem_euclid(360.0)).collect();
+    for _ in 0..200 {
+        let mut moved = false;
+        for k in 0..n {
+            let next = (k + 1) % n;
+            let next_lon = if next == 0 { shown[0] + 360.0 } else { shown[next] };
+            let gap = next_lon - shown[k];
+            if gap < spacing - 1e-6 {
+                let push = (spacing - gap) / 2.0;
+                shown[k] -= push;
+                shown[next] += push;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+
+    let mut out = vec![0.0; n];
+    for (k, &i) in order.iter().enumerate() {
+        out[i] = shown[k].rem_euclid(360.0);
+    }
+    out
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn format_degrees(longitude: f64) -> String {
+    let minutes = (longitude.rem_euclid(360.0) * 60.0).round() as i64 % 1800;
+    format!("{}°{:02}'", minutes / 60, minutes % 60)
+}
+
+struct Wheel {
+    svg: String,
+    center: f64,
+    radius: f64,
+    /// Longitude drawn at 9 o'clock: the Ascendant.
+    ascendant: f64,
+}
+
+impl Wheel {
+    fn new(options: &WheelOptions, ascendant: f64) -> Self {
+        let mut svg = String::new();
+        let size = options.size;
+        let _ = write!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{s}" height="{s}" viewBox="0 0 {s} {s}" font-family="sans-serif">"#, s = size);
+        let _ = write!(svg, "<style>.ring{{fill:none;stroke:#444;stroke-width:1}}.cusp{{stroke:#888;stroke-width:0.8}}\
+            .axis{{stroke:#222;stroke-width:1.6}}.tick{{stroke:#444;stroke-width:0.8}}\
+            .glyph{{font-size:{g}px;fill:#222}}.label{{font-size:{l}px;fill:#555}}.sign{{font-size:{z}px}}\
+            .house{{font-size:{l}px;fill:#777}}.retro{{font-size:{l}px;fill:#b03a2e}}{extra}</style>",
+            g = size * 0.034, l = size * 0.018, z = size * 0.03,
+            extra = options.style.as_deref().unwrap_or(""));
+        let _ = write!(svg, r##"<rect width="{s}" height="{s}" fill="#fff"/>"##, s = size);
+        Self { svg, center: size / 2.0, radius: size / 2.0 * 0.96, ascendant }
+    }
+
+    // Screen point for a longitude at a fraction of the radius; the zodiac
+    // runs counterclockwise from the Ascendant on the left.
+    fn point(&self, longitude: f64, r: f64) -> (f64, f64) {
+        let theta = (180.0 + longitude - self.ascendant).to_radians();
+        (self.center + self.radius * r * theta.cos(), self.center - self.radius * r * theta.sin())
+    }
+
+    fn circle(&mut self, r: f64, class: &str) {
+        let _ = write!(self.svg, r#"<circle class="{}" cx="{:.2}" cy="{:.2}" r="{:.2}"/>"#, class, self.center, self.center, self.radius * r);
+    }
+
+    fn line(&mut self, a: (f64, f64), b: (f64, f64), attributes: &str) {
+        let _ = write!(self.svg, r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" {}/>"#, a.0, a.1, b.0, b.1, attributes);
+    }
+
+    fn text(&mut self, at: (f64, f64), class: &str, text: &str, title: Option<&str>) {
+        let _ = write!(self.svg, r#"<text class="{}" x="{:.2}" y="{:.2}" text-anchor="middle" dominant-baseline="central">{}"#,
+            class, at.0, at.1, escape(text));
+        if let Some(title) = title {
+            let _ = write!(self.svg, "<title>{}</title>", escape(title));
+        }
+        self.svg.push_str("</text>");
+    }
+
+    fn zodiac(&mut self) {
+        for sign in 0..12 {
+            let start = sign as f64 * 30.0;
+            let (a, b) = (self.point(start, 1.0), self.point(start + 30.0, 1.0));
+            let (c, d) = (self.point(start + 30.0, ZODIAC_INNER), self.point(start, ZODIAC_INNER));
+            let (r_out, r_in) = (self.radius, self.radius * ZODIAC_INNER);
+            // Counterclockwise on screen is a negative sweep with y down
+            let _ = write!(self.svg, r##"<path d="M{:.2},{:.2} A{:.2},{:.2} 0 0 0 {:.2},{:.2} L{:.2},{:.2} A{:.2},{:.2} 0 0 1 {:.2},{:.2} Z" fill="{}" stroke="#444" stroke-width="1"/>"##,
+                a.0, a.1, r_out, r_out, b.0, b.1, c.0, c.1, r_in, r_in, d.0, d.1, ELEMENT_FILLS[sign % 4]);
+            let at = self.point(start + 15.0, (1.0 + ZODIAC_INNER) / 2.0);
+            self.text(at, "sign", SIGNS[sign], Some(SIGN_NAMES[sign]));
+        }
+        for degree in (0..360).step_by(5) {
+            let length = if degree % 10 == 0 { 0.025 } else { 0.015 };
+            let (a, b) = (self.point(degree as f64, ZODIAC_INNER), self.point(degree as f64, ZODIAC_INNER - length));
+            self.line(a, b, r#"class="tick""#);
+        }
+    }
+
+    fn houses(&mut self, cusps: &[f64; 12], mc: f64, inner: f64) {
+        let ring = inner + HOUSE_RING_WIDTH;
+        self.circle(ring, "ring");
+        self.circle(inner, "ring");
+        for (i, &cusp) in cusps.iter().enumerate() {
+            let (a, b) = (self.point(cusp, inner), self.point(cusp, ZODIAC_INNER));
+            self.line(a, b, r#"class="cusp""#);
+            let middle = cusp + (cusps[(i + 1) % 12] - cusp).rem_euclid(360.0) / 2.0;
+            let at = self.point(middle, inner + HOUSE_RING_WIDTH / 2.0);
+            self.text(at, "house", &(i + 1).to_string(), None);
+        }
+        // Ascendant–Descendant and MC–IC axes run through the zodiac ring
+        for (longitude, label) in [(self.ascendant, "AC"), (mc, "MC")] {
+            for (end, label) in [(longitude, label), (longitude + 180.0, "")] {
+                let (a, b) = (self.point(end, inner), self.point(end, 1.0));
+                self.line(a, b, r#"class="axis""#);
+                if !label.is_empty() {
+                    let at = self.point(end + 4.0, inner - 0.04);
+                    self.text(at, "label", label, None);
+                }
+            }
+        }
+    }
+
+    // Glyphs at `glyph_r`, spread apart where they crowd, each with a tick
+    // at its true longitude just inside the ring at `tick_at`.
+    fn bodies(&mut self, positions: &[(i32, Position)], glyph_r: f64, tick_at: f64, show_degrees: bool) {
+        let glyph_px = self.radius * 0.085;
+        let spacing = (glyph_px / (self.radius * glyph_r)).to_degrees();
+        let longitudes: Vec<f64> = positions.iter().map(|(_, p)| p.longitude).collect();
+        let shown = spread_glyphs(&longitudes, spacing);
+
+        for ((body, pos), &display) in positions.iter().zip(&shown) {
+            let tick_end = tick_at - TICK;
+            let (a, b) = (self.point(pos.longitude, tick_at), self.point(pos.longitude, tick_end));
+            self.line(a, b, r#"class="tick""#);
+            // Connector from the true place to a displaced glyph
+            if arc(pos.longitude, display).abs() > 0.5 {
+                let (c, d) = (self.point(pos.longitude, tick_end), self.point(display, glyph_r + 0.03));
+                self.line(c, d, r##"stroke="#bbb" stroke-width="0.6""##);
+            }
+
+            let title = format!("{} {}{}", body_name(*body), format_degrees(pos.longitude),
+                                SIGN_NAMES[(pos.longitude.rem_euclid(360.0) / 30.0) as usize % 12]);
+            let at = self.point(display, glyph_r);
+            self.text(at, "glyph", body_symbol(*body), Some(&title));
+            if show_degrees {
+                let at = self.point(display, glyph_r - 0.065);
+                let label = format!("{}{}", format_degrees(pos.longitude), if pos.is_retrograde() { "℞" } else { "" });
+                self.text(at, if pos.is_retrograde() { "retro" } else { "label" }, &label, None);
+            }
+        }
+    }
+
+    fn aspects(&mut self, aspects: &[Aspect], a: &[(i32, Position)], b: &[(i32, Position)], r: f64) {
+        let longitude = |positions: &[(i32, Position)], body: i32| positions.iter()
+            .find(|(p, _)| *p == body)
+            .map(|(_, pos)| pos.longitude);
+        for aspect in aspects.iter().filter(|aspect| aspect.kind.angle > 0.0) {
+            let (Some(from), Some(to)) = (longitude(a, aspect.body_a), longitude(b, aspect.body_b)) else { continue };
+            let colour = match aspect.kind.angle.round() as i64 {
+                180 | 90 | 45 | 135 => "#c0392b",
+                120 | 60 => "#2e6fd8",
+                _ => "#3a9a5b",
+            };
+            let dash = if aspect.kind.major { "" } else { r#" stroke-dasharray="4,3""# };
+            // Tighter aspects draw bolder
+            let width = 0.6 + 1.2 * (1.0 - aspect.orb / aspect.kind.orb.max(0.1)).clamp(0.0, 1.0);
+            let (p, q) = (self.point(from, r), self.point(to, r));
+            let attributes = format!(r#"stroke="{}" stroke-width="{:.2}"{}"#, colour, width, dash);
+            let _ = write!(self.svg, r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" {}><title>{} {} {} {}</title></line>"#,
+                p.0, p.1, q.0, q.1, attributes,
+                escape(body_name(aspect.body_a)), escape(&aspect.kind.name), escape(body_name(aspect.body_b)),
+                format_degrees(aspect.orb));
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.svg.push_str("</svg>");
+        self.svg
+    }
+}
+
+/// A natal wheel for `chart`: zodiac ring, house cusps with the Ascendant
+/// on the left, body glyphs spread apart where they crowd, and aspect lines
+/// from `options.aspects`.
+pub fn chart_wheel(chart: &Chart, options: &WheelOptions) -> String {
+    let mut wheel = Wheel::new(options, chart.houses.ascendant);
+    wheel.zodiac();
+    wheel.houses(&chart.houses.cusps, chart.houses.mc, SINGLE_HOUSES - HOUSE_RING_WIDTH);
+    wheel.bodies(&chart.positions, SINGLE_GLYPHS, ZODIAC_INNER, options.show_degrees);
+    let aspects = find_aspects(&chart.positions, &options.aspects);
+    wheel.aspects(&aspects, &chart.positions, &chart.positions, SINGLE_HOUSES - HOUSE_RING_WIDTH);
+    wheel.finish()
+}
+
+/// A bi-wheel: `chart` inside with its houses, `outer` (transits or a
+/// second chart's positions for synastry) in a ring outside it, and the
+/// aspects from the outer bodies to the inner ones.
+pub fn bi_wheel(chart: &Chart, outer: &[(i32, Position)], options: &WheelOptions) -> String {
+    let mut wheel = Wheel::new(options, chart.houses.ascendant);
+    wheel.zodiac();
+    wheel.circle(BI_RING, "ring");
+    wheel.houses(&chart.houses.cusps, chart.houses.mc, BI_HOUSES - HOUSE_RING_WIDTH);
+    wheel.bodies(outer, OUTER_GLYPHS, ZODIAC_INNER, options.show_degrees);
+    wheel.bodies(&chart.positions, INNER_GLYPHS, BI_RING, options.show_degrees);
+    let aspects = find_aspects_between(outer, &chart.positions, &options.aspects);
+    wheel.aspects(&aspects, outer, &chart.positions, BI_HOUSES - HOUSE_RING_WIDTH);
+    wheel.finish()
+}
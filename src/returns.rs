@@ -0,0 +1,141 @@
+use crate::chart::Chart;
+use crate::coords::{arc, precession, GeoLocation};
+use crate::houses::HouseSystem;
+use crate::kernel::{Kernel, KernelError};
+use crate::search::{find_roots, SearchOptions};
+use crate::sidereal::Zodiac;
+
+// Returns are searched this many days at a time, so the nearest one is
+// found without scanning the whole kernel.
+const CHUNK_DAYS: f64 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Next,
+    Previous,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReturnOptions {
+    pub zodiac: Zodiac,
+    /// Natal JD. When set, a tropical return is to the natal longitude plus
+    /// the precession since birth. Sidereal returns need no correction.
+    pub precession_from: Option<f64>,
+    pub house_system: HouseSystem,
+    pub search: SearchOptions,
+}
+
+impl Default for ReturnOptions {
+    fn default() -> Self {
+        Self {
+            zodiac: Zodiac::Tropical,
+            precession_from: None,
+            house_system: HouseSystem::Placidus,
+            search: SearchOptions::default(),
+        }
+    }
+}
+
+impl ReturnOptions {
+    // Target longitude and its rate at `jd`.
+    fn target(&self, natal_longitude: f64, jd: f64) -> (f64, f64) {
+        match (self.zodiac, self.precession_from) {
+            (Zodiac::Tropical, Some(natal_jd)) => (
+                natal_longitude + precession(jd) - precession(natal_jd),
+                precession(jd + 0.5) - precession(jd - 0.5),
+            ),
+            _ => (natal_longitude, 0.0),
+        }
+    }
+}
+
+impl Kernel {
+    /// Every return of `body` to `natal_longitude` in `from..to`.
+    pub fn returns(&self, body: i32, natal_longitude: f64, from: f64, to: f64,
+                   options: &ReturnOptions) -> Result<Vec<f64>, KernelError> {
+        let f = |jd: f64| {
+            let pos = self.position_in(body, jd, options.zodiac)?;
+            let (target, rate) = options.target(natal_longitude, jd);
+            Ok((arc(target, pos.longitude), pos.speed_longitude - rate))
+        };
+        find_roots(&f, from, to, &options.search)
+    }
+
+    /// The first return strictly after (or last strictly before) `jd`.
+    pub fn find_return(&self, body: i32, natal_longitude: f64, jd: f64, direction: Direction,
+                       options: &ReturnOptions) -> Result<f64, KernelError> {
+        let (start, end) = (self.header().start_jd, self.header().end_jd());
+        let mut edge = jd.clamp(start, end);
+        loop {
+            let found = match direction {
+                Direction::Next => {
+                    let to = (edge + CHUNK_DAYS).min(end);
+                    let hits = self.returns(body, natal_longitude, edge, to, options)?;
+                    edge = to;
+                    hits.into_iter().find(|&t| t > jd)
+                }
+                Direction::Previous => {
+                    let from = (edge - CHUNK_DAYS).max(start);
+                    let hits = self.returns(body, natal_longitude, from, edge, options)?;
+                    edge = from;
+                    hits.into_iter().rev().find(|&t| t < jd)
+                }
+            };
+            if let Some(t) = found {
+                return Ok(t);
+            }
+            if edge <= start || edge >= end {
+                return Err(KernelError::OutOfRange { jd, start, end });
+            }
+        }
+    }
+
+    /// The return chart cast for `location`, which may differ from the
+    /// birthplace (a relocated return).
+    pub fn return_chart(&self, body: i32, natal_longitude: f64, jd: f64, direction: Direction,
+                        location: GeoLocation, options: &ReturnOptions) -> Result<Chart, KernelError> {
+        let return_jd = self.find_return(body, natal_longitude, jd, direction, options)?;
+        self.chart(return_jd, location, options.house_system, options.zodiac)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::{KernelWriter, Position};
+
+    // A Sun moving exactly 1° a day from 100° at JD 0, for 800 days.
+    fn kernel() -> Kernel {
+        let mut writer = KernelWriter::new(0.0, 1.0, &[0]);
+        for day in 0..800 {
+            writer.push_record(&[Position { longitude: (100.0 + day as f64).rem_euclid(360.0), distance: 1.0,
+                                            speed_longitude: 1.0, ..Position::default() }]);
+        }
+        Kernel::from_bytes(writer.to_bytes()).unwrap()
+    }
+
+    #[test]
+    fn finds_returns_in_both_directions() {
+        let kernel = kernel();
+        let options = ReturnOptions::default();
+        assert_eq!(kernel.returns(0, 100.0, 0.0, 799.0, &options).unwrap().len(), 3);
+
+        let next = kernel.find_return(0, 100.0, 10.0, Direction::Next, &options).unwrap();
+        assert!((next - 360.0).abs() < 1e-4, "{}", next);
+        let previous = kernel.find_return(0, 100.0, 700.0, Direction::Previous, &options).unwrap();
+        assert!((previous - 360.0).abs() < 1e-4, "{}", previous);
+        // Nothing after the last return in the kernel
+        assert!(kernel.find_return(0, 100.0, 721.0, Direction::Next, &options).is_err());
+    }
+
+    #[test]
+    fn precession_moves_tropical_returns_later() {
+        let kernel = kernel();
+        let plain = kernel.find_return(0, 100.0, 10.0, Direction::Next, &ReturnOptions::default()).unwrap();
+        let options = ReturnOptions { precession_from: Some(0.0), ..ReturnOptions::default() };
+        let corrected = kernel.find_return(0, 100.0, 10.0, Direction::Next, &options).unwrap();
+        // About 50" of precession a year, at 1° a day
+        let expected = (precession(plain) - precession(0.0)) * 1.0;
+        assert!((corrected - plain - expected).abs() < 1e-4, "{} vs {}", corrected - plain, expected);
+    }
+}
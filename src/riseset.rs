@@ -0,0 +1,173 @@
+use chrono::NaiveDate;
+use crate::coords::GeoLocation;
+use crate::ephemeris::{self, EphemerisSource};
+use crate::kernel::{read_f64, read_u32, Kernel, KernelError};
+use crate::time::datetime_to_jd;
+use crate::{SE_BIT_DISC_BOTTOM, SE_BIT_DISC_CENTER, SE_BIT_NO_REFRACTION, SE_CALC_ITRANSIT,
+            SE_CALC_MTRANSIT, SE_CALC_RISE, SE_CALC_SET};
+
+pub const RISE_SET_TAG: [u8; 4] = *b"RISE";
+const HEADER_LEN: usize = 56;
+const EVENTS_PER_DAY: usize = 4;
+// Stored locations must match a request to within ~10 m.
+const LOCATION_TOLERANCE: f64 = 1e-4;
+
+/// Which part of the disc touches the horizon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disc {
+    /// Upper limb, the usual definition of sunrise.
+    Top,
+    Center,
+    Bottom,
+}
+
+impl Disc {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "top" | "limb" | "upper" => Ok(Disc::Top),
+            "center" | "centre" => Ok(Disc::Center),
+            "bottom" | "lower" => Ok(Disc::Bottom),
+            _ => Err(format!("unknown disc point {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiseSetOptions {
+    pub disc: Disc,
+    pub refraction: bool,
+    /// hPa; 0 uses the standard 1013.25.
+    pub pressure: f64,
+    /// °C
+    pub temperature: f64,
+}
+
+impl Default for RiseSetOptions {
+    fn default() -> Self {
+        Self { disc: Disc::Top, refraction: true, pressure: 0.0, temperature: 10.0 }
+    }
+}
+
+impl RiseSetOptions {
+    /// SE_BIT_* flags for swe_rise_trans.
+    pub fn flags(&self) -> i32 {
+        let mut flags = match self.disc {
+            Disc::Top => 0,
+            Disc::Center => SE_BIT_DISC_CENTER,
+            Disc::Bottom => SE_BIT_DISC_BOTTOM,
+        };
+        if !self.refraction {
+            flags |= SE_BIT_NO_REFRACTION;
+        }
+        flags as i32
+    }
+}
+
+/// One body's events for one local day, as JD UT. None when the event does
+/// not happen that day (the Moon skips a rise about once a month; bodies
+/// near the poles may not rise or set at all).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RiseSet {
+    pub rise: Option<f64>,
+    pub set: Option<f64>,
+    pub transit: Option<f64>,
+    pub lower_transit: Option<f64>,
+}
+
+/// JD of local mean midnight starting `date` at `longitude`.
+pub fn local_day_start(date: NaiveDate, longitude: f64) -> f64 {
+    let utc_midnight = datetime_to_jd(&date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    utc_midnight - longitude / 360.0
+}
+
+/// Rise, set and both transits of `body` in the day starting at `day_start`.
+pub fn compute_day(body: i32, day_start: f64, location: GeoLocation, options: &RiseSetOptions,
+                   source: EphemerisSource) -> Result<RiseSet, String> {
+    let within_day = |event: u32, flags: i32| -> Result<Option<f64>, String> {
+        let next = ephemeris::rise_trans(day_start, body, location, event as i32 | flags,
+                                         options.pressure, options.temperature, source)?;
+        Ok(next.filter(|&jd| jd < day_start + 1.0))
+    };
+    Ok(RiseSet {
+        rise: within_day(SE_CALC_RISE, options.flags())?,
+        set: within_day(SE_CALC_SET, options.flags())?,
+        transit: within_day(SE_CALC_MTRANSIT, 0)?,
+        lower_transit: within_day(SE_CALC_ITRANSIT, 0)?,
+    })
+}
+
+// RISE section layout:
+//   latitude f64, longitude f64, altitude f64, pressure f64, temperature f64,
+//   SE_BIT_* flags i32, first day start JD f64, day count u32
+//   per body in kernel order, per day: rise, set, transit, lower transit
+//   as f32 fractions of the day after its start (NaN = none)
+pub fn build_section(first_day: NaiveDate, days: u32, bodies: &[i32], location: GeoLocation,
+                     options: &RiseSetOptions, source: EphemerisSource) -> Result<Vec<u8>, String> {
+    let start = local_day_start(first_day, location.longitude);
+    let mut out = Vec::with_capacity(HEADER_LEN + 4 * EVENTS_PER_DAY * days as usize * bodies.len());
+    for value in [location.latitude, location.longitude, location.altitude, options.pressure, options.temperature] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    out.extend_from_slice(&options.flags().to_le_bytes());
+    out.extend_from_slice(&start.to_le_bytes());
+    out.extend_from_slice(&days.to_le_bytes());
+
+    for &body in bodies {
+        for day in 0..days {
+            let day_start = start + day as f64;
+            let events = compute_day(body, day_start, location, options, source)?;
+            for event in [events.rise, events.set, events.transit, events.lower_transit] {
+                let fraction = event.map_or(f32::NAN, |jd| (jd - day_start) as f32);
+                out.extend_from_slice(&fraction.to_le_bytes());
+            }
+        }
+    }
+    Ok(out)
+}
+
+impl Kernel {
+    /// Location the stored rise/set tables were generated for.
+    pub fn rise_set_location(&self) -> Result<GeoLocation, KernelError> {
+        let bytes = self.rise_set_section()?;
+        Ok(GeoLocation::new(read_f64(bytes, 0), read_f64(bytes, 8), read_f64(bytes, 16)))
+    }
+
+    /// Stored rise, set and transits of `body` on the local `date`.
+    pub fn rise_set(&self, body: i32, date: NaiveDate, location: GeoLocation) -> Result<RiseSet, KernelError> {
+        let bytes = self.rise_set_section()?;
+        let stored = self.rise_set_location()?;
+        if (stored.latitude - location.latitude).abs() > LOCATION_TOLERANCE
+            || (stored.longitude - location.longitude).abs() > LOCATION_TOLERANCE {
+            return Err(KernelError::LocationMismatch { latitude: stored.latitude, longitude: stored.longitude });
+        }
+
+        let start = read_f64(bytes, 44);
+        let days = read_u32(bytes, 52) as usize;
+        let day_start = local_day_start(date, stored.longitude);
+        let day = (day_start - start).round();
+        if day < 0.0 || day >= days as f64 {
+            return Err(KernelError::OutOfRange { jd: day_start, start, end: start + days as f64 });
+        }
+        let body_index = self.body_index(body)?;
+
+        let at = HEADER_LEN + 4 * EVENTS_PER_DAY * (body_index * days + day as usize);
+        let event = |i: usize| {
+            let fraction = f32::from_le_bytes(bytes[at + 4 * i..at + 4 * i + 4].try_into().unwrap());
+            (!fraction.is_nan()).then_some(day_start + fraction as f64)
+        };
+        Ok(RiseSet { rise: event(0), set: event(1), transit: event(2), lower_transit: event(3) })
+    }
+
+    fn rise_set_section(&self) -> Result<&[u8], KernelError> {
+        let bad = || KernelError::BadSection("RISE".to_string());
+        let bytes = self.section(&RISE_SET_TAG).ok_or_else(bad)?;
+        if bytes.len() < HEADER_LEN {
+            return Err(bad());
+        }
+        let days = read_u32(bytes, 52) as usize;
+        if bytes.len() < HEADER_LEN + 4 * EVENTS_PER_DAY * days * self.bodies().len() {
+            return Err(bad());
+        }
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,331 @@
+use crate::coords::arc;
+use crate::kernel::{Kernel, KernelError};
+
+pub const ONE_SECOND: f64 = 1.0 / 86400.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refinement {
+    Bisection,
+    /// Newton steps on the stored speeds, falling back to bisection when a
+    /// step leaves the bracket.
+    Newton,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SearchOptions {
+    /// Sampling interval in days used to bracket hits. It must be shorter
+    /// than the time between two passes, so keep it small for the Moon.
+    pub scan_step: f64,
+    pub tolerance: f64,
+    pub refinement: Refinement,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            scan_step: 0.25,
+            tolerance: ONE_SECOND,
+            refinement: Refinement::Newton,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    /// A fixed longitude, e.g. a natal position.
+    Natal(f64),
+    /// Another body in the same kernel.
+    Body(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AspectHit {
+    pub jd: f64,
+    pub body: i32,
+    pub target: Target,
+    /// Aspect angle measured from the target to the body (e.g. 90 or -90
+    /// for the two squares).
+    pub angle: f64,
+    pub body_retrograde: bool,
+}
+
+impl Kernel {
+    fn target_state(&self, target: Target, jd: f64) -> Result<(f64, f64), KernelError> {
+        match target {
+            Target::Natal(longitude) => Ok((longitude, 0.0)),
+            Target::Body(body) => {
+                let pos = self.position_at(body, jd)?;
+                Ok((pos.longitude, pos.speed_longitude))
+            }
+        }
+    }
+
+    // Signed distance of `body` from the exact aspect point, and its rate.
+    fn aspect_offset(&self, body: i32, target: Target, angle: f64, jd: f64) -> Result<(f64, f64), KernelError> {
+        let pos = self.position_at(body, jd)?;
+        let (target_lon, target_speed) = self.target_state(target, jd)?;
+        Ok((arc(target_lon + angle, pos.longitude), pos.speed_longitude - target_speed))
+    }
+
+    /// Every time in `from..to` when `body` is exactly `angle` degrees from
+    /// `target`. Both signed angles are searched for aspects other than the
+    /// conjunction and opposition, and retrograde loops give one hit per pass.
+    pub fn find_aspect_hits(&self, body: i32, target: Target, angle: f64, from: f64, to: f64,
+                            options: &SearchOptions) -> Result<Vec<AspectHit>, KernelError> {
+        let angle = angle.rem_euclid(360.0);
+        let mut angles = vec![angle];
+        if angle != 0.0 && angle != 180.0 {
+            angles.push(360.0 - angle);
+        }
+
+        let mut hits = Vec::new();
+        for angle in angles {
+            let f = |jd: f64| self.aspect_offset(body, target, angle, jd);
+            for jd in find_roots(&f, from, to, options)? {
+                hits.push(AspectHit {
+                    jd,
+                    body,
+                    target,
+                    angle: if angle > 180.0 { angle - 360.0 } else { angle },
+                    body_retrograde: self.position_at(body, jd)?.is_retrograde(),
+                });
+            }
+        }
+        hits.sort_by(|a, b| a.jd.total_cmp(&b.jd));
+        Ok(hits)
+    }
+
+    pub fn find_transits_to_natal(&self, body: i32, natal_longitude: f64, angles: &[f64], from: f64, to: f64,
+                                  options: &SearchOptions) -> Result<Vec<AspectHit>, KernelError> {
+        self.find_hits_for_angles(body, Target::Natal(natal_longitude), angles, from, to, options)
+    }
+
+    pub fn find_mutual_aspects(&self, body_a: i32, body_b: i32, angles: &[f64], from: f64, to: f64,
+                               options: &SearchOptions) -> Result<Vec<AspectHit>, KernelError> {
+        self.find_hits_for_angles(body_a, Target::Body(body_b), angles, from, to, options)
+    }
+
+    fn find_hits_for_angles(&self, body: i32, target: Target, angles: &[f64], from: f64, to: f64,
+                            options: &SearchOptions) -> Result<Vec<AspectHit>, KernelError> {
+        let mut hits = Vec::new();
+        for &angle in angles {
+            hits.extend(self.find_aspect_hits(body, target, angle, from, to, options)?);
+        }
+        hits.sort_by(|a, b| a.jd.total_cmp(&b.jd));
+        Ok(hits)
+    }
+}
+
+/// Roots of a signed angular offset `f` (value, rate per day) in `from..to`.
+/// Jumps across ±180° are not roots and are skipped. Within a step where the
+/// rate changes sign (a station) the extremum is located first, so a
+/// touch-and-go pass inside one step is still split into its two roots.
+pub fn find_roots<F>(f: &F, from: f64, to: f64, options: &SearchOptions) -> Result<Vec<f64>, KernelError>
+where
+    F: Fn(f64) -> Result<(f64, f64), KernelError>,
+{
+    // Anything else would never advance past `from`
+    if options.scan_step <= 0.0 || !options.scan_step.is_finite() {
+        return Err(KernelError::BadStep(options.scan_step));
+    }
+    let mut roots = Vec::new();
+    let mut a = from;
+    let (mut fa, mut ra) = f(a)?;
+
+    while a < to {
+        let b = (a + options.scan_step).min(to);
+        let (fb, rb) = f(b)?;
+
+        // Offsets near ±180 flip sign without passing through zero
+        let near_zero = fa.abs() < 90.0 && fb.abs() < 90.0;
+        if near_zero {
+            if fa == 0.0 {
+                roots.push(a);
+            } else if fa.signum() != fb.signum() && fb != 0.0 {
+                roots.push(refine(f, a, b, fa, options)?);
+            } else if ra.signum() != rb.signum() {
+                // The offset turns around inside this step
+                let turn = bisect_rate(f, a, b, ra, options.tolerance)?;
+                let (ft, _) = f(turn)?;
+                if ft.signum() != fa.signum() {
+                    roots.push(refine(f, a, turn, fa, options)?);
+                    roots.push(refine(f, turn, b, ft, options)?);
+                }
+            }
+        }
+
+        a = b;
+        fa = fb;
+        ra = rb;
+    }
+    if fa == 0.0 && roots.last() != Some(&to) {
+        roots.push(to);
+    }
+    Ok(roots)
+}
+
+pub(crate) fn refine<F>(f: &F, mut lo: f64, mut hi: f64, f_lo: f64, options: &SearchOptions) -> Result<f64, KernelError>
+where
+    F: Fn(f64) -> Result<(f64, f64), KernelError>,
+{
+    let lo_sign = f_lo.signum();
+    let mut t = (lo + hi) / 2.0;
+
+    for _ in 0..100 {
+        let (ft, rate) = f(t)?;
+        if ft == 0.0 {
+            return Ok(t);
+        }
+        // Checked before stepping: a step this small can round back onto
+        // the bracket edge and would fall through to bisection
+        if options.refinement == Refinement::Newton && rate != 0.0 && (ft / rate).abs() < options.tolerance {
+            return Ok(t - ft / rate);
+        }
+        if ft.signum() == lo_sign { lo = t } else { hi = t }
+        if hi - lo < options.tolerance {
+            break;
+        }
+
+        t = match options.refinement {
+            Refinement::Newton if rate != 0.0 => {
+                let next = t - ft / rate;
+                if next > lo && next < hi { next } else { (lo + hi) / 2.0 }
+            }
+            _ => (lo + hi) / 2.0,
+        };
+    }
+    Ok((lo + hi) / 2.0)
+}
+
+// Where the rate crosses zero between `lo` and `hi`.
+fn bisect_rate<F>(f: &F, mut lo: f64, mut hi: f64, rate_lo: f64, tolerance: f64) -> Result<f64, KernelError>
+where
+    F: Fn(f64) -> Result<(f64, f64), KernelError>,
+{
+    while hi - lo > tolerance {
+        let mid = (lo + hi) / 2.0;
+        let (_, rate) = f(mid)?;
+        if rate.signum() == rate_lo.signum() { lo = mid } else { hi = mid }
+    }
+    Ok((lo + hi) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use super::*;
+    use crate::kernel::{KernelWriter, Position};
+
+    // 0.5°/day with a 20° wobble: retrograde while cos(t/10) < -0.25,
+    // i.e. between t ≈ 18.2 and t ≈ 44.6.
+    fn looping(t: f64) -> (f64, f64) {
+        (0.5 * t + 20.0 * (t / 10.0).sin(), 0.5 + 2.0 * (t / 10.0).cos())
+    }
+
+    fn offset_from(c: f64) -> impl Fn(f64) -> Result<(f64, f64), KernelError> {
+        move |t| {
+            let (value, rate) = looping(t);
+            Ok((value - c, rate))
+        }
+    }
+
+    fn assert_roots(f: &impl Fn(f64) -> Result<(f64, f64), KernelError>, roots: &[f64], tolerance: f64) {
+        for &root in roots {
+            let (value, rate) = f(root).unwrap();
+            assert!((value / rate).abs() < tolerance, "residual {} days at {}", value / rate, root);
+        }
+    }
+
+    #[test]
+    fn finds_all_three_passes_of_a_retrograde_loop() {
+        let f = offset_from(15.0);
+        let options = SearchOptions::default();
+        let roots = find_roots(&f, 0.0, 60.0, &options).unwrap();
+        assert_eq!(roots.len(), 3, "{:?}", roots);
+        assert!(roots[0] < 18.2 && roots[1] > 18.2 && roots[1] < 44.6 && roots[2] > 44.6, "{:?}", roots);
+        assert!(f(roots[1]).unwrap().1 < 0.0, "middle pass should be retrograde");
+        assert_roots(&f, &roots, options.tolerance);
+    }
+
+    #[test]
+    fn splits_a_touch_and_go_pass_inside_one_step() {
+        // Both roots sit between two samples of a 20-day step, around the
+        // direct station at t ≈ 44.6 where the offset bottoms out near 2.94
+        let f = offset_from(3.5);
+        let options = SearchOptions { scan_step: 20.0, ..SearchOptions::default() };
+        let roots = find_roots(&f, 30.0, 70.0, &options).unwrap();
+        assert_eq!(roots.len(), 2, "{:?}", roots);
+        assert!(roots[0] < 44.6 && roots[1] > 44.6, "{:?}", roots);
+        assert_roots(&f, &roots, options.tolerance);
+    }
+
+    #[test]
+    fn reports_a_root_on_a_step_edge_once() {
+        let f = |t: f64| Ok((t - 1.0, 1.0));
+        let options = SearchOptions::default();
+        assert_eq!(find_roots(&f, 0.0, 2.0, &options).unwrap(), vec![1.0]);
+        assert_eq!(find_roots(&f, 1.0, 2.0, &options).unwrap(), vec![1.0]);
+        assert_eq!(find_roots(&f, 0.0, 1.0, &options).unwrap(), vec![1.0]);
+    }
+
+    #[test]
+    fn skips_the_jump_across_180_degrees() {
+        // The offset wraps from +179 to -179 here without being exact
+        let f = |t: f64| Ok((arc(0.0, 170.0 + t), 1.0));
+        assert!(find_roots(&f, 0.0, 20.0, &SearchOptions::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn newton_agrees_with_bisection_in_fewer_calls() {
+        let calls = Cell::new(0);
+        let f = |t: f64| {
+            calls.set(calls.get() + 1);
+            Ok((t * t * t - 2.0, 3.0 * t * t))
+        };
+        let mut refined = Vec::new();
+        for refinement in [Refinement::Newton, Refinement::Bisection] {
+            let options = SearchOptions { refinement, ..SearchOptions::default() };
+            calls.set(0);
+            let root = refine(&f, 1.0, 1.5, -1.0, &options).unwrap();
+            assert!((root - 2f64.cbrt()).abs() < options.tolerance, "{:?} gave {}", refinement, root);
+            refined.push(calls.get());
+        }
+        assert!(refined[0] < refined[1], "Newton took {} calls, bisection {}", refined[0], refined[1]);
+    }
+
+    #[test]
+    fn newton_falls_back_to_bisection_outside_the_bracket() {
+        // A flat rate sends the first Newton step far outside [0, 1]
+        let f = |t: f64| Ok(((t - 0.3).powi(3), 1e-9));
+        let root = refine(&f, 0.0, 1.0, -1.0, &SearchOptions::default()).unwrap();
+        assert!((root - 0.3).abs() < 1e-3, "{}", root);
+    }
+
+    #[test]
+    fn rejects_steps_that_never_advance() {
+        let f = offset_from(15.0);
+        for scan_step in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let options = SearchOptions { scan_step, ..SearchOptions::default() };
+            assert!(matches!(find_roots(&f, 0.0, 60.0, &options), Err(KernelError::BadStep(_))));
+        }
+    }
+
+    #[test]
+    fn finds_transits_in_a_kernel() {
+        let mut writer = KernelWriter::new(0.0, 0.5, &[0]);
+        for i in 0..=120 {
+            let (longitude, speed_longitude) = looping(i as f64 * 0.5);
+            writer.push_record(&[Position { longitude: (100.0 + longitude).rem_euclid(360.0), distance: 1.0,
+                                            speed_longitude, ..Position::default() }]);
+        }
+        let kernel = Kernel::from_bytes(writer.to_bytes()).unwrap();
+        let hits = kernel.find_transits_to_natal(0, 25.0, &[90.0], 0.0, 60.0, &SearchOptions::default()).unwrap();
+        assert_eq!(hits.len(), 3, "{:?}", hits);
+        assert!(hits.iter().all(|hit| hit.angle == 90.0));
+        assert_eq!(hits.iter().map(|hit| hit.body_retrograde).collect::<Vec<_>>(), [false, true, false]);
+        for hit in &hits {
+            let longitude = kernel.position_at(0, hit.jd).unwrap().longitude;
+            assert!(arc(115.0, longitude).abs() < 1e-4, "{} at {}", longitude, hit.jd);
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use crate::coords::precession;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ephemeris::{self, EphemerisSource};
+use crate::kernel::{read_f64, read_i32, read_u32, Kernel, KernelError, Position};
+use crate::{SE_NSIDM_PREDEF, SE_SIDM_FAGAN_BRADLEY, SE_SIDM_LAHIRI, SE_SIDM_TRUE_CITRA, SE_SIDM_USER};
+
+pub const AYANAMSA_TAG: [u8; 4] = *b"AYAN";
+
+// Index matches SE_SIDM_*.
+pub const MODE_NAMES: [&str; SE_NSIDM_PREDEF as usize] = [
+    "Fagan/Bradley", "Lahiri", "De Luce", "Raman", "Usha/Shashi",
+    "Krishnamurti", "Djwhal Khul", "Yukteshwar", "J.N. Bhasin",
+    "Babylonian/Kugler 1", "Babylonian/Kugler 2", "Babylonian/Kugler 3",
+    "Babylonian/Huber", "Babylonian/Eta Piscium", "Babylonian/Aldebaran = 15 Tau",
+    "Hipparchos", "Sassanian", "Galact. Center = 0 Sag", "J2000", "J1900", "B1950",
+    "Suryasiddhanta", "Suryasiddhanta, mean Sun", "Aryabhata", "Aryabhata, mean Sun",
+    "SS Revati", "SS Citra", "True Citra", "True Revati", "True Pushya (PVRN Rao)",
+    "Galactic Center (Gil Brand)", "Galactic Equator (IAU1958)", "Galactic Equator",
+    "Galactic Equator mid-Mula", "Skydram (Mardyks)", "True Mula (Chandra Hari)",
+    "Dhruva/Gal.Center/Mula (Wilhelm)", "Aryabhata 522", "Babylonian/Britton",
+    "\"Vedic\"/Sheoran", "Cochrane (Gal.Center = 0 Cap)", "Galactic Equator (Fiorenza)",
+    "Vettius Valens", "Lahiri 1940", "Lahiri VP285", "Krishnamurti-Senthilathiban",
+    "Lahiri ICRC",
+];
+
+// What the old medusajpl_sidereal kernels carried.
+pub const DEFAULT_MODES: [Ayanamsa; 3] = [
+    Ayanamsa::Predefined(SE_SIDM_FAGAN_BRADLEY as i32),
+    Ayanamsa::Predefined(SE_SIDM_LAHIRI as i32),
+    Ayanamsa::Predefined(SE_SIDM_TRUE_CITRA as i32),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ayanamsa {
+    /// One of the SE_SIDM_* modes.
+    Predefined(i32),
+    /// SE_SIDM_USER: ayanamsa `ayan_t0` degrees at JD `t0`.
+    User { t0: f64, ayan_t0: f64 },
+}
+
+impl Ayanamsa {
+    /// Accepts a mode number, a mode name ("lahiri", "true citra") or
+    /// `user:<t0>:<ayan_t0>`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if let Some(rest) = s.strip_prefix("user:") {
+            let mut parts = rest.split(':');
+            let t0 = parts.next().and_then(|p| p.parse().ok());
+            let ayan_t0 = parts.next().and_then(|p| p.parse().ok());
+            return match (t0, ayan_t0, parts.next()) {
+                (Some(t0), Some(ayan_t0), None) => Ok(Ayanamsa::User { t0, ayan_t0 }),
+                _ => Err(format!("expected user:<t0>:<ayan_t0>, got {}", s)),
+            };
+        }
+        if let Ok(mode) = s.parse::<i32>() {
+            if (0..SE_NSIDM_PREDEF as i32).contains(&mode) {
+                return Ok(Ayanamsa::Predefined(mode));
+            }
+            return Err(format!("unknown ayanamsa mode {}", mode));
+        }
+
+        let wanted = slug(s);
+        MODE_NAMES.iter()
+            .position(|name| slug(name) == wanted)
+            .map(|i| Ayanamsa::Predefined(i as i32))
+            .ok_or_else(|| format!("unknown ayanamsa {}", s))
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Ayanamsa::Predefined(mode) => MODE_NAMES.get(*mode as usize)
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| format!("mode {}", mode)),
+            Ayanamsa::User { t0, ayan_t0 } => format!("User ({:.6}° at JD {})", ayan_t0, t0),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn sid_mode(&self) -> (i32, f64, f64) {
+        match *self {
+            Ayanamsa::Predefined(mode) => (mode, 0.0, 0.0),
+            Ayanamsa::User { t0, ayan_t0 } => (SE_SIDM_USER as i32, t0, ayan_t0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Zodiac {
+    Tropical,
+    Sidereal(Ayanamsa),
+}
+
+impl Zodiac {
+    pub fn name(&self) -> String {
+        match self {
+            Zodiac::Tropical => "Tropical".to_string(),
+            Zodiac::Sidereal(ayanamsa) => ayanamsa.name(),
+        }
+    }
+}
+
+fn slug(s: &str) -> String {
+    s.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_lowercase()
+}
+
+// AYAN section layout:
+//   start JD f64, step f64, sample count u32, mode count u32
+//   nutation in longitude: f64 × sample count
+//   per mode: sid mode i32, t0 f64, ayan_t0 f64, mean ayanamsa f64 × sample count
+#[cfg(not(target_arch = "wasm32"))]
+pub fn build_section(start_jd: f64, end_jd: f64, step: f64, modes: &[Ayanamsa],
+                     source: EphemerisSource) -> Result<Vec<u8>, String> {
+    let count = ((end_jd - start_jd) / step).ceil() as u32 + 1;
+    let jd_at = |i: u32| start_jd + i as f64 * step;
+
+    let mut out = Vec::with_capacity(24 + 8 * count as usize * (modes.len() + 1));
+    out.extend_from_slice(&start_jd.to_le_bytes());
+    out.extend_from_slice(&step.to_le_bytes());
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&(modes.len() as u32).to_le_bytes());
+
+    for i in 0..count {
+        out.extend_from_slice(&ephemeris::nutation_longitude(jd_at(i), source)?.to_le_bytes());
+    }
+    for mode in modes {
+        let (sid_mode, t0, ayan_t0) = mode.sid_mode();
+        out.extend_from_slice(&sid_mode.to_le_bytes());
+        out.extend_from_slice(&t0.to_le_bytes());
+        out.extend_from_slice(&ayan_t0.to_le_bytes());
+        for i in 0..count {
+            out.extend_from_slice(&ephemeris::ayanamsa(jd_at(i), sid_mode, t0, ayan_t0).to_le_bytes());
+        }
+    }
+    Ok(out)
+}
+
+// A one-sample AYAN section for the mean ayanamsas a v1 kernel carried at
+// its single JD. v1 stored no nutation, so it is taken as zero.
+pub(crate) fn snapshot_section(jd: f64, modes: &[(i32, f64)]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(32 + 28 * modes.len());
+    out.extend_from_slice(&jd.to_le_bytes());
+    out.extend_from_slice(&1.0f64.to_le_bytes());
+    out.extend_from_slice(&1u32.to_le_bytes());
+    out.extend_from_slice(&(modes.len() as u32).to_le_bytes());
+    out.extend_from_slice(&0.0f64.to_le_bytes());
+    for &(sid_mode, ayanamsa) in modes {
+        out.extend_from_slice(&sid_mode.to_le_bytes());
+        out.extend_from_slice(&[0; 16]);
+        out.extend_from_slice(&ayanamsa.to_le_bytes());
+    }
+    out
+}
+
+struct AyanamsaSection<'a> {
+    bytes: &'a [u8],
+    start_jd: f64,
+    step: f64,
+    count: usize,
+    modes: Vec<(Ayanamsa, usize)>,
+}
+
+impl<'a> AyanamsaSection<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, KernelError> {
+        let bad = || KernelError::BadSection("AYAN".to_string());
+        if bytes.len() < 24 {
+            return Err(bad());
+        }
+        let start_jd = read_f64(bytes, 0);
+        let step = read_f64(bytes, 8);
+        let count = read_u32(bytes, 16) as usize;
+        let mode_count = read_u32(bytes, 20) as usize;
+        let series_len = count.checked_mul(8).ok_or_else(bad)?;
+        let needed = series_len.checked_add(20)
+            .and_then(|len| len.checked_mul(mode_count))
+            .and_then(|len| len.checked_add(24 + series_len));
+        if count == 0 || !step.is_finite() || step <= 0.0 || needed.is_none_or(|needed| bytes.len() < needed) {
+            return Err(bad());
+        }
+
+        let mut modes = Vec::with_capacity(mode_count);
+        let mut at = 24 + series_len;
+        for _ in 0..mode_count {
+            let sid_mode = read_i32(bytes, at);
+            let mode = if sid_mode == SE_SIDM_USER as i32 {
+                Ayanamsa::User { t0: read_f64(bytes, at + 4), ayan_t0: read_f64(bytes, at + 12) }
+            } else {
+                Ayanamsa::Predefined(sid_mode)
+            };
+            modes.push((mode, at + 20));
+            at += 20 + series_len;
+        }
+        Ok(Self { bytes, start_jd, step, count, modes })
+    }
+
+    // Linear interpolation of a stored series; returns value and rate per day.
+    fn sample(&self, series_offset: usize, jd: f64) -> Result<(f64, f64), KernelError> {
+        let end = self.start_jd + self.step * (self.count - 1) as f64;
+        if !(self.start_jd..=end).contains(&jd) {
+            return Err(KernelError::OutOfRange { jd, start: self.start_jd, end });
+        }
+        if self.count == 1 {
+            return Ok((read_f64(self.bytes, series_offset), 0.0));
+        }
+        let i = (((jd - self.start_jd) / self.step).floor() as usize).min(self.count - 2);
+        let a = read_f64(self.bytes, series_offset + 8 * i);
+        let b = read_f64(self.bytes, series_offset + 8 * (i + 1));
+        let t = (jd - self.start_jd) / self.step - i as f64;
+        Ok((a + (b - a) * t, (b - a) / self.step))
+    }
+
+    fn mean(&self, mode: Ayanamsa, jd: f64) -> Result<(f64, f64), KernelError> {
+        if let Some(&(_, offset)) = self.modes.iter().find(|(m, _)| *m == mode) {
+            return self.sample(offset, jd);
+        }
+        match mode {
+            // User modes are plain precession from (t0, ayan_t0), so they
+            // need no stored series.
+            Ayanamsa::User { t0, ayan_t0 } => {
+                let rate = precession(jd + 0.5) - precession(jd - 0.5);
+                Ok((ayan_t0 + precession(jd) - precession(t0), rate))
+            }
+            Ayanamsa::Predefined(m) => Err(KernelError::AyanamsaNotStored(m)),
+        }
+    }
+}
+
+impl Kernel {
+    fn ayanamsa_section(&self) -> Result<AyanamsaSection<'_>, KernelError> {
+        let bytes = self.section(&AYANAMSA_TAG)
+            .ok_or_else(|| KernelError::BadSection("AYAN".to_string()))?;
+        AyanamsaSection::parse(bytes)
+    }
+
+    pub fn stored_ayanamsas(&self) -> Vec<Ayanamsa> {
+        self.ayanamsa_section()
+            .map(|s| s.modes.iter().map(|(m, _)| *m).collect())
+            .unwrap_or_default()
+    }
+
+    /// True ayanamsa (mean + nutation) at `jd`, as used by SEFLG_SIDEREAL.
+    pub fn ayanamsa_at(&self, mode: Ayanamsa, jd: f64) -> Result<f64, KernelError> {
+        Ok(self.ayanamsa_with_rate(mode, jd)?.0)
+    }
+
+    pub(crate) fn ayanamsa_with_rate(&self, mode: Ayanamsa, jd: f64) -> Result<(f64, f64), KernelError> {
+        let section = self.ayanamsa_section()?;
+        let (nutation, nutation_rate) = section.sample(24, jd)?;
+        let (mean, mean_rate) = section.mean(mode, jd)?;
+        Ok((mean + nutation, mean_rate + nutation_rate))
+    }
+
+    pub fn sidereal_position_at(&self, body: i32, jd: f64, mode: Ayanamsa) -> Result<Position, KernelError> {
+        let mut pos = self.position_at(body, jd)?;
+        let (ayanamsa, rate) = self.ayanamsa_with_rate(mode, jd)?;
+        pos.longitude = (pos.longitude - ayanamsa).rem_euclid(360.0);
+        pos.speed_longitude -= rate;
+        Ok(pos)
+    }
+
+    pub fn position_in(&self, body: i32, jd: f64, zodiac: Zodiac) -> Result<Position, KernelError> {
+        match zodiac {
+            Zodiac::Tropical => self.position_at(body, jd),
+            Zodiac::Sidereal(mode) => self.sidereal_position_at(body, jd, mode),
+        }
+    }
+
+    pub fn positions_in(&self, jd: f64, zodiac: Zodiac) -> Result<Vec<(i32, Position)>, KernelError> {
+        self.bodies().iter()
+            .map(|&body| Ok((body, self.position_in(body, jd, zodiac)?)))
+            .collect()
+    }
+}
@@ -0,0 +1,395 @@
+use crate::coords::{arc, declination, mean_obliquity, right_ascension, semi_arc, GeoLocation};
+use crate::ephemeris::{self, EphemerisSource};
+use crate::kernel::{read_f64, read_u32, Kernel, KernelError, Position};
+use crate::search::{find_roots, SearchOptions};
+use crate::sidereal::Zodiac;
+
+pub const STAR_TAG: [u8; 4] = *b"STAR";
+const NAME_LEN: usize = 64;
+const HEADER_LEN: usize = 24;
+
+// Stars move under a minute of arc a year from precession and proper
+// motion, plus ±20" of annual aberration, so a 30-day series interpolates
+// them to well under an arcsecond.
+pub const DEFAULT_STAR_STEP: f64 = 30.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedStar {
+    /// "Name,designation" as in sefstars.txt, e.g. "Regulus,alLeo".
+    pub name: String,
+    pub magnitude: f64,
+}
+
+impl FixedStar {
+    /// The traditional name, without the Bayer designation.
+    pub fn short_name(&self) -> &str {
+        self.name.split(',').next().unwrap_or(&self.name)
+    }
+
+    fn matches(&self, wanted: &str) -> bool {
+        self.name.eq_ignore_ascii_case(wanted)
+            || self.name.split(',').any(|part| part.trim().eq_ignore_ascii_case(wanted.trim()))
+    }
+}
+
+/// A kernel body within orb of a star's longitude.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StarConjunction {
+    pub star: String,
+    pub body: i32,
+    /// Body minus star, degrees.
+    pub orb: f64,
+}
+
+/// The four points of the diurnal circle used for parans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HorizonEvent {
+    Rising,
+    Culminating,
+    Setting,
+    AntiCulminating,
+}
+
+impl HorizonEvent {
+    pub const ALL: [HorizonEvent; 4] = [
+        HorizonEvent::Rising, HorizonEvent::Culminating,
+        HorizonEvent::Setting, HorizonEvent::AntiCulminating,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HorizonEvent::Rising => "rising",
+            HorizonEvent::Culminating => "culminating",
+            HorizonEvent::Setting => "setting",
+            HorizonEvent::AntiCulminating => "anti-culminating",
+        }
+    }
+
+    // ARMC at which a point with this right ascension and semi-arc reaches
+    // the event; None for rising and setting of circumpolar points.
+    fn armc(&self, ra: f64, sda: Option<f64>) -> Option<f64> {
+        match self {
+            HorizonEvent::Rising => sda.map(|sda| (ra - sda).rem_euclid(360.0)),
+            HorizonEvent::Culminating => Some(ra),
+            HorizonEvent::Setting => sda.map(|sda| (ra + sda).rem_euclid(360.0)),
+            HorizonEvent::AntiCulminating => Some((ra + 180.0).rem_euclid(360.0)),
+        }
+    }
+}
+
+/// A star and a body on angles at the same moment of the day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paran {
+    pub star: String,
+    pub star_event: HorizonEvent,
+    pub body: i32,
+    pub body_event: HorizonEvent,
+    /// Degrees of sidereal time between the two events (1° = 4 minutes).
+    pub orb: f64,
+}
+
+// STAR section layout:
+//   start JD f64, step f64, sample count u32, star count u32
+//   per star: name [u8; 64] NUL-padded, magnitude f64,
+//             (longitude f64, latitude f64) × sample count
+pub fn build_section(start_jd: f64, end_jd: f64, step: f64, names: &[&str],
+                     source: EphemerisSource) -> Result<Vec<u8>, String> {
+    let count = ((end_jd - start_jd) / step).ceil() as u32 + 1;
+    let mut out = Vec::with_capacity(HEADER_LEN + names.len() * (NAME_LEN + 8 + 16 * count as usize));
+    out.extend_from_slice(&start_jd.to_le_bytes());
+    out.extend_from_slice(&step.to_le_bytes());
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&(names.len() as u32).to_le_bytes());
+
+    for name in names {
+        let (resolved, _) = ephemeris::fixed_star(name, start_jd, source)?;
+        let mut padded = [0u8; NAME_LEN];
+        for (p, b) in padded.iter_mut().zip(resolved.bytes().take(NAME_LEN - 1)) {
+            *p = b;
+        }
+        out.extend_from_slice(&padded);
+        out.extend_from_slice(&ephemeris::fixed_star_magnitude(&resolved)?.to_le_bytes());
+        for i in 0..count {
+            let (_, pos) = ephemeris::fixed_star(&resolved, start_jd + i as f64 * step, source)?;
+            out.extend_from_slice(&pos.longitude.to_le_bytes());
+            out.extend_from_slice(&pos.latitude.to_le_bytes());
+        }
+    }
+    Ok(out)
+}
+
+struct StarSection<'a> {
+    bytes: &'a [u8],
+    start_jd: f64,
+    step: f64,
+    count: usize,
+    stars: Vec<(FixedStar, usize)>,
+}
+
+impl<'a> StarSection<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, KernelError> {
+        let bad = || KernelError::BadSection("STAR".to_string());
+        if bytes.len() < HEADER_LEN {
+            return Err(bad());
+        }
+        let start_jd = read_f64(bytes, 0);
+        let step = read_f64(bytes, 8);
+        let count = read_u32(bytes, 16) as usize;
+        let star_count = read_u32(bytes, 20) as usize;
+        let star_len = count.checked_mul(16).and_then(|len| len.checked_add(NAME_LEN + 8)).ok_or_else(bad)?;
+        let needed = star_count.checked_mul(star_len).and_then(|len| len.checked_add(HEADER_LEN));
+        if count == 0 || needed.is_none_or(|needed| bytes.len() < needed) {
+            return Err(bad());
+        }
+
+        let stars = (0..star_count)
+            .map(|i| {
+                let at = HEADER_LEN + i * star_len;
+                let raw = &bytes[at..at + NAME_LEN];
+                let end = raw.iter().position(|&b| b == 0).unwrap_or(NAME_LEN);
+                let name = String::from_utf8_lossy(&raw[..end]).into_owned();
+                let magnitude = read_f64(bytes, at + NAME_LEN);
+                (FixedStar { name, magnitude }, at + NAME_LEN + 8)
+            })
+            .collect();
+        Ok(Self { bytes, start_jd, step, count, stars })
+    }
+
+    // Linear interpolation of one star's series, with speeds per day.
+    fn position(&self, series_offset: usize, jd: f64) -> Result<Position, KernelError> {
+        let end = self.start_jd + self.step * (self.count - 1) as f64;
+        if jd < self.start_jd || jd > end {
+            return Err(KernelError::OutOfRange { jd, start: self.start_jd, end });
+        }
+        let sample = |i: usize| (read_f64(self.bytes, series_offset + 16 * i), read_f64(self.bytes, series_offset + 16 * i + 8));
+        if self.count == 1 {
+            let (longitude, latitude) = sample(0);
+            return Ok(Position { longitude, latitude, ..Position::default() });
+        }
+        let i = (((jd - self.start_jd) / self.step).floor() as usize).min(self.count - 2);
+        let t = (jd - self.start_jd) / self.step - i as f64;
+        let (lon_a, lat_a) = sample(i);
+        let (lon_b, lat_b) = sample(i + 1);
+        let dlon = arc(lon_a, lon_b);
+        Ok(Position {
+            longitude: (lon_a + dlon * t).rem_euclid(360.0),
+            latitude: lat_a + (lat_b - lat_a) * t,
+            speed_longitude: dlon / self.step,
+            speed_latitude: (lat_b - lat_a) / self.step,
+            ..Position::default()
+        })
+    }
+}
+
+impl Kernel {
+    fn star_section(&self) -> Result<StarSection<'_>, KernelError> {
+        let bytes = self.section(&STAR_TAG)
+            .ok_or_else(|| KernelError::BadSection("STAR".to_string()))?;
+        StarSection::parse(bytes)
+    }
+
+    pub fn fixed_stars(&self) -> Vec<FixedStar> {
+        self.star_section()
+            .map(|s| s.stars.into_iter().map(|(star, _)| star).collect())
+            .unwrap_or_default()
+    }
+
+    /// Apparent tropical position of a stored star, looked up by name,
+    /// designation ("alLeo") or both. Distance is not stored.
+    pub fn star_position(&self, star: &str, jd: f64) -> Result<Position, KernelError> {
+        let section = self.star_section()?;
+        let (_, offset) = section.stars.iter()
+            .find(|(s, _)| s.matches(star))
+            .ok_or_else(|| KernelError::UnknownStar(star.to_string()))?;
+        section.position(*offset, jd)
+    }
+
+    pub fn star_position_in(&self, star: &str, jd: f64, zodiac: Zodiac) -> Result<Position, KernelError> {
+        let mut pos = self.star_position(star, jd)?;
+        if let Zodiac::Sidereal(mode) = zodiac {
+            pos.longitude = (pos.longitude - self.ayanamsa_at(mode, jd)?).rem_euclid(360.0);
+        }
+        Ok(pos)
+    }
+
+    /// Every kernel body within `orb` degrees of longitude of a stored star
+    /// at `jd`, tightest first.
+    pub fn star_conjunctions_at(&self, jd: f64, orb: f64) -> Result<Vec<StarConjunction>, KernelError> {
+        let positions = self.positions_at(jd)?;
+        let mut conjunctions = Vec::new();
+        for star in self.fixed_stars() {
+            let star_lon = self.star_position(&star.name, jd)?.longitude;
+            for (body, pos) in &positions {
+                let distance = arc(star_lon, pos.longitude);
+                if distance.abs() <= orb {
+                    conjunctions.push(StarConjunction { star: star.name.clone(), body: *body, orb: distance });
+                }
+            }
+        }
+        conjunctions.sort_by(|a, b| a.orb.abs().total_cmp(&b.orb.abs()));
+        Ok(conjunctions)
+    }
+
+    /// Times in `from..to` when `body` passes the longitude of `star`.
+    pub fn find_star_conjunctions(&self, body: i32, star: &str, from: f64, to: f64,
+                                  options: &SearchOptions) -> Result<Vec<f64>, KernelError> {
+        let f = |jd: f64| {
+            let pos = self.position_at(body, jd)?;
+            let star = self.star_position(star, jd)?;
+            Ok((arc(star.longitude, pos.longitude), pos.speed_longitude - star.speed_longitude))
+        };
+        find_roots(&f, from, to, options)
+    }
+
+    /// Parans at `location` for the day of `jd`: a star and a body reaching
+    /// the horizon or meridian within `orb` degrees of sidereal time of each
+    /// other, with positions held at `jd` as in the usual natal method.
+    pub fn parans(&self, jd: f64, location: GeoLocation, orb: f64) -> Result<Vec<Paran>, KernelError> {
+        let eps = mean_obliquity(jd);
+        let diurnal = |pos: &Position| {
+            let decl = declination(pos.longitude, pos.latitude, eps);
+            (right_ascension(pos.longitude, pos.latitude, eps), semi_arc(decl, location.latitude))
+        };
+
+        let bodies: Vec<(i32, (f64, Option<f64>))> = self.positions_at(jd)?.iter()
+            .map(|(body, pos)| (*body, diurnal(pos)))
+            .collect();
+        let mut parans = Vec::new();
+        for star in self.fixed_stars() {
+            let (star_ra, star_sda) = diurnal(&self.star_position(&star.name, jd)?);
+            for star_event in HorizonEvent::ALL {
+                let Some(star_armc) = star_event.armc(star_ra, star_sda) else { continue };
+                for &(body, (ra, sda)) in &bodies {
+                    for body_event in HorizonEvent::ALL {
+                        let Some(body_armc) = body_event.armc(ra, sda) else { continue };
+                        let distance = arc(star_armc, body_armc).abs();
+                        if distance <= orb {
+                            parans.push(Paran { star: star.name.clone(), star_event, body, body_event, orb: distance });
+                        }
+                    }
+                }
+            }
+        }
+        parans.sort_by(|a, b| a.orb.total_cmp(&b.orb));
+        Ok(parans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::KernelWriter;
+
+    const START: f64 = 2460300.5;
+
+    // Name, magnitude and (longitude, latitude) samples.
+    type Star<'a> = (&'a str, f64, &'a [(f64, f64)]);
+
+    // A STAR section as build_section lays it out.
+    fn section(start_jd: f64, step: f64, stars: &[Star]) -> Vec<u8> {
+        let count = stars.first().map_or(1, |(_, _, samples)| samples.len()) as u32;
+        let mut out = Vec::new();
+        out.extend_from_slice(&start_jd.to_le_bytes());
+        out.extend_from_slice(&step.to_le_bytes());
+        out.extend_from_slice(&count.to_le_bytes());
+        out.extend_from_slice(&(stars.len() as u32).to_le_bytes());
+        for (name, magnitude, samples) in stars {
+            let mut padded = [0u8; NAME_LEN];
+            padded[..name.len()].copy_from_slice(name.as_bytes());
+            out.extend_from_slice(&padded);
+            out.extend_from_slice(&magnitude.to_le_bytes());
+            for (longitude, latitude) in *samples {
+                out.extend_from_slice(&longitude.to_le_bytes());
+                out.extend_from_slice(&latitude.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    fn stars() -> Vec<u8> {
+        section(START, 30.0, &[
+            ("Regulus,alLeo", 1.4, &[(150.0, 0.4), (150.05, 0.4)]),
+            ("Scheat,bePeg", 2.4, &[(359.99, 31.1), (0.01, 31.1)]),
+        ])
+    }
+
+    // The Sun moving a degree a day from 140° over twenty days.
+    fn kernel_with(section: Option<Vec<u8>>) -> Kernel {
+        let mut writer = KernelWriter::new(START, 1.0, &[0]);
+        for day in 0..=20 {
+            writer.push_record(&[Position { longitude: 140.0 + day as f64, speed_longitude: 1.0, ..Position::default() }]);
+        }
+        if let Some(section) = section {
+            writer.add_section(STAR_TAG, section);
+        }
+        Kernel::from_bytes(writer.to_bytes()).unwrap()
+    }
+
+    #[test]
+    fn looks_up_stars_by_name_or_designation() {
+        let kernel = kernel_with(Some(stars()));
+        let stored = kernel.fixed_stars();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0], FixedStar { name: "Regulus,alLeo".to_string(), magnitude: 1.4 });
+        assert_eq!(stored[1].short_name(), "Scheat");
+
+        let at_start = kernel.star_position("regulus", START).unwrap();
+        assert_eq!((at_start.longitude, at_start.latitude), (150.0, 0.4));
+        assert_eq!(kernel.star_position("alLeo", START).unwrap(), at_start);
+        assert_eq!(kernel.star_position("Regulus,alLeo", START).unwrap(), at_start);
+        assert!(matches!(kernel.star_position("Spica", START), Err(KernelError::UnknownStar(_))));
+        assert!(matches!(kernel.star_position("Regulus", START + 31.0), Err(KernelError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn interpolates_across_aries() {
+        let kernel = kernel_with(Some(stars()));
+        let regulus = kernel.star_position("Regulus", START + 15.0).unwrap();
+        assert!((regulus.longitude - 150.025).abs() < 1e-9);
+        assert!((regulus.speed_longitude - 0.05 / 30.0).abs() < 1e-12);
+        let scheat = kernel.star_position("Scheat", START + 15.0).unwrap();
+        assert!(arc(0.0, scheat.longitude).abs() < 1e-9);
+        assert!(scheat.speed_longitude > 0.0);
+    }
+
+    #[test]
+    fn rejects_missing_and_malformed_sections() {
+        let kernel = kernel_with(None);
+        assert!(kernel.fixed_stars().is_empty());
+        assert!(matches!(kernel.star_position("Regulus", START), Err(KernelError::BadSection(_))));
+
+        let good = stars();
+        assert!(StarSection::parse(&good[..HEADER_LEN - 1]).is_err());
+        assert!(StarSection::parse(&good[..good.len() - 1]).is_err());
+        let mut empty = good.clone();
+        empty[16..20].copy_from_slice(&0u32.to_le_bytes());
+        assert!(StarSection::parse(&empty).is_err());
+        let mut huge = good.clone();
+        huge[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+        huge[20..24].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(StarSection::parse(&huge).is_err());
+    }
+
+    #[test]
+    fn finds_conjunctions_with_kernel_bodies() {
+        let kernel = kernel_with(Some(stars()));
+        let now = kernel.star_conjunctions_at(START + 10.0, 1.0).unwrap();
+        assert_eq!(now.len(), 1);
+        assert_eq!((now[0].star.as_str(), now[0].body), ("Regulus,alLeo", 0));
+        assert!((now[0].orb + 0.05 / 3.0).abs() < 1e-9);
+
+        let times = kernel.find_star_conjunctions(0, "Regulus", START, START + 20.0, &SearchOptions::default()).unwrap();
+        assert_eq!(times.len(), 1);
+        assert!((times[0] - (START + 10.0 * 600.0 / 599.0)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn parans_on_the_equator_pair_like_events() {
+        // Every point's semi-arc is 90° on the equator, so the Sun and a star
+        // near its longitude pair only on matching events.
+        let kernel = kernel_with(Some(stars()));
+        let parans = kernel.parans(START + 10.0, GeoLocation::new(0.0, 0.0, 0.0), 1.0).unwrap();
+        assert_eq!(parans.len(), 4);
+        assert!(parans.iter().all(|p| p.star == "Regulus,alLeo" && p.star_event == p.body_event));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::aspects::{find_aspects_between, Aspect, AspectConfig};
+use crate::chart::Chart;
+use crate::coords::{mean_obliquity, GeoLocation};
+use crate::houses::{houses_from_armc, HouseSystem};
+use crate::kernel::{Kernel, KernelError, Position};
+use crate::midpoints::midpoint;
+use crate::sidereal::Zodiac;
+
+// Relationship charts from two natal charts: synastry compares them as they
+// are, the composite takes the midpoint of each pair of points, and the
+// Davison chart is cast for the midpoint in time and space.
+
+/// A body of one chart placed in the houses of the other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HouseOverlay {
+    pub body: i32,
+    pub longitude: f64,
+    pub house: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Synastry {
+    /// Aspects from each body of A (`body_a`) to each body of B (`body_b`).
+    pub aspects: Vec<Aspect>,
+    /// A's bodies in B's houses.
+    pub a_in_b: Vec<HouseOverlay>,
+    /// B's bodies in A's houses.
+    pub b_in_a: Vec<HouseOverlay>,
+}
+
+/// Everything [`Kernel::relationship`] computes for a pair of births.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relationship {
+    pub a: Chart,
+    pub b: Chart,
+    pub synastry: Synastry,
+    pub composite: Chart,
+    pub davison: Chart,
+}
+
+fn overlays(from: &Chart, into: &Chart) -> Vec<HouseOverlay> {
+    from.positions.iter()
+        .map(|(body, pos)| HouseOverlay { body: *body, longitude: pos.longitude, house: into.houses.house_of(pos.longitude) })
+        .collect()
+}
+
+/// Inter-aspects and house overlays. Both charts should be in the same
+/// zodiac.
+pub fn synastry(a: &Chart, b: &Chart, config: &AspectConfig) -> Synastry {
+    Synastry {
+        aspects: find_aspects_between(&a.positions, &b.positions, config),
+        a_in_b: overlays(a, b),
+        b_in_a: overlays(b, a),
+    }
+}
+
+/// Point halfway along the great circle between two places, with the mean
+/// altitude.
+pub fn geographic_midpoint(a: GeoLocation, b: GeoLocation) -> GeoLocation {
+    let vector = |l: GeoLocation| {
+        let (lat, lon) = (l.latitude.to_radians(), l.longitude.to_radians());
+        [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+    };
+    let (va, vb) = (vector(a), vector(b));
+    let [x, y, z] = [va[0] + vb[0], va[1] + vb[1], va[2] + vb[2]];
+    // Antipodes have no unique midpoint; fall back to the mean coordinates
+    if x.hypot(y).hypot(z) < 1e-12 {
+        return GeoLocation::new((a.latitude + b.latitude) / 2.0, (a.longitude + b.longitude) / 2.0, (a.altitude + b.altitude) / 2.0);
+    }
+    GeoLocation::new(
+        z.atan2(x.hypot(y)).to_degrees(),
+        y.atan2(x).to_degrees(),
+        (a.altitude + b.altitude) / 2.0,
+    )
+}
+
+// Degrees the chart's zodiac sits behind the tropical one (its ayanamsa),
+// recovered from the MC so composites need no kernel lookup.
+fn zodiac_offset(chart: &Chart) -> f64 {
+    match chart.zodiac {
+        Zodiac::Tropical => 0.0,
+        Zodiac::Sidereal(_) => {
+            let tropical = houses_from_armc(chart.houses.armc, chart.location.latitude, mean_obliquity(chart.jd), chart.houses.system);
+            (tropical.mc - chart.houses.mc).rem_euclid(360.0)
+        }
+    }
+}
+
+/// Midpoint composite of two charts in the same zodiac. Each body common to
+/// both sits at the nearer midpoint of its two longitudes, with the other
+/// coordinates and speeds averaged. Houses are cast from the midpoint ARMC
+/// at the latitude of the geographic midpoint, so the cusps stay in order
+/// where midpointing each cusp would not. `jd` and `location` are the
+/// nominal midpoints.
+pub fn composite(a: &Chart, b: &Chart, system: HouseSystem) -> Chart {
+    let jd = (a.jd + b.jd) / 2.0;
+    let location = geographic_midpoint(a.location, b.location);
+    let positions = a.positions.iter()
+        .filter_map(|(body, pa)| b.position(*body).map(|pb| (*body, Position {
+            longitude: midpoint(pa.longitude, pb.longitude),
+            latitude: (pa.latitude + pb.latitude) / 2.0,
+            distance: (pa.distance + pb.distance) / 2.0,
+            speed_longitude: (pa.speed_longitude + pb.speed_longitude) / 2.0,
+            speed_latitude: (pa.speed_latitude + pb.speed_latitude) / 2.0,
+            speed_distance: (pa.speed_distance + pb.speed_distance) / 2.0,
+        })))
+        .collect();
+    let armc = midpoint(a.houses.armc, b.houses.armc);
+    let offset = (zodiac_offset(a) + zodiac_offset(b)) / 2.0;
+    let houses = houses_from_armc(armc, location.latitude, mean_obliquity(jd), system).shifted(offset);
+    Chart { jd, location, zodiac: a.zodiac, positions, houses }
+}
+
+impl Kernel {
+    /// Davison relationship chart: an ordinary chart for the midpoint in
+    /// time of two births and the geographic midpoint of their places. The
+    /// midpoint moment must be inside this kernel.
+    pub fn davison(&self, a: (f64, GeoLocation), b: (f64, GeoLocation), system: HouseSystem,
+                   zodiac: Zodiac) -> Result<Chart, KernelError> {
+        self.chart((a.0 + b.0) / 2.0, geographic_midpoint(a.1, b.1), system, zodiac)
+    }
+
+    /// Natal charts for two births (JD and place) with their synastry,
+    /// composite and Davison charts.
+    pub fn relationship(&self, a: (f64, GeoLocation), b: (f64, GeoLocation), system: HouseSystem,
+                        zodiac: Zodiac, config: &AspectConfig) -> Result<Relationship, KernelError> {
+        let chart_a = self.chart(a.0, a.1, system, zodiac)?;
+        let chart_b = self.chart(b.0, b.1, system, zodiac)?;
+        Ok(Relationship {
+            synastry: synastry(&chart_a, &chart_b, config),
+            composite: composite(&chart_a, &chart_b, system),
+            davison: self.davison(a, b, system, zodiac)?,
+            a: chart_a,
+            b: chart_b,
+        })
+    }
+}
+
+/// [`Kernel::relationship`] for births stored in different kernels (say,
+/// generations apart). The kernels must share a frame; the Davison chart
+/// comes from whichever one covers the midpoint.
+pub fn relationship_across(kernel_a: &Kernel, a: (f64, GeoLocation), kernel_b: &Kernel, b: (f64, GeoLocation),
+                           system: HouseSystem, zodiac: Zodiac, config: &AspectConfig) -> Result<Relationship, KernelError> {
+    kernel_a.require_same_frame(kernel_b)?;
+    let chart_a = kernel_a.chart(a.0, a.1, system, zodiac)?;
+    let chart_b = kernel_b.chart(b.0, b.1, system, zodiac)?;
+    let davison = kernel_a.davison(a, b, system, zodiac)
+        .or_else(|_| kernel_b.davison(a, b, system, zodiac))?;
+    Ok(Relationship {
+        synastry: synastry(&chart_a, &chart_b, config),
+        composite: composite(&chart_a, &chart_b, system),
+        davison,
+        a: chart_a,
+        b: chart_b,
+    })
+}
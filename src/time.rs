@@ -0,0 +1,65 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+pub const UNIX_EPOCH_JD: f64 = 2440587.5;
+
+pub fn jd_to_datetime(jd: f64) -> DateTime<Utc> {
+    let unix_time = (jd - UNIX_EPOCH_JD) * 86400.0;
+    Utc.timestamp_opt(unix_time.round() as i64, 0).unwrap()
+}
+
+pub fn jd_to_local<T: TimeZone>(jd: f64, tz: &T) -> DateTime<T> {
+    jd_to_datetime(jd).with_timezone(tz)
+}
+
+/// JD of local midnight starting `date` in `tz`.
+pub fn local_midnight_jd<T: TimeZone>(date: NaiveDate, tz: &T) -> Option<f64> {
+    let midnight = tz.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()?;
+    Some(datetime_to_jd(&midnight.with_timezone(&Utc)))
+}
+
+pub fn datetime_to_jd(dt: &DateTime<Utc>) -> f64 {
+    (dt.timestamp() as f64 / 86400.0) + UNIX_EPOCH_JD
+}
+
+/// Parses a JD ("2451545.0") or a UTC date/time ("2024-02-04T15:30:45",
+/// "2024-02-04 15:30:45", "2024-02-04").
+pub fn parse_jd_or_datetime(s: &str) -> Result<f64, String> {
+    if let Ok(jd) = s.parse::<f64>() {
+        // "nan" and "inf" parse as floats but are no time at all
+        return if jd.is_finite() { Ok(jd) } else { Err(format!("not a JD or date/time: {}", s)) };
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(datetime_to_jd(&dt.with_timezone(&Utc)));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, format) {
+            return Ok(datetime_to_jd(&naive.and_utc()));
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(datetime_to_jd(&date.and_hms_opt(0, 0, 0).unwrap().and_utc()));
+    }
+    Err(format!("not a JD or date/time: {}", s))
+}
+
+/// Parses a step as days: "1d", "12h", "30m", "90s", or a plain number of
+/// days ("0.5").
+pub fn parse_step(s: &str) -> Result<f64, String> {
+    let s = s.trim();
+    let (number, unit) = match s.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&s[..i], c.to_ascii_lowercase()),
+        _ => (s, 'd'),
+    };
+    let value: f64 = number.parse().map_err(|_| format!("not a step: {}", s))?;
+    let days = match unit {
+        'd' => value,
+        'h' => value / 24.0,
+        'm' => value / 1440.0,
+        's' => value / 86400.0,
+        _ => return Err(format!("unknown step unit in {} (d, h, m, s)", s)),
+    };
+    if days <= 0.0 || !days.is_finite() {
+        return Err(format!("step must be positive: {}", s));
+    }
+    Ok(days)
+}
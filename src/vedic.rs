@@ -0,0 +1,298 @@
+use crate::bodies::{SOUTH_NODE, TRUE_NODE};
+use crate::kernel::{Kernel, KernelError};
+use crate::sidereal::Ayanamsa;
+
+pub const NAKSHATRA_SPAN: f64 = 360.0 / 27.0;
+pub const PADA_SPAN: f64 = NAKSHATRA_SPAN / 4.0;
+
+pub const NAKSHATRAS: [&str; 27] = [
+    "Ashwini", "Bharani", "Krittika", "Rohini", "Mrigashira", "Ardra",
+    "Punarvasu", "Pushya", "Ashlesha", "Magha", "Purva Phalguni", "Uttara Phalguni",
+    "Hasta", "Chitra", "Swati", "Vishakha", "Anuradha", "Jyeshtha",
+    "Mula", "Purva Ashadha", "Uttara Ashadha", "Shravana", "Dhanishta", "Shatabhisha",
+    "Purva Bhadrapada", "Uttara Bhadrapada", "Revati",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Graha {
+    Sun,
+    Moon,
+    Mars,
+    Mercury,
+    Jupiter,
+    Venus,
+    Saturn,
+    Rahu,
+    Ketu,
+}
+
+impl Graha {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Graha::Sun => "Sun",
+            Graha::Moon => "Moon",
+            Graha::Mars => "Mars",
+            Graha::Mercury => "Mercury",
+            Graha::Jupiter => "Jupiter",
+            Graha::Venus => "Venus",
+            Graha::Saturn => "Saturn",
+            Graha::Rahu => "Rahu",
+            Graha::Ketu => "Ketu",
+        }
+    }
+}
+
+// Nakshatra lords repeat in this order from Ashwini.
+pub const NAKSHATRA_LORDS: [Graha; 9] = [
+    Graha::Ketu, Graha::Venus, Graha::Sun, Graha::Moon, Graha::Mars,
+    Graha::Rahu, Graha::Jupiter, Graha::Saturn, Graha::Mercury,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Nakshatra {
+    /// 0 = Ashwini .. 26 = Revati
+    pub index: usize,
+    /// 1..=4
+    pub pada: u8,
+    pub lord: Graha,
+    /// Fraction of the nakshatra already traversed, 0..1
+    pub elapsed: f64,
+}
+
+impl Nakshatra {
+    pub fn from_longitude(sidereal_longitude: f64) -> Self {
+        let lon = sidereal_longitude.rem_euclid(360.0);
+        let index = ((lon / NAKSHATRA_SPAN) as usize).min(26);
+        let within = lon - index as f64 * NAKSHATRA_SPAN;
+        Self {
+            index,
+            pada: ((within / PADA_SPAN) as u8).min(3) + 1,
+            lord: NAKSHATRA_LORDS[index % 9],
+            elapsed: within / NAKSHATRA_SPAN,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        NAKSHATRAS[self.index]
+    }
+}
+
+/// The sixteen Parashari divisional charts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Varga {
+    D1,
+    D2,
+    D3,
+    D4,
+    D7,
+    D9,
+    D10,
+    D12,
+    D16,
+    D20,
+    D24,
+    D27,
+    D30,
+    D40,
+    D45,
+    D60,
+}
+
+impl Varga {
+    pub const ALL: [Varga; 16] = [
+        Varga::D1, Varga::D2, Varga::D3, Varga::D4, Varga::D7, Varga::D9,
+        Varga::D10, Varga::D12, Varga::D16, Varga::D20, Varga::D24, Varga::D27,
+        Varga::D30, Varga::D40, Varga::D45, Varga::D60,
+    ];
+
+    pub fn division(&self) -> u32 {
+        match self {
+            Varga::D1 => 1,
+            Varga::D2 => 2,
+            Varga::D3 => 3,
+            Varga::D4 => 4,
+            Varga::D7 => 7,
+            Varga::D9 => 9,
+            Varga::D10 => 10,
+            Varga::D12 => 12,
+            Varga::D16 => 16,
+            Varga::D20 => 20,
+            Varga::D24 => 24,
+            Varga::D27 => 27,
+            Varga::D30 => 30,
+            Varga::D40 => 40,
+            Varga::D45 => 45,
+            Varga::D60 => 60,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Varga::D1 => "Rasi",
+            Varga::D2 => "Hora",
+            Varga::D3 => "Drekkana",
+            Varga::D4 => "Chaturthamsa",
+            Varga::D7 => "Saptamsa",
+            Varga::D9 => "Navamsa",
+            Varga::D10 => "Dasamsa",
+            Varga::D12 => "Dwadasamsa",
+            Varga::D16 => "Shodasamsa",
+            Varga::D20 => "Vimsamsa",
+            Varga::D24 => "Chaturvimsamsa",
+            Varga::D27 => "Bhamsa",
+            Varga::D30 => "Trimsamsa",
+            Varga::D40 => "Khavedamsa",
+            Varga::D45 => "Akshavedamsa",
+            Varga::D60 => "Shashtiamsa",
+        }
+    }
+
+    /// Accepts "D9", "d9" or "9".
+    pub fn parse(s: &str) -> Option<Self> {
+        let n: u32 = s.trim_start_matches(['D', 'd']).parse().ok()?;
+        Varga::ALL.iter().copied().find(|v| v.division() == n)
+    }
+
+    /// Sign (0 = Aries) the sidereal longitude falls in for this chart.
+    pub fn sign(&self, sidereal_longitude: f64) -> usize {
+        let lon = sidereal_longitude.rem_euclid(360.0);
+        let sign = (lon / 30.0) as usize % 12;
+        let deg = lon - sign as f64 * 30.0;
+        let odd = sign.is_multiple_of(2); // Aries is the first, odd sign
+        let part = |width: f64| (deg / width) as usize;
+        // Movable / fixed / dual start points, used by D16, D20 and D45
+        let by_quality = |movable: usize, fixed: usize, dual: usize| match sign % 3 {
+            0 => movable,
+            1 => fixed,
+            _ => dual,
+        };
+
+        let result = match self {
+            Varga::D1 => sign,
+            Varga::D2 => match (odd, deg < 15.0) {
+                (true, true) | (false, false) => 4,
+                _ => 3,
+            },
+            Varga::D3 => sign + 4 * part(10.0),
+            Varga::D4 => sign + 3 * part(7.5),
+            Varga::D7 => (if odd { sign } else { sign + 6 }) + part(30.0 / 7.0),
+            Varga::D9 => (lon / (30.0 / 9.0)) as usize,
+            Varga::D10 => (if odd { sign } else { sign + 8 }) + part(3.0),
+            Varga::D12 => sign + part(2.5),
+            Varga::D16 => by_quality(0, 4, 8) + part(30.0 / 16.0),
+            Varga::D20 => by_quality(0, 8, 4) + part(1.5),
+            Varga::D24 => (if odd { 4 } else { 3 }) + part(1.25),
+            Varga::D27 => (lon / (30.0 / 27.0)) as usize,
+            Varga::D30 => trimsamsa(odd, deg),
+            Varga::D40 => (if odd { 0 } else { 6 }) + part(0.75),
+            Varga::D45 => by_quality(0, 4, 8) + part(30.0 / 45.0),
+            Varga::D60 => sign + part(0.5),
+        };
+        result % 12
+    }
+}
+
+// Parashari trimsamsa uses unequal portions ruled by the five star planets.
+fn trimsamsa(odd: bool, deg: f64) -> usize {
+    if odd {
+        match deg {
+            d if d < 5.0 => 0,   // Mars: Aries
+            d if d < 10.0 => 10, // Saturn: Aquarius
+            d if d < 18.0 => 8,  // Jupiter: Sagittarius
+            d if d < 25.0 => 2,  // Mercury: Gemini
+            _ => 6,              // Venus: Libra
+        }
+    } else {
+        match deg {
+            d if d < 5.0 => 1,   // Venus: Taurus
+            d if d < 12.0 => 5,  // Mercury: Virgo
+            d if d < 20.0 => 11, // Jupiter: Pisces
+            d if d < 25.0 => 9,  // Saturn: Capricorn
+            _ => 7,              // Mars: Scorpio
+        }
+    }
+}
+
+impl Kernel {
+    pub fn nakshatra_at(&self, body: i32, jd: f64, mode: Ayanamsa) -> Result<Nakshatra, KernelError> {
+        Ok(Nakshatra::from_longitude(self.sidereal_position_at(body, jd, mode)?.longitude))
+    }
+
+    pub fn varga_sign_at(&self, body: i32, jd: f64, mode: Ayanamsa, varga: Varga) -> Result<usize, KernelError> {
+        Ok(varga.sign(self.sidereal_position_at(body, jd, mode)?.longitude))
+    }
+
+    /// Divisional chart for every stored body plus Ketu, as (body, sign).
+    pub fn varga_chart(&self, jd: f64, mode: Ayanamsa, varga: Varga) -> Result<Vec<(i32, usize)>, KernelError> {
+        let mut bodies = self.bodies().to_vec();
+        if bodies.contains(&TRUE_NODE) {
+            bodies.push(SOUTH_NODE);
+        }
+        bodies.into_iter()
+            .map(|body| Ok((body, self.varga_sign_at(body, jd, mode, varga)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sidereal longitude of `degree` into sign `sign` (0 = Aries).
+    fn at(sign: usize, degree: f64) -> f64 {
+        sign as f64 * 30.0 + degree
+    }
+
+    #[test]
+    fn nakshatra_and_pada() {
+        let ashwini = Nakshatra::from_longitude(0.0);
+        assert_eq!((ashwini.name(), ashwini.pada, ashwini.lord), ("Ashwini", 1, Graha::Ketu));
+
+        let bharani = Nakshatra::from_longitude(NAKSHATRA_SPAN + 3.5);
+        assert_eq!((bharani.index, bharani.pada, bharani.lord), (1, 2, Graha::Venus));
+
+        let revati = Nakshatra::from_longitude(-0.01);
+        assert_eq!((revati.name(), revati.pada, revati.lord), ("Revati", 4, Graha::Mercury));
+        assert!(revati.elapsed > 0.99 && revati.elapsed < 1.0);
+    }
+
+    #[test]
+    fn navamsa_starts_from_the_element() {
+        // Fire from Aries, earth from Capricorn, air from Libra, water from Cancer
+        assert_eq!(Varga::D9.sign(at(0, 1.0)), 0);
+        assert_eq!(Varga::D9.sign(at(1, 1.0)), 9);
+        assert_eq!(Varga::D9.sign(at(2, 1.0)), 6);
+        assert_eq!(Varga::D9.sign(at(3, 1.0)), 3);
+        // Last navamsa of Pisces is Pisces
+        assert_eq!(Varga::D9.sign(at(11, 29.9)), 11);
+    }
+
+    #[test]
+    fn parashari_divisions() {
+        // Hora: odd signs Sun then Moon, even signs Moon then Sun
+        assert_eq!((Varga::D2.sign(at(0, 10.0)), Varga::D2.sign(at(0, 20.0))), (4, 3));
+        assert_eq!((Varga::D2.sign(at(1, 10.0)), Varga::D2.sign(at(1, 20.0))), (3, 4));
+        // Drekkana: the sign, its 5th, its 9th
+        assert_eq!([5.0, 15.0, 25.0].map(|d| Varga::D3.sign(at(0, d))), [0, 4, 8]);
+        // Dasamsa: even signs count from the 9th
+        assert_eq!(Varga::D10.sign(at(1, 0.5)), 9);
+        // Dwadasamsa: the last part is the sign before
+        assert_eq!(Varga::D12.sign(at(2, 29.0)), 1);
+        // Trimsamsa: Mars, Saturn, Jupiter, Mercury, Venus in odd signs, reversed in even
+        assert_eq!([2.0, 7.0, 15.0, 22.0, 28.0].map(|d| Varga::D30.sign(at(0, d))), [0, 10, 8, 2, 6]);
+        assert_eq!([2.0, 7.0, 15.0, 22.0, 28.0].map(|d| Varga::D30.sign(at(1, d))), [1, 5, 11, 9, 7]);
+        // D1 is the sign itself, and every chart wraps into 0..12
+        for lon in (0..720).map(|i| i as f64 * 0.5) {
+            assert_eq!(Varga::D1.sign(lon), (lon / 30.0) as usize);
+            assert!(Varga::ALL.iter().all(|v| v.sign(lon) < 12));
+        }
+    }
+
+    #[test]
+    fn parses_varga_names() {
+        assert_eq!(Varga::parse("D9"), Some(Varga::D9));
+        assert_eq!(Varga::parse("d60"), Some(Varga::D60));
+        assert_eq!(Varga::parse("27"), Some(Varga::D27));
+        assert_eq!(Varga::parse("D5"), None);
+    }
+}
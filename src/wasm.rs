@@ -0,0 +1,97 @@
+use wasm_bindgen::prelude::*;
+use crate::coords::GeoLocation;
+use crate::houses::HouseSystem;
+use crate::json::chart_json;
+use crate::kernel::{Kernel, KernelError};
+use crate::sidereal::{Ayanamsa, Zodiac};
+
+// Kernel reader for the browser (the `wasm` feature). Reading needs no
+// Swiss Ephemeris, so the page fetches a kernel and charts it locally:
+//
+//     import init, { Kernel } from "./pkg/medusa.js";
+//     await init();
+//     const kernel = new Kernel(new Uint8Array(await (await fetch("zenith.kernel")).arrayBuffer()));
+//     const [lon, lat, dist] = kernel.positionAt(0, 2460400.5);
+//
+// Positions come back as flat Float64Arrays to avoid an object per body.
+// `zodiac` is "tropical" (or undefined) or an ayanamsa stored in the kernel.
+
+fn js_error(e: KernelError) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+fn parse_zodiac(zodiac: Option<String>) -> Result<Zodiac, JsError> {
+    match zodiac.as_deref() {
+        None | Some("tropical") => Ok(Zodiac::Tropical),
+        Some(mode) => Ok(Zodiac::Sidereal(Ayanamsa::parse(mode).map_err(|e| JsError::new(&e))?)),
+    }
+}
+
+#[wasm_bindgen(js_name = Kernel)]
+pub struct WasmKernel(Kernel);
+
+#[wasm_bindgen(js_class = Kernel)]
+impl WasmKernel {
+    /// Takes a copy of the kernel bytes.
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8]) -> Result<WasmKernel, JsError> {
+        Kernel::from_bytes(bytes.to_vec()).map(WasmKernel).map_err(js_error)
+    }
+
+    #[wasm_bindgen(getter, js_name = startJd)]
+    pub fn start_jd(&self) -> f64 {
+        self.0.header().start_jd
+    }
+
+    #[wasm_bindgen(getter, js_name = endJd)]
+    pub fn end_jd(&self) -> f64 {
+        self.0.header().end_jd()
+    }
+
+    /// Swiss Ephemeris body numbers, in the order `positions` uses.
+    #[wasm_bindgen(getter)]
+    pub fn bodies(&self) -> Vec<i32> {
+        self.0.bodies().to_vec()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn frame(&self) -> String {
+        self.0.frame().to_string()
+    }
+
+    /// [longitude, latitude, distance, speed longitude, speed latitude,
+    /// speed distance] of `body` at `jd`.
+    #[wasm_bindgen(js_name = positionAt)]
+    pub fn position_at(&self, body: i32, jd: f64, zodiac: Option<String>) -> Result<Vec<f64>, JsError> {
+        let pos = self.0.position_in(body, jd, parse_zodiac(zodiac)?).map_err(js_error)?;
+        Ok(pos.values().to_vec())
+    }
+
+    /// Six values per body, as in `positionAt`, for every body in `bodies`.
+    pub fn positions(&self, jd: f64, zodiac: Option<String>) -> Result<Vec<f64>, JsError> {
+        let positions = self.0.positions_in(jd, parse_zodiac(zodiac)?).map_err(js_error)?;
+        Ok(positions.iter().flat_map(|(_, pos)| pos.values()).collect())
+    }
+
+    /// The twelve cusps followed by the Ascendant, MC and ARMC. `system` is
+    /// a house system letter or name.
+    pub fn houses(&self, jd: f64, latitude: f64, longitude: f64, system: &str,
+                  zodiac: Option<String>) -> Result<Vec<f64>, JsError> {
+        let system = HouseSystem::parse(system).map_err(|e| JsError::new(&e))?;
+        let location = GeoLocation::new(latitude, longitude, 0.0);
+        let houses = self.0.houses_in(jd, location, system, parse_zodiac(zodiac)?).map_err(js_error)?;
+        let mut out = houses.cusps.to_vec();
+        out.extend([houses.ascendant, houses.mc, houses.armc]);
+        Ok(out)
+    }
+
+    /// The chart as the JSON string the server's /chart endpoint returns.
+    #[wasm_bindgen(js_name = chartJson)]
+    pub fn chart_json(&self, jd: f64, latitude: f64, longitude: f64, system: &str,
+                      zodiac: Option<String>) -> Result<String, JsError> {
+        let system = HouseSystem::parse(system).map_err(|e| JsError::new(&e))?;
+        let location = GeoLocation::new(latitude, longitude, 0.0);
+        let chart = self.0.chart(jd, location, system, parse_zodiac(zodiac)?).map_err(js_error)?;
+        Ok(chart_json(&chart).to_string())
+    }
+}
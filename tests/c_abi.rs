@@ -0,0 +1,65 @@
+// Compiles examples/c/harness.c against the static library from this build
+// and runs it on a small kernel written with KernelWriter, the same one the
+// c_fixture example writes for `make -C examples/c`:
+//
+//     cargo test --test c_abi
+//
+// Skipped when no C compiler is found ($CC, else cc).
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use medusa::kernel::{KernelWriter, Position};
+
+fn write_fixture(path: &Path) {
+    let mut writer = KernelWriter::new(2451545.0, 1.0, &[0]);
+    for day in 0..30 {
+        writer.push_record(&[Position {
+            longitude: 280.0 + day as f64,
+            distance: 0.98,
+            speed_longitude: 1.0,
+            ..Position::default()
+        }]);
+    }
+    writer.write(path).expect("write fixture kernel");
+}
+
+// `cargo test` builds every crate type of the lib but only copies the rlib
+// up to target/<profile>, so take the static library from deps/ next to
+// this test's executable
+fn static_library() -> PathBuf {
+    let exe = std::env::current_exe().expect("test executable path");
+    exe.parent().expect("deps directory").join("libmedusa.a")
+}
+
+#[test]
+fn c_harness_passes() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    if Command::new(&cc).arg("--version").output().is_err() {
+        eprintln!("skipping: no C compiler ({})", cc);
+        return;
+    }
+    let library = static_library();
+    assert!(library.exists(), "{} was not built", library.display());
+
+    let kernel = out.join("c_abi.kernel");
+    write_fixture(&kernel);
+    let harness = out.join("c_abi_harness");
+    let status = Command::new(&cc)
+        .args(["-std=c99", "-Wall", "-Wextra", "-O2", "-I"])
+        .arg(root.join("include"))
+        .arg(root.join("examples/c/harness.c"))
+        .arg(&library)
+        .args(["-lswe", "-lm", "-lpthread", "-ldl", "-o"])
+        .arg(&harness)
+        .status()
+        .expect("run the C compiler");
+    assert!(status.success(), "harness.c did not build");
+
+    let output = Command::new(&harness).arg(&kernel).output().expect("run the harness");
+    print!("{}", String::from_utf8_lossy(&output.stdout));
+    assert!(output.status.success(), "harness failed:\n{}", String::from_utf8_lossy(&output.stderr));
+}
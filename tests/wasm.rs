@@ -0,0 +1,96 @@
+// The browser reader under a local wasm runtime (Node.js):
+//
+//     wasm-pack test --node -- --no-default-features --features wasm
+//
+// Kernels are built in memory, so no Swiss Ephemeris or files are needed.
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use medusa::kernel::{Kernel, KernelWriter, Position};
+use medusa::wasm::WasmKernel;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const START: f64 = 2451545.0;
+
+// Ten daily records: the Sun from 280° and the Moon from 350° at constant
+// speeds, so interpolation is exact and the Moon wraps through 0°.
+fn kernel_bytes() -> Vec<u8> {
+    let mut writer = KernelWriter::new(START, 1.0, &[0, 1]);
+    for day in 0..10 {
+        let day = day as f64;
+        writer.push_record(&[
+            Position { longitude: 280.0 + day, distance: 0.98, speed_longitude: 1.0, ..Position::default() },
+            Position { longitude: (350.0 + 13.0 * day) % 360.0, latitude: 5.0, distance: 0.0026, speed_longitude: 13.0, ..Position::default() },
+        ]);
+    }
+    writer.to_bytes()
+}
+
+fn kernel() -> WasmKernel {
+    match WasmKernel::new(&kernel_bytes()) {
+        Ok(kernel) => kernel,
+        Err(_) => panic!("kernel bytes did not parse"),
+    }
+}
+
+#[wasm_bindgen_test]
+fn reads_the_header() {
+    let kernel = kernel();
+    assert_eq!(kernel.start_jd(), START);
+    assert_eq!(kernel.end_jd(), START + 9.0);
+    assert_eq!(kernel.bodies(), vec![0, 1]);
+    assert!(WasmKernel::new(b"not a kernel").is_err());
+}
+
+#[wasm_bindgen_test]
+fn interpolates_positions() {
+    let kernel = kernel();
+    let Ok(sun) = kernel.position_at(0, START + 2.5, None) else { panic!("sun out of range") };
+    assert_eq!(sun.len(), 6);
+    assert!((sun[0] - 282.5).abs() < 1e-9);
+    assert!((sun[3] - 1.0).abs() < 1e-9);
+
+    // Across 0°: 350° + 13° × 0.5
+    let Ok(moon) = kernel.position_at(1, START + 0.5, None) else { panic!("moon out of range") };
+    assert!((moon[0] - 356.5).abs() < 1e-9);
+
+    let Ok(all) = kernel.positions(START + 2.5, None) else { panic!("positions out of range") };
+    assert_eq!(all.len(), 12);
+    assert_eq!(&all[..6], &sun[..]);
+}
+
+#[wasm_bindgen_test]
+fn matches_the_native_reader() {
+    let native = Kernel::from_bytes(kernel_bytes()).unwrap();
+    let kernel = kernel();
+    for jd in [START, START + 0.3, START + 4.75, START + 9.0] {
+        for body in [0, 1] {
+            let Ok(wasm) = kernel.position_at(body, jd, Some("tropical".to_string())) else { panic!("out of range") };
+            let pos = native.position_at(body, jd).unwrap();
+            assert_eq!(wasm, vec![pos.longitude, pos.latitude, pos.distance,
+                                  pos.speed_longitude, pos.speed_latitude, pos.speed_distance]);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn reports_errors() {
+    let kernel = kernel();
+    assert!(kernel.position_at(0, START + 100.0, None).is_err());
+    assert!(kernel.position_at(4, START, None).is_err());
+    assert!(kernel.houses(START, 51.5, 0.0, "Z", None).is_err());
+    // No AYAN section in this kernel
+    assert!(kernel.position_at(0, START, Some("lahiri".to_string())).is_err());
+}
+
+#[wasm_bindgen_test]
+fn casts_houses_and_charts() {
+    let kernel = kernel();
+    let Ok(houses) = kernel.houses(START + 1.0, 51.4769, 0.0, "P", None) else { panic!("bad house system") };
+    assert_eq!(houses.len(), 15);
+    assert!((houses[0] - houses[12]).abs() < 1e-9);
+    assert!((houses[9] - houses[13]).abs() < 1e-9);
+
+    let Ok(json) = kernel.chart_json(START + 1.0, 51.4769, 0.0, "W", None) else { panic!("chart failed") };
+    assert!(json.starts_with("{\"jd\":2451546,"));
+    assert!(json.contains("\"system\":\"Whole Sign\""));
+}